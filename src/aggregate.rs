@@ -0,0 +1,375 @@
+//! Workout statistics bounded to arbitrary date ranges (`ta compare`, `ta stats --since`).
+//!
+//! The library only aggregates exercise stats over all history and volume per day, so
+//! these totals are computed here from listed workouts. Workouts are bounded by the
+//! local date they were logged on, and individually logged sets take precedence over
+//! the workout row as everywhere else.
+
+use crate::sets::{self, SetEntry};
+use crate::strength::{self, E1rmFormula};
+use chrono::{Local, NaiveDate};
+use std::collections::{BTreeMap, BTreeSet};
+use task_athlete_lib::{ExerciseStats, ExerciseType, PersonalBests, Units, Workout, KM_TO_MILE};
+
+/// Totals of the workouts in one date range.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub training_days: usize,
+    pub workouts: usize,
+    /// Volume of non-cardio workouts in the configured weight unit.
+    pub volume: f64,
+    pub best_weight: Option<f64>,
+    pub best_reps: Option<i64>,
+    pub best_e1rm: Option<f64>,
+    pub distance_km: f64,
+    /// Average pace over workouts with both a duration and a distance.
+    pub pace_seconds_per_km: Option<f64>,
+}
+
+/// Whether a workout was logged within `range`, both ends included.
+pub fn in_range(workout: &Workout, range: (NaiveDate, NaiveDate)) -> bool {
+    let date = workout.timestamp.with_timezone(&Local).date_naive();
+    (range.0..=range.1).contains(&date)
+}
+
+/// Totals of the workouts in `range`.
+pub fn summarize(
+    workouts: &[Workout],
+    range: (NaiveDate, NaiveDate),
+    logged: &BTreeMap<i64, Vec<SetEntry>>,
+    formula: E1rmFormula,
+) -> Summary {
+    let workouts: Vec<Workout> = workouts
+        .iter()
+        .filter(|w| in_range(w, range))
+        .cloned()
+        .collect();
+    let mut summary = Summary {
+        training_days: workouts
+            .iter()
+            .map(|w| w.timestamp.with_timezone(&Local).date_naive())
+            .collect::<BTreeSet<_>>()
+            .len(),
+        workouts: workouts.len(),
+        best_e1rm: strength::best_e1rm(&workouts, logged, formula),
+        ..Summary::default()
+    };
+    let (mut paced_minutes, mut paced_km) = (0, 0.0);
+    for workout in &workouts {
+        let logged = logged.get(&workout.id);
+        if workout.exercise_type != Some(ExerciseType::Cardio) {
+            summary.volume += sets::workout_volume(workout, logged);
+        }
+        for set in sets::sets_for_workout(workout, logged) {
+            summary.best_reps = summary.best_reps.max(Some(set.reps));
+            if let Some(weight) = set.weight {
+                summary.best_weight = Some(summary.best_weight.map_or(weight, |b| b.max(weight)));
+            }
+        }
+        let distance = workout.distance.unwrap_or(0.0);
+        summary.distance_km += distance;
+        if let Some(minutes) = workout.duration_minutes.filter(|_| distance > 0.0) {
+            paced_minutes += minutes;
+            paced_km += distance;
+        }
+    }
+    summary.pace_seconds_per_km =
+        (paced_minutes > 0).then(|| paced_minutes as f64 * 60.0 / paced_km);
+    summary
+}
+
+/// [`ExerciseStats`] of one exercise's `workouts`. Consecutive training days at most
+/// `streak_interval_days` apart form a streak, which is current if its last day is
+/// within the interval of `today`. Weight and reps bests come from the `logged` sets.
+pub fn exercise_stats(
+    canonical_name: &str,
+    workouts: &[Workout],
+    logged: &BTreeMap<i64, Vec<SetEntry>>,
+    streak_interval_days: u32,
+    today: NaiveDate,
+) -> ExerciseStats {
+    let dates: Vec<NaiveDate> = workouts
+        .iter()
+        .map(|w| w.timestamp.with_timezone(&Local).date_naive())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let (first, last) = (dates.first().copied(), dates.last().copied());
+    let gaps: Vec<i64> = dates.windows(2).map(|d| (d[1] - d[0]).num_days()).collect();
+
+    let interval = i64::from(streak_interval_days);
+    let mut streak = u32::from(!dates.is_empty());
+    let mut longest_streak = streak;
+    for gap in &gaps {
+        streak = if *gap <= interval { streak + 1 } else { 1 };
+        longest_streak = longest_streak.max(streak);
+    }
+    let current_streak = match last {
+        Some(last) if (today - last).num_days() <= interval => streak,
+        _ => 0,
+    };
+
+    let max_f64 = |values: &mut dyn Iterator<Item = f64>| values.reduce(f64::max);
+    let sets: Vec<SetEntry> = workouts
+        .iter()
+        .flat_map(|w| sets::sets_for_workout(w, logged.get(&w.id)))
+        .collect();
+    ExerciseStats {
+        canonical_name: canonical_name.to_string(),
+        total_workouts: workouts.len(),
+        first_workout_date: first,
+        last_workout_date: last,
+        avg_workouts_per_week: first.zip(last).map(|(first, last)| {
+            let weeks = ((last - first).num_days() + 1) as f64 / 7.0;
+            workouts.len() as f64 / weeks.max(1.0)
+        }),
+        longest_gap_days: gaps.iter().max().map(|gap| *gap as u64),
+        personal_bests: PersonalBests {
+            max_weight: max_f64(&mut sets.iter().filter_map(|set| set.weight)),
+            max_reps: sets.iter().map(|set| set.reps).max(),
+            max_duration_minutes: workouts.iter().filter_map(|w| w.duration_minutes).max(),
+            max_distance_km: max_f64(&mut workouts.iter().filter_map(|w| w.distance)),
+        },
+        current_streak,
+        longest_streak,
+        streak_interval_days,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareMetric {
+    TrainingDays,
+    Workouts,
+    Volume,
+    BestWeight,
+    BestReps,
+    BestE1rm,
+    Distance,
+    /// Seconds per km or mile.
+    Pace,
+}
+
+impl CompareMetric {
+    /// Stable name used in JSON output.
+    pub fn key(self) -> &'static str {
+        match self {
+            CompareMetric::TrainingDays => "training_days",
+            CompareMetric::Workouts => "workouts",
+            CompareMetric::Volume => "volume",
+            CompareMetric::BestWeight => "best_weight",
+            CompareMetric::BestReps => "best_reps",
+            CompareMetric::BestE1rm => "best_e1rm",
+            CompareMetric::Distance => "distance",
+            CompareMetric::Pace => "pace_seconds",
+        }
+    }
+
+    pub fn label(self, units: Units) -> String {
+        match self {
+            CompareMetric::TrainingDays => "Training days".to_string(),
+            CompareMetric::Workouts => "Workouts".to_string(),
+            CompareMetric::Volume => format!("Volume ({})", units.weight_abbr()),
+            CompareMetric::BestWeight => format!("Best weight ({})", units.weight_abbr()),
+            CompareMetric::BestReps => "Best reps".to_string(),
+            CompareMetric::BestE1rm => format!("Best e1RM ({})", units.weight_abbr()),
+            CompareMetric::Distance => format!("Distance ({})", units.distance_abbr()),
+            CompareMetric::Pace => format!("Avg pace (/{})", units.distance_abbr()),
+        }
+    }
+}
+
+/// One metric of both ranges, in display units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompareRow {
+    pub metric: CompareMetric,
+    pub first: Option<f64>,
+    pub second: Option<f64>,
+}
+
+impl CompareRow {
+    /// Second minus first, if both have a value.
+    pub fn change(&self) -> Option<f64> {
+        Some(self.second? - self.first?)
+    }
+
+    pub fn change_pct(&self) -> Option<f64> {
+        let first = self.first.filter(|f| *f != 0.0)?;
+        Some(self.change()? / first * 100.0)
+    }
+}
+
+/// Side-by-side rows of two summaries, converting distances and paces to `units`.
+pub fn compare(first: &Summary, second: &Summary, units: Units) -> Vec<CompareRow> {
+    let per_unit = match units {
+        Units::Metric => 1.0,
+        Units::Imperial => KM_TO_MILE,
+    };
+    let value = |summary: &Summary, metric: CompareMetric| match metric {
+        CompareMetric::TrainingDays => Some(summary.training_days as f64),
+        CompareMetric::Workouts => Some(summary.workouts as f64),
+        CompareMetric::Volume => Some(summary.volume),
+        CompareMetric::BestWeight => summary.best_weight,
+        CompareMetric::BestReps => summary.best_reps.map(|r| r as f64),
+        CompareMetric::BestE1rm => summary.best_e1rm,
+        CompareMetric::Distance => Some(summary.distance_km * per_unit),
+        CompareMetric::Pace => summary.pace_seconds_per_km.map(|p| p / per_unit),
+    };
+    [
+        CompareMetric::TrainingDays,
+        CompareMetric::Workouts,
+        CompareMetric::Volume,
+        CompareMetric::BestWeight,
+        CompareMetric::BestReps,
+        CompareMetric::BestE1rm,
+        CompareMetric::Distance,
+        CompareMetric::Pace,
+    ]
+    .into_iter()
+    .map(|metric| CompareRow {
+        metric,
+        first: value(first, metric),
+        second: value(second, metric),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn workout(id: i64, day: u32, exercise_type: ExerciseType) -> Workout {
+        let timestamp = Local
+            .with_ymd_and_hms(2026, 1, day, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        Workout {
+            id,
+            timestamp,
+            exercise_name: "Test".to_string(),
+            sets: None,
+            reps: None,
+            weight: None,
+            duration_minutes: None,
+            distance: None,
+            notes: None,
+            exercise_type: Some(exercise_type),
+            bodyweight: None,
+        }
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, day).unwrap()
+    }
+
+    #[test]
+    fn test_summarize_within_range() {
+        let lift = |id, day, weight| Workout {
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(weight),
+            ..workout(id, day, ExerciseType::Resistance)
+        };
+        let run = |id, day, minutes, km| Workout {
+            duration_minutes: Some(minutes),
+            distance: Some(km),
+            ..workout(id, day, ExerciseType::Cardio)
+        };
+        let workouts = vec![
+            lift(1, 5, 100.0),
+            lift(2, 5, 80.0),
+            run(3, 6, 30, 6.0),
+            run(4, 7, 25, 4.0),
+            lift(5, 20, 120.0),
+        ];
+        let logged = BTreeMap::from([(
+            2,
+            vec![SetEntry {
+                weight: Some(80.0),
+                reps: 12,
+                rpe: None,
+            }],
+        )]);
+        let summary = summarize(&workouts, (day(1), day(10)), &logged, E1rmFormula::Epley);
+        assert_eq!(summary.training_days, 3);
+        assert_eq!(summary.workouts, 4);
+        assert_eq!(summary.volume, 1500.0 + 960.0);
+        assert_eq!(summary.best_weight, Some(100.0));
+        assert_eq!(summary.best_reps, Some(12));
+        assert_eq!(summary.distance_km, 10.0);
+        assert_eq!(summary.pace_seconds_per_km, Some(330.0));
+    }
+
+    #[test]
+    fn test_exercise_stats() {
+        let lift = |id, day, weight, reps| Workout {
+            reps: Some(reps),
+            weight: Some(weight),
+            ..workout(id, day, ExerciseType::Resistance)
+        };
+        let workouts = vec![
+            lift(1, 1, 100.0, 5),
+            lift(2, 2, 105.0, 3),
+            lift(3, 2, 80.0, 10),
+            lift(4, 3, 100.0, 6),
+            lift(5, 8, 110.0, 2),
+            lift(6, 9, 90.0, 8),
+        ];
+        let stats = exercise_stats("Bench", &workouts, &BTreeMap::new(), 1, day(10));
+        assert_eq!(stats.total_workouts, 6);
+        assert_eq!(stats.first_workout_date, Some(day(1)));
+        assert_eq!(stats.last_workout_date, Some(day(9)));
+        assert_eq!(stats.avg_workouts_per_week, Some(6.0 / (9.0 / 7.0)));
+        assert_eq!(stats.longest_gap_days, Some(5));
+        assert_eq!((stats.current_streak, stats.longest_streak), (2, 3));
+        assert_eq!(stats.personal_bests.max_weight, Some(110.0));
+        assert_eq!(stats.personal_bests.max_reps, Some(10));
+        assert_eq!(stats.personal_bests.max_distance_km, None);
+
+        let stale = exercise_stats("Bench", &workouts, &BTreeMap::new(), 1, day(15));
+        assert_eq!(stale.current_streak, 0);
+    }
+
+    #[test]
+    fn test_exercise_stats_bests_from_logged_sets() {
+        // The row holds the top set (100x6); the lighter set has more reps
+        let workouts = vec![Workout {
+            sets: Some(2),
+            reps: Some(6),
+            weight: Some(100.0),
+            ..workout(1, 1, ExerciseType::Resistance)
+        }];
+        let set = |weight, reps| SetEntry {
+            weight: Some(weight),
+            reps,
+            rpe: None,
+        };
+        let logged = BTreeMap::from([(1, vec![set(100.0, 6), set(60.0, 12)])]);
+        let stats = exercise_stats("Bench", &workouts, &logged, 1, day(1));
+        assert_eq!(stats.personal_bests.max_weight, Some(100.0));
+        assert_eq!(stats.personal_bests.max_reps, Some(12));
+    }
+
+    #[test]
+    fn test_compare_rows() {
+        let first = Summary {
+            workouts: 4,
+            distance_km: 10.0,
+            ..Summary::default()
+        };
+        let second = Summary {
+            workouts: 5,
+            distance_km: 12.0,
+            best_weight: Some(100.0),
+            ..Summary::default()
+        };
+        let rows = compare(&first, &second, Units::Metric);
+        let row = |metric| rows.iter().find(|r| r.metric == metric).unwrap();
+        assert_eq!(row(CompareMetric::Workouts).change(), Some(1.0));
+        assert_eq!(row(CompareMetric::Workouts).change_pct(), Some(25.0));
+        assert_eq!(row(CompareMetric::BestWeight).change(), None);
+        assert_eq!(row(CompareMetric::TrainingDays).change_pct(), None);
+        let imperial = compare(&first, &second, Units::Imperial);
+        assert_eq!(imperial[6].first, Some(10.0 * KM_TO_MILE));
+    }
+}
@@ -0,0 +1,275 @@
+//! Backups of the database, the config file and the CLI's own data (`ta backup`,
+//! `ta restore`).
+//!
+//! A backup is a gzipped tar archive. The database is copied with SQLite's online
+//! backup API, so the copy is consistent even while another `ta` is writing. Risky
+//! commands (imports, applying sync changes, deleting exercises, restoring) take an
+//! automatic backup first; only the newest [`AUTO_BACKUPS_KEPT`] of those are kept.
+
+use crate::journal::JOURNAL_FILE_NAME;
+use crate::store::STORE_FILE_NAME;
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use task_athlete_lib::AppService;
+
+/// Number of automatic backups kept; older ones are deleted when a new one is taken.
+pub const AUTO_BACKUPS_KEPT: usize = 5;
+
+const BACKUP_DIR_NAME: &str = "backups";
+const MANUAL_PREFIX: &str = "ta-backup-";
+const AUTO_PREFIX: &str = "ta-auto-";
+const ARCHIVE_EXTENSION: &str = ".tar.gz";
+
+// Names of the files inside an archive
+const DATABASE_ENTRY: &str = "database.sqlite";
+const CONFIG_ENTRY: &str = "config.toml";
+const MANIFEST_ENTRY: &str = "manifest.json";
+const CLI_FILES: [&str; 2] = [STORE_FILE_NAME, JOURNAL_FILE_NAME];
+
+/// Directory for backups when `--to` is not given: `backups/` next to the config file.
+pub fn default_backup_dir(service: &AppService) -> PathBuf {
+    service.get_config_path().with_file_name(BACKUP_DIR_NAME)
+}
+
+/// A directory under the system temp dir that is removed when dropped.
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn new() -> Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "ta-{}-{}",
+            std::process::id(),
+            Local::now().format("%Y%m%d%H%M%S%f")
+        ));
+        fs::create_dir_all(&path)
+            .with_context(|| format!("Failed to create temporary directory {:?}", path))?;
+        Ok(ScratchDir(path))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Writes a backup archive into `dir` and returns its path. `reason` marks an
+/// automatic backup and names the command it was taken for.
+pub fn create_backup(service: &AppService, dir: &Path, reason: Option<&str>) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {:?}", dir))?;
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    let base_name = match reason {
+        Some(reason) => format!("{AUTO_PREFIX}{stamp}-{reason}"),
+        None => format!("{MANUAL_PREFIX}{stamp}"),
+    };
+    let mut path = dir.join(format!("{base_name}{ARCHIVE_EXTENSION}"));
+    let mut counter = 2;
+    while path.exists() {
+        path = dir.join(format!("{base_name}-{counter}{ARCHIVE_EXTENSION}"));
+        counter += 1;
+    }
+
+    let scratch = ScratchDir::new()?;
+    let db_copy = scratch.0.join(DATABASE_ENTRY);
+    let source =
+        Connection::open_with_flags(service.get_db_path(), OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .with_context(|| format!("Failed to open database {:?}", service.get_db_path()))?;
+    source
+        .backup(DatabaseName::Main, &db_copy, None)
+        .context("Failed to copy the database")?;
+    let manifest = json!({
+        "created_at": Local::now().to_rfc3339(),
+        "reason": reason,
+        "ta_version": env!("CARGO_PKG_VERSION"),
+        "tables": table_counts(&db_copy)?,
+    });
+
+    let tmp_path = path.with_extension("tmp");
+    let file = File::create(&tmp_path)
+        .with_context(|| format!("Failed to create backup file {:?}", tmp_path))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let mut header = tar::Header::new_gnu();
+    let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+    header.set_size(manifest_bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_ENTRY, manifest_bytes.as_slice())?;
+    builder.append_path_with_name(&db_copy, DATABASE_ENTRY)?;
+    let config_path = service.get_config_path();
+    if config_path.exists() {
+        builder.append_path_with_name(config_path, CONFIG_ENTRY)?;
+    }
+    for name in CLI_FILES {
+        let cli_file = config_path.with_file_name(name);
+        if cli_file.exists() {
+            builder.append_path_with_name(&cli_file, name)?;
+        }
+    }
+    builder
+        .into_inner()?
+        .finish()
+        .with_context(|| format!("Failed to write backup file {:?}", tmp_path))?;
+    fs::rename(&tmp_path, &path)
+        .with_context(|| format!("Failed to move backup into place at {:?}", path))?;
+    Ok(path)
+}
+
+/// Takes an automatic backup before a risky command and deletes the oldest automatic
+/// backups beyond [`AUTO_BACKUPS_KEPT`].
+pub fn auto_backup(service: &AppService, reason: &str) -> Result<PathBuf> {
+    let dir = default_backup_dir(service);
+    let path = create_backup(service, &dir, Some(reason))
+        .with_context(|| format!("Automatic backup before {} failed", reason))?;
+
+    let mut automatic: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(AUTO_PREFIX) && n.ends_with(ARCHIVE_EXTENSION))
+        })
+        .collect();
+    // Timestamped names sort chronologically
+    automatic.sort();
+    let excess = automatic.len().saturating_sub(AUTO_BACKUPS_KEPT);
+    for old in &automatic[..excess] {
+        fs::remove_file(old).with_context(|| format!("Failed to delete old backup {:?}", old))?;
+    }
+    Ok(path)
+}
+
+/// Row counts of every table in a database, by table name.
+pub fn table_counts(db_path: &Path) -> Result<BTreeMap<String, i64>> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("Failed to open database {:?}", db_path))?;
+    let mut statement = conn.prepare(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+    )?;
+    let tables: Vec<String> = statement
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+
+    let mut counts = BTreeMap::new();
+    for table in tables {
+        let count: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\"")),
+            [],
+            |row| row.get(0),
+        )?;
+        counts.insert(table, count);
+    }
+    Ok(counts)
+}
+
+/// An unpacked backup archive, ready to be previewed and restored.
+pub struct UnpackedBackup {
+    scratch: ScratchDir,
+    /// When the backup was taken, as written in its manifest.
+    pub created_at: Option<String>,
+    /// Row counts of the archived database, by table name.
+    pub tables: BTreeMap<String, i64>,
+}
+
+impl UnpackedBackup {
+    pub fn open(archive: &Path) -> Result<Self> {
+        let scratch = ScratchDir::new()?;
+        let file =
+            File::open(archive).with_context(|| format!("Failed to open backup {:?}", archive))?;
+        tar::Archive::new(GzDecoder::new(file))
+            .unpack(&scratch.0)
+            .with_context(|| format!("Failed to unpack backup {:?}", archive))?;
+
+        let db_path = scratch.0.join(DATABASE_ENTRY);
+        if !db_path.exists() {
+            bail!(
+                "{:?} is not a ta backup (it has no {}).",
+                archive,
+                DATABASE_ENTRY
+            );
+        }
+        let manifest: Option<Value> = fs::read_to_string(scratch.0.join(MANIFEST_ENTRY))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok());
+        let created_at = manifest
+            .as_ref()
+            .and_then(|m| m["created_at"].as_str())
+            .map(str::to_string);
+        let tables = table_counts(&db_path)?;
+        Ok(UnpackedBackup {
+            scratch,
+            created_at,
+            tables,
+        })
+    }
+
+    /// Replaces the database, config and CLI data with the archived ones. CLI data
+    /// files missing from the archive are removed, as they did not exist back then.
+    pub fn restore(&self, service: &AppService) -> Result<()> {
+        let mut target = Connection::open(service.get_db_path())
+            .with_context(|| format!("Failed to open database {:?}", service.get_db_path()))?;
+        target
+            .restore(
+                DatabaseName::Main,
+                self.scratch.0.join(DATABASE_ENTRY),
+                None::<fn(rusqlite::backup::Progress)>,
+            )
+            .context("Failed to restore the database")?;
+
+        let config_path = service.get_config_path();
+        let archived_config = self.scratch.0.join(CONFIG_ENTRY);
+        if archived_config.exists() {
+            replace_file(&archived_config, config_path)?;
+        }
+        for name in CLI_FILES {
+            let archived = self.scratch.0.join(name);
+            let current = config_path.with_file_name(name);
+            if archived.exists() {
+                replace_file(&archived, &current)?;
+            } else if current.exists() {
+                fs::remove_file(&current)
+                    .with_context(|| format!("Failed to remove {:?}", current))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copies `source` over `target` via a temporary file and rename.
+fn replace_file(source: &Path, target: &Path) -> Result<()> {
+    let tmp_path = target.with_extension("restore.tmp");
+    fs::copy(source, &tmp_path).with_context(|| format!("Failed to write {:?}", tmp_path))?;
+    fs::rename(&tmp_path, target).with_context(|| format!("Failed to replace {:?}", target))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_counts() {
+        let scratch = ScratchDir::new().unwrap();
+        let db_path = scratch.0.join("test.sqlite");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE workouts (id INTEGER PRIMARY KEY);
+             CREATE TABLE aliases (alias TEXT);
+             INSERT INTO workouts (id) VALUES (1), (2), (3);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let counts = table_counts(&db_path).unwrap();
+        assert_eq!(counts.get("workouts"), Some(&3));
+        assert_eq!(counts.get("aliases"), Some(&0));
+        assert_eq!(counts.len(), 2);
+    }
+}
@@ -0,0 +1,276 @@
+//! Year-at-a-glance training calendar (`ta calendar`), rendered as a week-by-day grid.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::ValueEnum;
+use comfy_table::Color;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Glyphs for intensity levels 0 (no training) to 4, with and without color.
+const UNICODE_LEVELS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+const ASCII_LEVELS: [char; 5] = ['.', '-', '+', '*', '#'];
+const CURRENT_STREAK_MARK: char = '^';
+const LONGEST_STREAK_MARK: char = '=';
+const DAY_LABEL_WIDTH: usize = 4;
+const ANSI_RESET: &str = "\x1b[0m";
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CalendarMetric {
+    /// Number of workouts per day
+    #[default]
+    Workouts,
+    /// Total volume (sets*reps*weight) per day
+    Volume,
+}
+
+/// A run of training days where no gap exceeds the streak interval.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Streak {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Number of training days in the streak.
+    pub days: usize,
+}
+
+/// Finds the current and the longest streak among `active_days`.
+/// A streak is current if its last day is within `interval_days` of `today`.
+pub fn find_streaks(
+    active_days: &BTreeSet<NaiveDate>,
+    interval_days: u32,
+    today: NaiveDate,
+) -> (Option<Streak>, Option<Streak>) {
+    let interval = Duration::days(interval_days.max(1) as i64);
+    let mut streaks: Vec<Streak> = Vec::new();
+    for &day in active_days {
+        match streaks.last_mut() {
+            Some(streak) if day - streak.end <= interval => {
+                streak.end = day;
+                streak.days += 1;
+            }
+            _ => streaks.push(Streak {
+                start: day,
+                end: day,
+                days: 1,
+            }),
+        }
+    }
+
+    let current = streaks
+        .last()
+        .filter(|s| today >= s.end && today - s.end <= interval)
+        .copied();
+    // Ties go to the most recent streak
+    let longest = streaks.iter().rev().max_by_key(|s| s.days).copied();
+    (current, longest)
+}
+
+/// Intensity level from 0 (nothing logged) to 4 (close to the busiest day).
+pub fn intensity_level(value: f64, max: f64) -> usize {
+    if value <= 0.0 || max <= 0.0 {
+        return 0;
+    }
+    ((value / max * 4.0).ceil() as usize).clamp(1, 4)
+}
+
+/// Renders the calendar for `year`: a month header, one row per weekday and a row
+/// marking the weeks of the current and longest streak. `color` shades training days
+/// with Unicode blocks; without it plain ASCII glyphs are used.
+pub fn render_calendar(
+    year: i32,
+    values: &BTreeMap<NaiveDate, f64>,
+    current: Option<Streak>,
+    longest: Option<Streak>,
+    color: Option<Color>,
+) -> Vec<String> {
+    let (Some(first), Some(last)) = (
+        NaiveDate::from_ymd_opt(year, 1, 1),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) else {
+        return Vec::new();
+    };
+    let grid_start = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+    let weeks = ((last - grid_start).num_days() / 7 + 1) as usize;
+    let max = values.values().copied().fold(0.0, f64::max);
+    let glyphs = if color.is_some() {
+        UNICODE_LEVELS
+    } else {
+        ASCII_LEVELS
+    };
+    let color_code = color.and_then(ansi_foreground);
+
+    let mut lines = Vec::with_capacity(9);
+
+    // Month names above the week in which the month starts
+    let mut header = vec![' '; weeks + 3];
+    for month in 1..=12 {
+        let Some(month_start) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            continue;
+        };
+        let col = ((month_start - grid_start).num_days() / 7) as usize;
+        let label = month_start.format("%b").to_string();
+        // Skip a label that would run into the previous one
+        if col > 0 && header[col - 1] != ' ' {
+            continue;
+        }
+        for (offset, c) in label.chars().enumerate() {
+            header[col + offset] = c;
+        }
+    }
+    lines.push(format!(
+        "{:DAY_LABEL_WIDTH$}{}",
+        "",
+        header.into_iter().collect::<String>().trim_end()
+    ));
+
+    for weekday in 0..7 {
+        let label = match weekday {
+            0 => "Mon",
+            2 => "Wed",
+            4 => "Fri",
+            _ => "",
+        };
+        let mut row = format!("{label:DAY_LABEL_WIDTH$}");
+        for week in 0..weeks {
+            let date = grid_start + Duration::days((week * 7 + weekday) as i64);
+            if date < first || date > last {
+                row.push(' ');
+                continue;
+            }
+            let level = intensity_level(values.get(&date).copied().unwrap_or(0.0), max);
+            match (&color_code, level) {
+                (Some(code), 1..) => {
+                    row.push_str(code);
+                    row.push(glyphs[level]);
+                    row.push_str(ANSI_RESET);
+                }
+                _ => row.push(glyphs[level]),
+            }
+        }
+        lines.push(row.trim_end().to_string());
+    }
+
+    // Streak markers under the weeks they cover; the current streak wins on overlap
+    let marks: String = (0..weeks)
+        .map(|week| {
+            let week_start = grid_start + Duration::days(week as i64 * 7);
+            let in_week = |streak: Option<Streak>| {
+                streak.is_some_and(|s| {
+                    s.start <= week_start + Duration::days(6) && s.end >= week_start
+                })
+            };
+            if in_week(current) {
+                CURRENT_STREAK_MARK
+            } else if in_week(longest) {
+                LONGEST_STREAK_MARK
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    if !marks.trim().is_empty() {
+        lines.push(format!("{:DAY_LABEL_WIDTH$}{}", "", marks.trim_end()));
+    }
+    lines
+}
+
+/// Legend describing the glyph ramp and the streak markers.
+pub fn legend(color: bool) -> String {
+    let glyphs = if color { UNICODE_LEVELS } else { ASCII_LEVELS };
+    format!(
+        "Less {} More   {CURRENT_STREAK_MARK} current streak   {LONGEST_STREAK_MARK} longest streak",
+        glyphs.iter().collect::<String>()
+    )
+}
+
+/// ANSI escape sequence that sets `color` as the foreground color.
+fn ansi_foreground(color: Color) -> Option<String> {
+    let code = match color {
+        Color::Black => "30".to_string(),
+        Color::DarkRed => "31".to_string(),
+        Color::DarkGreen => "32".to_string(),
+        Color::DarkYellow => "33".to_string(),
+        Color::DarkBlue => "34".to_string(),
+        Color::DarkMagenta => "35".to_string(),
+        Color::DarkCyan => "36".to_string(),
+        Color::Grey => "37".to_string(),
+        Color::DarkGrey => "90".to_string(),
+        Color::Red => "91".to_string(),
+        Color::Green => "92".to_string(),
+        Color::Yellow => "93".to_string(),
+        Color::Blue => "94".to_string(),
+        Color::Magenta => "95".to_string(),
+        Color::Cyan => "96".to_string(),
+        Color::White => "97".to_string(),
+        Color::Rgb { r, g, b } => format!("38;2;{r};{g};{b}"),
+        Color::AnsiValue(value) => format!("38;5;{value}"),
+        Color::Reset => return None,
+    };
+    Some(format!("\x1b[{code}m"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_find_streaks() {
+        let days: BTreeSet<NaiveDate> = [
+            date(1, 1),
+            date(1, 2),
+            date(1, 3),
+            date(1, 4),
+            date(1, 10),
+            date(1, 12),
+        ]
+        .into_iter()
+        .collect();
+
+        let (current, longest) = find_streaks(&days, 1, date(1, 13));
+        assert_eq!(current.unwrap().days, 1);
+        assert_eq!(longest.unwrap().start, date(1, 1));
+        assert_eq!(longest.unwrap().days, 4);
+
+        // A 2-day interval joins Jan 10 and Jan 12
+        let (current, _) = find_streaks(&days, 2, date(1, 13));
+        assert_eq!(current.unwrap().start, date(1, 10));
+        assert_eq!(current.unwrap().days, 2);
+
+        // Nothing logged recently: no current streak
+        let (current, _) = find_streaks(&days, 1, date(2, 1));
+        assert!(current.is_none());
+    }
+
+    #[test]
+    fn test_intensity_level() {
+        assert_eq!(intensity_level(0.0, 10.0), 0);
+        assert_eq!(intensity_level(0.5, 10.0), 1);
+        assert_eq!(intensity_level(6.0, 10.0), 3);
+        assert_eq!(intensity_level(10.0, 10.0), 4);
+    }
+
+    #[test]
+    fn test_render_calendar_ascii() {
+        let values: BTreeMap<NaiveDate, f64> =
+            [(date(1, 1), 1.0), (date(1, 2), 2.0)].into_iter().collect();
+        let streak = Streak {
+            start: date(1, 1),
+            end: date(1, 2),
+            days: 2,
+        };
+        let lines = render_calendar(2025, &values, None, Some(streak), None);
+
+        // Header, 7 weekdays, streak markers
+        assert_eq!(lines.len(), 9);
+        assert!(lines[0].trim_start().starts_with("Jan"));
+        // 2025-01-01 is a Wednesday, in the first week column
+        assert!(lines[3].starts_with("Wed +."));
+        assert!(lines[4].starts_with("    #."));
+        // Monday of that week belongs to 2024 and stays blank
+        assert!(lines[1].starts_with("Mon  ."));
+        assert!(lines[8].starts_with("    ="));
+        assert!(lines.iter().all(|l| l.is_ascii()));
+    }
+}
@@ -0,0 +1,430 @@
+//! Terminal charts (`ta chart`) and sparklines.
+//!
+//! Charts plot one value per logged day. Days are spread evenly along the x axis
+//! (gaps between training days are not drawn), so the axis shows the first, middle
+//! and last date rather than a continuous timeline.
+
+use crate::sets::{self, SetEntry};
+use crate::strength::{self, E1rmFormula};
+use chrono::{Local, NaiveDate};
+use clap::ValueEnum;
+use std::collections::BTreeMap;
+use std::fmt;
+use task_athlete_lib::{Units, Workout, KM_TO_MILE};
+
+/// Block characters from 1/8 to a full cell, used for bars and sparklines.
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const POINT: char = '●';
+const AVERAGE_POINT: char = '·';
+const TARGET_LINE: char = '─';
+/// Width of a `YYYY-MM-DD` date label.
+const DATE_LABEL_WIDTH: usize = 10;
+/// Chart width used when the terminal width is unknown.
+const DEFAULT_WIDTH: usize = 80;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChartMetric {
+    /// Heaviest weight of the day
+    Weight,
+    /// Most reps in a set of the day
+    Reps,
+    /// Total volume (sets*reps*weight) of the day
+    Volume,
+    /// Best estimated 1RM of the day
+    E1rm,
+    /// Total distance of the day
+    Distance,
+    /// Total duration of the day
+    Duration,
+}
+
+impl fmt::Display for ChartMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ChartMetric::Weight => "weight",
+            ChartMetric::Reps => "reps",
+            ChartMetric::Volume => "volume",
+            ChartMetric::E1rm => "e1RM",
+            ChartMetric::Distance => "distance",
+            ChartMetric::Duration => "duration",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl ChartMetric {
+    /// Unit label for the metric's values, if it has one.
+    pub fn unit(self, units: Units) -> Option<&'static str> {
+        match self {
+            ChartMetric::Weight | ChartMetric::Volume | ChartMetric::E1rm => {
+                Some(units.weight_abbr())
+            }
+            ChartMetric::Distance => Some(units.distance_abbr()),
+            ChartMetric::Duration => Some("min"),
+            ChartMetric::Reps => None,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChartStyle {
+    /// One point per column
+    #[default]
+    Line,
+    /// Filled columns
+    Bar,
+}
+
+#[derive(Clone, Debug)]
+pub struct ChartOptions {
+    /// Total width in terminal columns, including the y-axis labels.
+    pub width: usize,
+    /// Height of the plot area in rows.
+    pub height: usize,
+    pub style: ChartStyle,
+    /// Window (in data points) of the trailing moving average to overlay.
+    pub moving_average: Option<usize>,
+    /// Value drawn as a horizontal line (e.g. target bodyweight).
+    pub target: Option<f64>,
+}
+
+/// Width of the terminal, falling back to `$COLUMNS` (e.g. when output is piped)
+/// and then to 80 columns.
+pub fn terminal_width() -> usize {
+    queried_width()
+        .or_else(|| {
+            std::env::var("COLUMNS")
+                .ok()
+                .and_then(|c| c.trim().parse::<usize>().ok())
+        })
+        .filter(|&c| c > 0)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+#[cfg(feature = "tui")]
+fn queried_width() -> Option<usize> {
+    ratatui::crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| usize::from(columns))
+}
+
+#[cfg(all(unix, not(feature = "tui")))]
+fn queried_width() -> Option<usize> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: TIOCGWINSZ only writes a `winsize` to the pointer, which outlives the call
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    (result == 0).then_some(usize::from(size.ws_col))
+}
+
+#[cfg(not(any(unix, feature = "tui")))]
+fn queried_width() -> Option<usize> {
+    None
+}
+
+/// Per-day values of `metric` for an exercise's workouts, oldest first.
+/// Days where the metric wasn't recorded are left out.
+pub fn daily_series(
+    workouts: &[Workout],
+    logged: &BTreeMap<i64, Vec<SetEntry>>,
+    metric: ChartMetric,
+    formula: E1rmFormula,
+    units: Units,
+) -> Vec<(NaiveDate, f64)> {
+    let mut days: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for workout in workouts {
+        let logged_sets = logged.get(&workout.id);
+        let value = match metric {
+            ChartMetric::Weight => workout.weight,
+            ChartMetric::Reps => workout.reps.map(|r| r as f64),
+            ChartMetric::Volume => {
+                Some(sets::workout_volume(workout, logged_sets)).filter(|volume| *volume > 0.0)
+            }
+            ChartMetric::E1rm => strength::workout_e1rm(workout, logged_sets, formula),
+            ChartMetric::Distance => workout.distance.map(|km| match units {
+                Units::Metric => km,
+                Units::Imperial => km * KM_TO_MILE,
+            }),
+            ChartMetric::Duration => workout.duration_minutes.map(|m| m as f64),
+        };
+        let Some(value) = value else {
+            continue;
+        };
+
+        let date = workout.timestamp.with_timezone(&Local).date_naive();
+        let summed = matches!(
+            metric,
+            ChartMetric::Volume | ChartMetric::Distance | ChartMetric::Duration
+        );
+        days.entry(date)
+            .and_modify(|v| *v = if summed { *v + value } else { v.max(value) })
+            .or_insert(value);
+    }
+    days.into_iter().collect()
+}
+
+/// Trailing moving average; the first points average over the values available so far.
+pub fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    (0..values.len())
+        .map(|i| {
+            let start = (i + 1).saturating_sub(window);
+            let slice = &values[start..=i];
+            slice.iter().sum::<f64>() / slice.len() as f64
+        })
+        .collect()
+}
+
+/// One-line chart of `values`, e.g. `▁▃▅▇█`.
+pub fn sparkline(values: &[f64]) -> String {
+    let Some((lo, hi)) = min_max(values.iter().copied()) else {
+        return String::new();
+    };
+    values
+        .iter()
+        .map(|v| {
+            if (hi - lo).abs() < f64::EPSILON {
+                BLOCKS[BLOCKS.len() / 2]
+            } else {
+                let idx = ((v - lo) / (hi - lo) * (BLOCKS.len() - 1) as f64).round() as usize;
+                BLOCKS[idx.min(BLOCKS.len() - 1)]
+            }
+        })
+        .collect()
+}
+
+/// Renders `points` (oldest first) as chart lines: the plot with y-axis labels,
+/// the x axis and a line of date labels. Returns no lines for an empty series.
+pub fn render_chart(points: &[(NaiveDate, f64)], options: &ChartOptions) -> Vec<String> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let values: Vec<f64> = points.iter().map(|(_, v)| *v).collect();
+    let average = options
+        .moving_average
+        .filter(|&w| w > 1)
+        .map(|w| moving_average(&values, w));
+
+    // Value range covers the data, the average and the target line.
+    let all_values = values
+        .iter()
+        .chain(average.iter().flatten())
+        .chain(options.target.iter())
+        .copied();
+    let (mut lo, mut hi) = min_max(all_values).unwrap_or((0.0, 1.0));
+    if options.style == ChartStyle::Bar && lo > 0.0 {
+        lo = 0.0; // Bars grow from zero
+    }
+    if (hi - lo).abs() < f64::EPSILON {
+        hi += 1.0;
+        if options.style == ChartStyle::Line {
+            lo -= 1.0;
+        }
+    }
+
+    let height = options.height.max(3);
+    let label_width = format_axis_value(lo).len().max(format_axis_value(hi).len());
+    let plot_width = options.width.saturating_sub(label_width + 2).max(10);
+
+    let columns = resample(&values, plot_width);
+    let average_columns = average.map(|a| resample(&a, plot_width));
+
+    let mut grid = vec![vec![' '; plot_width]; height];
+    let row_of = |v: f64| -> usize {
+        let level = ((v - lo) / (hi - lo) * (height - 1) as f64).round() as usize;
+        height - 1 - level.min(height - 1)
+    };
+
+    // The target line sits behind points but in front of bars
+    let draw_target = |grid: &mut Vec<Vec<char>>| {
+        if let Some(target) = options.target {
+            grid[row_of(target)].fill(TARGET_LINE);
+        }
+    };
+    if options.style == ChartStyle::Line {
+        draw_target(&mut grid);
+    }
+    match options.style {
+        ChartStyle::Line => {
+            if let Some(average_columns) = &average_columns {
+                for (col, v) in average_columns.iter().enumerate() {
+                    grid[row_of(*v)][col] = AVERAGE_POINT;
+                }
+            }
+            for (col, v) in columns.iter().enumerate() {
+                grid[row_of(*v)][col] = POINT;
+            }
+        }
+        ChartStyle::Bar => {
+            for (col, v) in columns.iter().enumerate() {
+                let eighths = ((v - lo) / (hi - lo) * (height * 8) as f64).round() as usize;
+                for level in 0..height {
+                    let filled = eighths.saturating_sub(level * 8).min(8);
+                    if filled > 0 {
+                        grid[height - 1 - level][col] = BLOCKS[filled - 1];
+                    }
+                }
+            }
+            draw_target(&mut grid);
+            if let Some(average_columns) = &average_columns {
+                for (col, v) in average_columns.iter().enumerate() {
+                    grid[row_of(*v)][col] = AVERAGE_POINT;
+                }
+            }
+        }
+    }
+
+    let mut lines = Vec::with_capacity(height + 2);
+    for (i, row) in grid.into_iter().enumerate() {
+        let row: String = row.into_iter().collect();
+        // Label the top and bottom rows and every fourth row in between,
+        // skipping labels that would crowd the bottom one
+        let labelled = i == height - 1 || (i % 4 == 0 && height - 1 - i >= 2);
+        if labelled {
+            let value = hi - i as f64 * (hi - lo) / (height - 1) as f64;
+            lines.push(format!("{:>label_width$} ┤{row}", format_axis_value(value)));
+        } else {
+            lines.push(format!("{:>label_width$} │{row}", ""));
+        }
+    }
+    lines.push(format!(
+        "{:>label_width$} └{}",
+        "",
+        TARGET_LINE.to_string().repeat(plot_width)
+    ));
+    lines.push(format!(
+        "{:>label_width$}  {}",
+        "",
+        date_axis(points, plot_width)
+    ));
+    lines
+}
+
+/// Maps `values` onto `width` columns: several values per column are averaged,
+/// fewer values than columns are stretched.
+fn resample(values: &[f64], width: usize) -> Vec<f64> {
+    let n = values.len();
+    (0..width)
+        .map(|col| {
+            let start = (col * n / width).min(n - 1);
+            let end = ((col + 1) * n / width).clamp(start + 1, n);
+            let slice = &values[start..end];
+            slice.iter().sum::<f64>() / slice.len() as f64
+        })
+        .collect()
+}
+
+/// First, middle and last date placed under the plot area.
+fn date_axis(points: &[(NaiveDate, f64)], width: usize) -> String {
+    let mut axis = vec![' '; width];
+    let mut place = |col: usize, date: NaiveDate| {
+        let label = date.format("%Y-%m-%d").to_string();
+        let start = col.min(width.saturating_sub(DATE_LABEL_WIDTH));
+        for (offset, c) in label.chars().enumerate() {
+            if let Some(cell) = axis.get_mut(start + offset) {
+                *cell = c;
+            }
+        }
+    };
+
+    let first = points[0].0;
+    let last = points[points.len() - 1].0;
+    place(0, first);
+    if last != first && width >= 2 * DATE_LABEL_WIDTH + 2 {
+        place(width - DATE_LABEL_WIDTH, last);
+        let mid = points[points.len() / 2].0;
+        if mid != first && mid != last && width >= 3 * DATE_LABEL_WIDTH + 4 {
+            place(width / 2 - DATE_LABEL_WIDTH / 2, mid);
+        }
+    }
+    axis.into_iter().collect::<String>().trim_end().to_string()
+}
+
+fn format_axis_value(value: f64) -> String {
+    format!("{value:.1}")
+}
+
+fn min_max(values: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    values.fold(None, |acc, v| match acc {
+        None => Some((v, v)),
+        Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(values: &[f64]) -> Vec<(NaiveDate, f64)> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (start + chrono::Duration::days(i as i64), *v))
+            .collect()
+    }
+
+    fn options(style: ChartStyle) -> ChartOptions {
+        ChartOptions {
+            width: 40,
+            height: 5,
+            style,
+            moving_average: None,
+            target: None,
+        }
+    }
+
+    #[test]
+    fn test_moving_average() {
+        assert_eq!(
+            moving_average(&[2.0, 4.0, 6.0, 8.0], 2),
+            vec![2.0, 3.0, 5.0, 7.0]
+        );
+        assert_eq!(moving_average(&[1.0, 2.0], 1), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0.0, 7.0, 14.0]), "▁▅█");
+        assert_eq!(sparkline(&[3.0, 3.0]), "▅▅");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_render_line_chart_layout() {
+        let lines = render_chart(&series(&[60.0, 70.0, 80.0]), &options(ChartStyle::Line));
+        // 5 plot rows, x axis, date labels
+        assert_eq!(lines.len(), 7);
+        assert!(lines[0].starts_with("80.0 ┤"));
+        assert!(lines[4].starts_with("60.0 ┤"));
+        assert!(lines[0].ends_with(POINT));
+        assert!(lines[4].contains(POINT));
+        assert!(lines[6].contains("2024-01-01"));
+        assert!(lines[6].ends_with("2024-01-03"));
+        // Every line fits the requested width
+        assert!(lines.iter().all(|l| l.chars().count() <= 40));
+    }
+
+    #[test]
+    fn test_render_bar_chart_starts_at_zero() {
+        let lines = render_chart(&series(&[5.0, 10.0]), &options(ChartStyle::Bar));
+        assert!(lines[4].starts_with(" 0.0 ┤"));
+        // The second half of the columns reaches the top row
+        assert!(lines[0].ends_with('█'));
+        assert!(!lines[0].contains("█ "));
+    }
+
+    #[test]
+    fn test_render_target_line() {
+        let mut opts = options(ChartStyle::Line);
+        opts.target = Some(100.0);
+        let lines = render_chart(&series(&[80.0, 80.0]), &opts);
+        assert!(lines[0].starts_with("100.0 ┤"));
+        assert!(lines[0].contains(&TARGET_LINE.to_string().repeat(10)));
+        assert!(render_chart(&[], &opts).is_empty());
+    }
+}
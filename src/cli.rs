@@ -1,6 +1,18 @@
-use chrono::{Duration, NaiveDate, Utc};
-use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use crate::calendar::CalendarMetric;
+use crate::chart::{ChartMetric, ChartStyle};
+use crate::import::native::CsvKind;
+use crate::load::{parse_intensity, parse_load_band, LoadBand, DEFAULT_CARDIO_INTENSITY};
+use crate::muscles::{parse_set_range, SetRange};
+use crate::report::ReportPeriod;
+use crate::sets::{parse_set_spec, SetEntry};
+use crate::strength::E1rmFormula;
+use crate::template::{parse_template_item, TemplateItem};
+use crate::timer::TimerKind;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, Weekday};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
+use std::io::IsTerminal;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "A CLI tool to track workouts", long_about = None)]
@@ -8,8 +20,73 @@ use clap_complete::Shell;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
-    #[arg(long, global = true)]
+    /// Shorthand for `--format csv`
+    #[arg(long, global = true, conflicts_with = "format")]
     pub export_csv: bool,
+    /// Output format for listing and statistics commands
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<OutputFormat>,
+    /// Never prompt; fail with exit code 3 where an answer would be needed (implied when stdin is not a terminal)
+    #[arg(long, global = true)]
+    pub no_input: bool,
+    /// Use this profile's database and config instead of the active one ('default' for the standard locations)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+}
+
+impl Cli {
+    /// Resolves the effective output format, honouring the legacy `--export-csv` flag.
+    pub fn output_format(&self) -> OutputFormat {
+        match self.format {
+            Some(format) => format,
+            None if self.export_csv => OutputFormat::Csv,
+            None => OutputFormat::Table,
+        }
+    }
+
+    /// Whether commands may prompt: not disabled with `--no-input` and stdin is a terminal.
+    pub fn interactive(&self) -> bool {
+        !self.no_input && std::io::stdin().is_terminal()
+    }
+}
+
+/// Answers to the prompts `add` may show, for scripts and cron jobs.
+#[derive(Args, Debug, Clone, Default)]
+pub struct PromptAnswers {
+    /// Your current bodyweight, logged and used when a bodyweight exercise needs one and none is logged yet
+    #[arg(long)]
+    pub bodyweight: Option<f64>,
+    /// Whether to notify about PBs from now on (yes/no), if not configured yet
+    #[arg(long, value_name = "yes|no", value_parser = clap::builder::BoolishValueParser::new())]
+    pub pb_notify: Option<bool>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable tables (default)
+    Table,
+    /// Comma-separated values with unit-suffixed headers
+    Csv,
+    /// A single JSON document with raw values and a units block
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// Tab-separated values with unit-suffixed headers
+    Tsv,
+}
+
+/// Order of `ta list` results.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListSort {
+    /// Newest first
+    #[default]
+    Date,
+    /// Exercise name, A to Z
+    Exercise,
+    /// Heaviest first
+    Weight,
+    /// Largest volume first (logged sets, or sets x reps x weight)
+    Volume,
 }
 
 #[derive(ValueEnum, Clone, Debug, PartialEq, Eq)]
@@ -19,33 +96,230 @@ pub enum ExerciseTypeCli {
     BodyWeight,
 }
 
-// Custom parser for date strings and shorthands
+// Custom parser for exercise name mappings: NAME=EXERCISE
+fn parse_exercise_mapping(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((from, to)) if !from.trim().is_empty() && !to.trim().is_empty() => {
+            Ok((from.trim().to_string(), to.trim().to_string()))
+        }
+        _ => Err(format!(
+            "Invalid mapping: '{}'. Use NAME=EXERCISE, e.g. \"Bench Press (Barbell)=bench\".",
+            s
+        )),
+    }
+}
+
+// Custom parser for ages: Nd (days), Nw (weeks) or a plain number of days
+pub fn parse_age(s: &str) -> Result<Duration, String> {
+    let lower = s.trim().to_lowercase();
+    let (number, days_per_unit) = match lower.strip_suffix('w') {
+        Some(weeks) => (weeks, 7),
+        None => (lower.strip_suffix('d').unwrap_or(&lower), 1),
+    };
+    match number.trim().parse::<i64>() {
+        Ok(n) if n >= 0 => Ok(Duration::days(n * days_per_unit)),
+        _ => Err(format!(
+            "Invalid age: '{}'. Use a number of days (30, 30d) or weeks (4w).",
+            s
+        )),
+    }
+}
+
+// Custom parser for the start of a window: an age back from today (90d, 12w) or a date
+pub fn parse_since(s: &str) -> Result<NaiveDate, String> {
+    match parse_age(s) {
+        Ok(age) => Ok(Local::now().date_naive() - age),
+        Err(_) => parse_range_start(s),
+    }
+}
+
+// Custom parser for timer lengths: 90s, 2m, 1m30s or a plain number of seconds
+pub fn parse_timer_duration(s: &str) -> Result<Duration, String> {
+    let invalid = || {
+        format!(
+            "Invalid duration: '{}'. Use seconds (90, 90s), minutes (2m) or both (1m30s).",
+            s
+        )
+    };
+    let lower = s.trim().to_lowercase();
+    if lower.is_empty() {
+        return Err(invalid());
+    }
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in lower.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let n: i64 = number.parse().map_err(|_| invalid())?;
+        seconds += match c {
+            'h' => n * 3600,
+            'm' => n * 60,
+            's' => n,
+            _ => return Err(invalid()),
+        };
+        number.clear();
+    }
+    // A trailing bare number counts as seconds, e.g. "1m30"
+    if !number.is_empty() {
+        seconds += number.parse::<i64>().map_err(|_| invalid())?;
+    }
+    Ok(Duration::seconds(seconds))
+}
+
+/// A date given on the command line, with an optional time of day in local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateArg {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+}
+
+impl From<NaiveDate> for DateArg {
+    fn from(date: NaiveDate) -> Self {
+        DateArg { date, time: None }
+    }
+}
+
+const DATE_FORMATS_HELP: &str = "Use 'today', 'yesterday', a weekday ('monday', 'last friday'), an offset ('-3d', '2w ago'), YYYY-MM-DD, DD.MM.YYYY, or YYYY/MM/DD.";
+
+/// Most recent `weekday` on or before `today` (strictly before it with `strictly_before`).
+fn last_weekday(today: NaiveDate, weekday: Weekday, strictly_before: bool) -> NaiveDate {
+    let days_back =
+        (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+    let days_back = if days_back == 0 && strictly_before {
+        7
+    } else {
+        days_back
+    };
+    today - Duration::days(i64::from(days_back))
+}
+
+/// Resolves a date relative to `today`.
+fn resolve_date(s: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let lower = s.trim().to_lowercase();
+    match lower.as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        _ => {}
+    }
+    if resolve_range(&lower, today).is_some() {
+        return Err(format!(
+            "'{}' is a range of dates; give a single date here. {}",
+            s, DATE_FORMATS_HELP
+        ));
+    }
+
+    // Weekday names: "friday" is the most recent Friday, "last friday" the one before today
+    let (weekday_name, strictly_before) = match lower.strip_prefix("last ") {
+        Some(name) => (name.trim(), true),
+        None => (lower.as_str(), false),
+    };
+    if let Ok(weekday) = weekday_name.parse::<Weekday>() {
+        return Ok(last_weekday(today, weekday, strictly_before));
+    }
+
+    // Offsets into the past: "-3d", "2w ago", "3 days ago"
+    if let Some(offset) = lower
+        .strip_prefix('-')
+        .or_else(|| lower.strip_suffix("ago"))
+    {
+        let compact: String = offset.split_whitespace().collect();
+        let compact = compact
+            .replace("days", "d")
+            .replace("day", "d")
+            .replace("weeks", "w")
+            .replace("week", "w");
+        if let Ok(age) = parse_age(&compact) {
+            return Ok(today - age);
+        }
+    }
+
+    let s = s.trim();
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(s, "%d.%m.%Y"))
+        .or_else(|_| NaiveDate::parse_from_str(s, "%Y/%m/%d"))
+        .map_err(|_| format!("Invalid date format: '{}'. {}", s, DATE_FORMATS_HELP))
+}
+
+/// Resolves calendar ranges such as "this week" (weeks start on Monday).
+fn resolve_range(lower: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let week_start = last_weekday(today, Weekday::Mon, false);
+    let month_start = today.with_day(1)?;
+    let year_start = today.with_ordinal(1)?;
+    let range = match lower {
+        "this week" => (week_start, week_start + Duration::days(6)),
+        "last week" => (
+            week_start - Duration::days(7),
+            week_start - Duration::days(1),
+        ),
+        "this month" => {
+            let next_month = (month_start + Duration::days(31)).with_day(1)?;
+            (month_start, next_month - Duration::days(1))
+        }
+        "last month" => {
+            let end = month_start - Duration::days(1);
+            (end.with_day(1)?, end)
+        }
+        "this year" => (year_start, year_start.with_month(12)?.with_day(31)?),
+        "last year" => {
+            let end = year_start - Duration::days(1);
+            (end.with_ordinal(1)?, end)
+        }
+        _ => return None,
+    };
+    Some(range)
+}
+
+// Custom parser for date strings and shorthands, relative to the local date
 pub fn parse_date_shorthand(s: &str) -> Result<NaiveDate, String> {
-    match s.to_lowercase().as_str() {
-        "today" => Ok(Utc::now().date_naive()),
-        "yesterday" => Ok((Utc::now() - Duration::days(1)).date_naive()),
-        _ => {
-            // Try parsing YYYY-MM-DD first
-            if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
-                Ok(date)
-            }
-            // Try parsing DD.MM.YYYY next
-            else if let Ok(date) = NaiveDate::parse_from_str(s, "%d.%m.%Y") {
-                Ok(date)
-            }
-            // Try parsing YYYY/MM/DD
-            else if let Ok(date) = NaiveDate::parse_from_str(s, "%Y/%m/%d") {
-                Ok(date)
-            } else {
-                Err(format!(
-                    "Invalid date format: '{}'. Use 'today', 'yesterday', YYYY-MM-DD, DD.MM.YYYY, or YYYY/MM/DD.", // Updated help message
-                    s
-                ))
-            }
+    resolve_date(s, Local::now().date_naive())
+}
+
+// Custom parser for a date with an optional time, e.g. "today 18:30" or just "18:30"
+pub fn parse_date_time(s: &str) -> Result<DateArg, String> {
+    let trimmed = s.trim();
+    let parse_time = |t: &str| NaiveTime::parse_from_str(t, "%H:%M").ok();
+    let (date, time) = match trimmed.rsplit_once(char::is_whitespace) {
+        Some((date, time)) if parse_time(time).is_some() => (date, parse_time(time)),
+        _ if parse_time(trimmed).is_some() => ("today", parse_time(trimmed)),
+        _ => (trimmed, None),
+    };
+    Ok(DateArg {
+        date: parse_date_shorthand(date)?,
+        time,
+    })
+}
+
+// Custom parser for a range of dates: "this week", "last month", ..., a single date,
+// or two of these as START..END
+pub fn parse_date_range(s: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    if let Some((first, last)) = s.split_once("..") {
+        let (start, end) = (parse_range_start(first)?, parse_range_end(last)?);
+        if start > end {
+            return Err(format!(
+                "Invalid range: '{}'. START must not be after END.",
+                s
+            ));
         }
+        return Ok((start, end));
+    }
+    let today = Local::now().date_naive();
+    match resolve_range(&s.trim().to_lowercase(), today) {
+        Some(range) => Ok(range),
+        None => resolve_date(s, today).map(|date| (date, date)),
     }
 }
 
+// Custom parsers for range bounds: a date, or the first/last day of a range like "last month"
+pub fn parse_range_start(s: &str) -> Result<NaiveDate, String> {
+    parse_date_range(s).map(|(start, _)| start)
+}
+
+pub fn parse_range_end(s: &str) -> Result<NaiveDate, String> {
+    parse_date_range(s).map(|(_, end)| end)
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Define a new exercise type
@@ -121,6 +395,10 @@ pub enum Commands {
         #[arg(short, long)]
         weight: Option<f64>,
 
+        /// Log an individual set as WEIGHTxREPS or REPS, optionally with @rpeN (repeatable, e.g. --set 100x8 --set 110x6@rpe9)
+        #[arg(long = "set", value_name = "SET", value_parser = parse_set_spec, conflicts_with_all = &["sets", "reps", "weight"])]
+        set_entries: Vec<SetEntry>,
+
         /// Duration in minutes (for cardio or timed exercises)
         #[arg(short = 'd', long)] // Added short alias
         duration: Option<i64>,
@@ -133,10 +411,10 @@ pub enum Commands {
         #[arg(short, long)]
         notes: Option<String>,
 
-        /// Date of the workout ('today', 'yesterday', YYYY-MM-DD, DD.MM.YYYY, YYYY/MM/DD)
-        #[arg(long, value_parser = parse_date_shorthand, default_value = "today")]
+        /// Date of the workout ('today', 'yesterday', 'monday', '-3d', YYYY-MM-DD, DD.MM.YYYY, YYYY/MM/DD), optionally with a time ('today 18:30')
+        #[arg(long, value_parser = parse_date_time, default_value = "today")]
         // Feature 3
-        date: NaiveDate,
+        date: DateArg,
 
         // Optional fields for implicit exercise creation during 'add' if exercise not found
         #[arg(
@@ -149,6 +427,9 @@ pub enum Commands {
 
         #[arg(long, requires = "implicit-exercise-type", id = "implicit-muscles")]
         implicit_muscles: Option<String>, // Renamed to avoid clash with filter
+
+        #[command(flatten)]
+        answers: PromptAnswers,
     },
     /// Edit an existing workout entry
     EditWorkout {
@@ -166,6 +447,9 @@ pub enum Commands {
         /// New weight used (absolute value, bodyweight logic NOT reapplied on edit)
         #[arg(short, long)]
         weight: Option<f64>,
+        /// Replace the logged sets (WEIGHTxREPS or REPS, optionally with @rpeN; repeatable)
+        #[arg(long = "set", value_name = "SET", value_parser = parse_set_spec, conflicts_with_all = &["sets", "reps", "weight"])]
+        set_entries: Vec<SetEntry>,
         /// New duration in minutes
         #[arg(short = 'd', long)] // Added short alias
         duration: Option<i64>,
@@ -175,7 +459,7 @@ pub enum Commands {
         /// New additional notes
         #[arg(short, long)]
         notes: Option<String>,
-        /// New date for the workout ('today', 'yesterday', YYYY-MM-DD, DD.MM.YYYY, YYYY/MM/DD)
+        /// New date for the workout ('today', 'yesterday', 'monday', '-3d', YYYY-MM-DD, DD.MM.YYYY, YYYY/MM/DD)
         #[arg(long, value_parser = parse_date_shorthand)] // Feature 3 (for editing date)
         date: Option<NaiveDate>,
         #[arg(long)]
@@ -192,7 +476,7 @@ pub enum Commands {
         #[arg(short = 'e', long, conflicts_with = "nth_last_day_exercise")]
         exercise: Option<String>,
 
-        /// Filter by a specific date ('today', 'yesterday', YYYY-MM-DD, DD.MM.YYYY)
+        /// Filter by a specific date ('today', 'yesterday', 'monday', '-3d', YYYY-MM-DD, DD.MM.YYYY)
         #[arg(long, value_parser = parse_date_shorthand, conflicts_with_all = &["today_flag", "yesterday_flag", "nth_last_day_exercise"])]
         date: Option<NaiveDate>,
 
@@ -204,9 +488,12 @@ pub enum Commands {
         #[arg(short, long)]
         muscle: Option<String>, // Short 'm'
 
-        /// Show only the last N entries (when no date/day filters used)
-        #[arg(short = 'n', long, default_value_t = 20, conflicts_with_all = &["today_flag", "yesterday_flag", "date", "nth_last_day_exercise"])]
-        limit: u32,
+        /// Show only N entries (default 20 when no date, day or range filters are used)
+        #[arg(short = 'n', long, conflicts_with_all = &["today_flag", "yesterday_flag", "date", "nth_last_day_exercise"])]
+        limit: Option<u32>,
+
+        #[command(flatten)]
+        window: ListWindow,
 
         // Keep flags for backward compatibility or preference, but date is more versatile
         #[arg(long, conflicts_with_all = &["yesterday_flag", "date", "nth_last_day_exercise", "limit"])]
@@ -219,6 +506,10 @@ pub enum Commands {
         nth_last_day_exercise: Option<String>,
         #[arg(long, value_name = "N", requires = "nth_last_day_exercise", conflicts_with_all = &["limit", "date", "today_flag", "yesterday_flag", "exercise", "type_", "muscle"])]
         nth_last_day_n: Option<u32>,
+
+        /// Show the workouts logged during a training session (see `ta session list`)
+        #[arg(long, value_name = "SESSION_ID", conflicts_with_all = &["date", "today_flag", "yesterday_flag", "nth_last_day_exercise"])]
+        session: Option<i64>,
     },
     /// List defined exercise types
     ListExercises {
@@ -229,11 +520,52 @@ pub enum Commands {
         #[arg(short = 'm', long, num_args(0..))] // short 'm'
         muscle: Option<Vec<String>>,
     },
-    /// Show statistics for a specific exercise
+    /// Show statistics for one or more exercises, side by side for several
     Stats {
-        /// Name, ID, or Alias of the exercise to show stats for
+        /// Name, ID, or Alias of the exercise to show stats for (repeat to compare several)
+        #[arg(short = 'e', long, required_unless_present = "all")]
+        exercise: Vec<String>,
+        /// Summarize every exercise with workouts, most recently trained first
+        #[arg(long, conflicts_with_all = &["exercise", "rm_table"])]
+        all: bool,
+        /// Only count workouts from this date on: an age (90d, 12w) or a date ('last month', YYYY-MM-DD)
+        #[arg(long, value_parser = parse_since)]
+        since: Option<NaiveDate>,
+        /// Show estimated loads for 1-12 reps based on the best estimated 1RM
+        #[arg(long)]
+        rm_table: bool,
+    },
+    /// Draw a chart of an exercise metric (or of your bodyweight) over time
+    #[command(args_conflicts_with_subcommands = true)]
+    Chart {
+        #[command(subcommand)]
+        subject: Option<ChartSubject>,
+        /// Name, ID, or Alias of the exercise to chart
         #[arg(short = 'e', long)]
-        exercise: String,
+        exercise: Option<String>,
+        /// Metric to chart (defaults to weight, reps or distance depending on the exercise type)
+        #[arg(short = 'm', long, value_enum)]
+        metric: Option<ChartMetric>,
+        #[command(flatten)]
+        options: ChartArgs,
+    },
+    /// Show a calendar heatmap of a year of training
+    Calendar {
+        /// Year to show (defaults to the current year)
+        #[arg(short, long)]
+        year: Option<i32>,
+        /// Only count workouts of this exercise (Name, ID or Alias)
+        #[arg(short = 'e', long)]
+        exercise: Option<String>,
+        /// Only count workouts targeting this muscle
+        #[arg(short, long)]
+        muscle: Option<String>,
+        /// What the shading of each day represents
+        #[arg(long, value_enum, default_value_t = CalendarMetric::Workouts)]
+        by: CalendarMetric,
+        /// Use plain ASCII characters without color
+        #[arg(long)]
+        ascii: bool,
     },
     /// Create an alias for an existing exercise
     Alias {
@@ -256,7 +588,7 @@ pub enum Commands {
     LogBodyweight {
         /// Your bodyweight
         weight: f64,
-        /// Date of measurement ('today', 'yesterday', YYYY-MM-DD, DD.MM.YYYY, YYYY/MM/DD)
+        /// Date of measurement ('today', 'yesterday', 'monday', '-3d', YYYY-MM-DD, DD.MM.YYYY, YYYY/MM/DD)
         #[arg(long, value_parser = parse_date_shorthand, default_value = "today")]
         date: NaiveDate,
     },
@@ -278,6 +610,20 @@ pub enum Commands {
     ClearTargetWeight,
     /// Show the path to the database file
     ConfigPath,
+    /// Back up the database, config and CLI data into a timestamped archive
+    Backup {
+        /// Directory to write the archive to (default: 'backups' next to the config file)
+        #[arg(long)]
+        to: Option<PathBuf>,
+    },
+    /// Replace the current data with a backup archive, after previewing it
+    Restore {
+        /// Backup archive created by 'ta backup' (or automatically before risky commands)
+        archive: PathBuf,
+        /// Restore without asking for confirmation
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
     /// Enable or disable Personal Best (PB) notifications globally
     SetPbNotification {
         // Feature 4
@@ -310,6 +656,52 @@ pub enum Commands {
         #[arg(value_parser = clap::value_parser!(u32).range(1..))] // Ensure at least 1 day
         days: u32,
     },
+    /// Set the formula used for estimated one-rep max (e1RM)
+    SetE1rmFormula {
+        #[arg(value_enum)]
+        formula: E1rmFormula,
+    },
+    /// Set the weekly hard-set target range of a muscle (or 'default' for all others)
+    SetMuscleTarget {
+        /// Muscle as listed on exercises (e.g. chest), or 'default'
+        muscle: String,
+        /// Weekly hard sets, MIN-MAX (e.g. 10-20)
+        #[arg(value_parser = parse_set_range, required_unless_present = "clear")]
+        range: Option<SetRange>,
+        /// Remove the muscle's own target so it uses the default again
+        #[arg(long, conflicts_with = "range")]
+        clear: bool,
+    },
+    /// Show weekly hard sets per muscle against targets, and push/pull and quad/hamstring balance
+    Muscles {
+        /// Number of weeks to look back, ending today
+        #[arg(short, long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
+        weeks: u32,
+    },
+    /// Set the acute:chronic workload ratio band considered safe by 'ta load'
+    SetLoadBand {
+        /// Lowest and highest safe ratio, LOW-HIGH (default 0.8-1.3)
+        #[arg(value_parser = parse_load_band)]
+        band: LoadBand,
+    },
+    /// Show daily training load with 7-day acute and 28-day chronic load, their ratio, monotony and strain
+    Load {
+        /// Filter by exercise Name, ID or Alias
+        #[arg(short = 'e', long)]
+        exercise: Option<String>,
+        /// Filter by exercise type
+        #[arg(short = 't', long, value_enum)]
+        type_: Option<ExerciseTypeCli>,
+        /// Filter by target muscle (matches if muscle is in the list)
+        #[arg(short, long)]
+        muscle: Option<String>,
+        /// Number of days to show, ending today
+        #[arg(short, long, default_value_t = 28, value_parser = clap::value_parser!(u32).range(1..))]
+        days: u32,
+        /// Session RPE (1-10) of cardio workouts; their load is minutes times this
+        #[arg(long, default_value_t = DEFAULT_CARDIO_INTENSITY, value_parser = parse_intensity)]
+        intensity: f64,
+    },
     /// Show total workout volume (sets*reps*weight) per day
     Volume {
         // Feature 1
@@ -317,10 +709,10 @@ pub enum Commands {
         #[arg(short = 'e', long)]
         exercise: Option<String>,
 
-        /// Filter by a specific date ('today', 'yesterday', YYYY-MM-DD, DD.MM.YYYY, Weekday Name)
-        #[arg(long, value_parser = parse_date_shorthand, conflicts_with_all = &["start_date", "end_date", "limit_days"])]
+        /// Filter by a date or a range ('today', 'monday', '-3d', 'this week', 'last month', YYYY-MM-DD, DD.MM.YYYY, START..END)
+        #[arg(long, value_parser = parse_date_range, conflicts_with_all = &["start_date", "end_date", "limit_days"])]
         // Corrected conflicts
-        date: Option<NaiveDate>,
+        date: Option<(NaiveDate, NaiveDate)>,
 
         /// Filter by exercise type
         #[arg(short = 't', long, value_enum)]
@@ -343,12 +735,129 @@ pub enum Commands {
         // Corrected conflicts and added requires
         end_date: Option<NaiveDate>,
     },
+    /// Compare frequency, volume, bests, distance and pace of two date ranges side by side
+    Compare {
+        /// First range, e.g. 2026-01-01..2026-03-31 or 'last month'
+        #[arg(long, value_parser = parse_date_range)]
+        from: (NaiveDate, NaiveDate),
+        /// Second range, compared against the first
+        #[arg(long, value_parser = parse_date_range)]
+        to: (NaiveDate, NaiveDate),
+        /// Filter by exercise Name, ID or Alias
+        #[arg(short = 'e', long)]
+        exercise: Option<String>,
+        /// Filter by exercise type
+        #[arg(short = 't', long, value_enum)]
+        type_: Option<ExerciseTypeCli>,
+        /// Filter by target muscle (matches if muscle is in the list)
+        #[arg(short, long)]
+        muscle: Option<String>,
+    },
+    /// Show weekly, monthly or yearly totals compared with the previous period
+    Report {
+        #[arg(value_enum)]
+        period: ReportPeriod,
+        /// Number of periods to show, newest first
+        #[arg(short = 'n', long, default_value_t = 8, value_parser = clap::value_parser!(u32).range(1..))]
+        periods: u32,
+        /// Filter by exercise Name, ID or Alias
+        #[arg(short = 'e', long)]
+        exercise: Option<String>,
+        /// Filter by exercise type
+        #[arg(short = 't', long, value_enum)]
+        type_: Option<ExerciseTypeCli>,
+        /// Filter by target muscle (matches if muscle is in the list)
+        #[arg(short, long)]
+        muscle: Option<String>,
+    },
     /// Set default units (Metric/Imperial)
     SetUnits {
         // Feature 3
         #[arg(value_enum)]
         units: UnitsCli,
     },
+    /// Import data from files
+    Import {
+        #[command(subcommand)]
+        source: ImportCommands,
+    },
+    /// Group workouts into training sessions
+    Session {
+        #[command(subcommand)]
+        action: SessionCommands,
+    },
+    /// Manage workout templates and replay them
+    Template {
+        #[command(subcommand)]
+        action: TemplateCommands,
+    },
+    /// Create, list, switch between or delete profiles (separate databases and configs)
+    Profile {
+        #[command(subcommand)]
+        action: ProfileCommands,
+    },
+    /// List, restore or permanently remove deleted records
+    Trash {
+        #[command(subcommand)]
+        action: TrashCommands,
+    },
+    /// Count down a rest between sets and record it against the last workout
+    Rest {
+        /// Length of the rest, e.g. 90s, 2m or 1m30s (a plain number is seconds)
+        #[arg(default_value = "90s", value_parser = parse_timer_duration)]
+        duration: Duration,
+        /// Record the rest against this workout ID instead of the last one logged
+        /// (in the open session, otherwise today)
+        #[arg(short, long)]
+        workout: Option<i64>,
+        /// Only count down; don't record the rest
+        #[arg(long, conflicts_with = "workout")]
+        no_record: bool,
+    },
+    /// Run an interval timer for conditioning, optionally logging it as a workout
+    Timer {
+        #[arg(value_enum)]
+        kind: TimerKind,
+        /// Number of rounds (EMOM: 10, Tabata: 8)
+        #[arg(short, long)]
+        rounds: Option<u32>,
+        /// Work per round, e.g. 40s (EMOM: interval length, 1m; AMRAP: total length, 10m; Tabata: 20s)
+        #[arg(short, long, value_parser = parse_timer_duration)]
+        work: Option<Duration>,
+        /// Rest between rounds (Tabata only, 10s)
+        #[arg(long, value_parser = parse_timer_duration)]
+        rest: Option<Duration>,
+        /// Log a workout of this Cardio or BodyWeight exercise with the time spent
+        #[arg(short, long)]
+        log: Option<String>,
+        /// Notes for the logged workout
+        #[arg(short, long, requires = "log")]
+        notes: Option<String>,
+        #[command(flatten)]
+        answers: PromptAnswers,
+    },
+    /// Start an interactive shell for logging during a session (e.g. `bench 3x10@60`)
+    Shell,
+    /// Open the full-screen dashboard (needs a build with the `tui` feature)
+    Tui,
+    /// Revert the most recent changes, newest first (see `ta history`)
+    Undo {
+        /// Number of operations to undo
+        #[arg(default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        count: u32,
+        /// Undo without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Show the changes recorded for `ta undo`
+    #[command(args_conflicts_with_subcommands = true)]
+    History {
+        #[command(subcommand)]
+        action: Option<HistoryCommands>,
+        /// Number of most recent operations to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
     Sync {
         /// Optional: Override the server URL from config (e.g., http://localhost:3030)
         #[arg(long)]
@@ -361,6 +870,271 @@ pub enum Commands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommands {
+    /// Create an empty profile
+    Create {
+        /// Profile name (letters, digits, '-' and '_')
+        name: String,
+    },
+    /// List profiles, marking the active one
+    List,
+    /// Make a profile the one used by default ('default' for the standard locations)
+    Switch { name: String },
+    /// Delete a profile with its database, config and backups
+    Delete {
+        name: String,
+        /// Delete without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TrashCommands {
+    /// List deleted workouts, exercises and bodyweight entries
+    List,
+    /// Add a deleted record back (it gets a new ID)
+    Restore {
+        /// ID of the trash item, as shown by `ta trash list`
+        id: i64,
+    },
+    /// Permanently remove items from the trash
+    Empty {
+        /// Only remove items deleted longer ago than this (e.g. 30d, 8w; a plain number is days)
+        #[arg(long, value_name = "AGE", value_parser = parse_age)]
+        older_than: Option<Duration>,
+        /// Remove without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryCommands {
+    /// Remove old operations from the history; they can no longer be undone
+    Prune {
+        /// Keep only the N most recent operations
+        #[arg(long, value_name = "N", required_unless_present = "older_than")]
+        keep: Option<usize>,
+        /// Remove operations older than this (e.g. 30d, 8w; a plain number is days)
+        #[arg(long, value_name = "AGE", value_parser = parse_age)]
+        older_than: Option<Duration>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ChartSubject {
+    /// Chart logged bodyweight, with the target bodyweight as a horizontal line
+    Bodyweight {
+        #[command(flatten)]
+        options: ChartArgs,
+    },
+}
+
+/// Date range, order and paging for `ta list`
+#[derive(Args, Debug, Default)]
+pub struct ListWindow {
+    /// Only workouts on or after this date (or from the start of a range such as 'last month')
+    #[arg(long, value_parser = parse_range_start, conflicts_with_all = &["date", "today_flag", "yesterday_flag"])]
+    pub from: Option<NaiveDate>,
+    /// Only workouts on or before this date (or up to the end of a range such as 'last month')
+    #[arg(long, value_parser = parse_range_end, conflicts_with_all = &["date", "today_flag", "yesterday_flag"])]
+    pub to: Option<NaiveDate>,
+    /// Skip the first N entries (for paging with --limit)
+    #[arg(long, default_value_t = 0)]
+    pub offset: usize,
+    /// Order of the entries
+    #[arg(long, value_enum)]
+    pub sort: Option<ListSort>,
+    /// Reverse the order
+    #[arg(long)]
+    pub reverse: bool,
+}
+
+impl ListWindow {
+    /// Whether any option needs the full result set rather than the library's limit.
+    pub fn is_active(&self) -> bool {
+        self.from.is_some()
+            || self.to.is_some()
+            || self.offset > 0
+            || self.sort.is_some()
+            || self.reverse
+    }
+}
+
+/// Display options shared by all charts
+#[derive(Args, Debug)]
+pub struct ChartArgs {
+    /// Chart style
+    #[arg(long, value_enum, default_value_t = ChartStyle::Line)]
+    pub style: ChartStyle,
+    /// Overlay a moving average over the last N data points
+    #[arg(long = "ma", value_name = "N", value_parser = clap::value_parser!(u32).range(2..))]
+    pub moving_average: Option<u32>,
+    /// Chart only the last N data points (logged days)
+    #[arg(short = 'n', long, default_value_t = 60)]
+    pub limit: usize,
+    /// Height of the chart in rows
+    #[arg(long, default_value_t = 15, value_parser = clap::value_parser!(u16).range(3..))]
+    pub height: u16,
+    /// Width of the chart in columns (defaults to the terminal width, $COLUMNS or 80)
+    #[arg(long)]
+    pub width: Option<u16>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ImportCommands {
+    /// Import a CSV or TSV file in the format written by `--format csv`/`tsv`
+    Csv {
+        /// Path of the file to import
+        file: PathBuf,
+        /// What the file contains
+        #[arg(long, value_enum)]
+        kind: CsvKind,
+        /// Check the file and report what would be imported without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Import a Strong CSV export
+    Strong {
+        #[command(flatten)]
+        args: AppImportArgs,
+    },
+    /// Import a Hevy CSV export
+    Hevy {
+        #[command(flatten)]
+        args: AppImportArgs,
+    },
+    /// Import a FitNotes CSV export
+    Fitnotes {
+        #[command(flatten)]
+        args: AppImportArgs,
+    },
+    /// Import a GPX track (or a directory of them) as Cardio workouts
+    Gpx {
+        #[command(flatten)]
+        args: TrackImportArgs,
+    },
+    /// Import a TCX activity (or a directory of them) as Cardio workouts
+    Tcx {
+        #[command(flatten)]
+        args: TrackImportArgs,
+    },
+}
+
+/// Options shared by the GPX and TCX importers
+#[derive(Args, Debug)]
+pub struct TrackImportArgs {
+    /// Path of the file, or of a directory whose files are all imported
+    pub path: PathBuf,
+    /// Exercise to log the workouts as (created as Cardio if it doesn't exist)
+    #[arg(short, long)]
+    pub exercise: String,
+    /// Notes added to each workout before the route summary
+    #[arg(short, long)]
+    pub notes: Option<String>,
+    /// Check the files and report what would be imported without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Options shared by the workout app importers
+#[derive(Args, Debug)]
+pub struct AppImportArgs {
+    /// Path of the exported CSV file
+    pub file: PathBuf,
+    /// Log an exercise from the file as an existing exercise, NAME=EXERCISE (repeatable)
+    #[arg(long = "map", value_name = "NAME=EXERCISE", value_parser = parse_exercise_mapping)]
+    pub mappings: Vec<(String, String)>,
+    /// Unit of weights in the file when it doesn't say (defaults to your configured units)
+    #[arg(long, value_parser = ["kg", "lbs"])]
+    pub weight_unit: Option<String>,
+    /// Unit of distances in the file when it doesn't say (defaults to your configured units)
+    #[arg(long, value_parser = ["km", "mi"])]
+    pub distance_unit: Option<String>,
+    /// Create unknown exercises without asking whether to map them to existing ones
+    #[arg(long)]
+    pub create_missing: bool,
+    /// Check the file and report what would be imported without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SessionCommands {
+    /// Start a session; workouts added while it is open are attached to it
+    Start {
+        /// Name of the session (e.g., "Push A")
+        #[arg(short, long)]
+        name: Option<String>,
+        /// Notes for the session
+        #[arg(long)]
+        notes: Option<String>,
+    },
+    /// End the open session
+    End {
+        /// Notes to append to the session
+        #[arg(long)]
+        notes: Option<String>,
+    },
+    /// Show a session summary (defaults to the open or most recent session)
+    Show {
+        /// ID of the session to show
+        id: Option<i64>,
+    },
+    /// List all sessions
+    List {
+        /// Show only the last N sessions
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TemplateCommands {
+    /// Create a template from an ordered list of exercises
+    Create {
+        /// Name of the template (e.g., "Push A") - Must be unique (case-insensitive)
+        name: String,
+        /// Exercise with an optional target, EXERCISE[:SETSxREPS@WEIGHT] (repeatable, in order)
+        #[arg(short, long = "item", value_name = "ITEM", value_parser = parse_template_item, required = true)]
+        items: Vec<TemplateItem>,
+    },
+    /// Edit a template
+    Edit {
+        /// Name of the template to edit
+        name: String,
+        /// New name for the template
+        #[arg(long)]
+        rename: Option<String>,
+        /// Append an exercise, EXERCISE[:SETSxREPS@WEIGHT] (repeatable)
+        #[arg(short, long = "add", value_name = "ITEM", value_parser = parse_template_item)]
+        add: Vec<TemplateItem>,
+        /// Remove an exercise by the name used in the template (repeatable)
+        #[arg(short, long = "remove", value_name = "EXERCISE")]
+        remove: Vec<String>,
+        /// Replace all exercises, EXERCISE[:SETSxREPS@WEIGHT] (repeatable, in order)
+        #[arg(long = "item", value_name = "ITEM", value_parser = parse_template_item, conflicts_with_all = &["add", "remove"])]
+        items: Vec<TemplateItem>,
+    },
+    /// List templates
+    List,
+    /// Delete a template
+    Delete {
+        /// Name of the template to delete
+        name: String,
+    },
+    /// Walk through a template, logging each accepted exercise
+    Run {
+        /// Name of the template to run
+        name: String,
+        /// Date of the workouts ('today', 'yesterday', 'monday', '-3d', YYYY-MM-DD, DD.MM.YYYY, YYYY/MM/DD)
+        #[arg(long, value_parser = parse_date_shorthand, default_value = "today")]
+        date: NaiveDate,
+    },
+}
+
 // Function to parse CLI arguments
 pub fn parse_args() -> Cli {
     Cli::parse()
@@ -379,19 +1153,19 @@ pub enum UnitsCli {
 #[cfg(test)]
 mod tests {
     use super::*; // Import items from the parent module (cli)
-    use chrono::{Duration, NaiveDate, Utc};
+    use chrono::{Duration, NaiveDate};
 
     #[test]
     fn test_date_parsing_today() {
         let result = parse_date_shorthand("today").unwrap();
-        let today = Utc::now().date_naive();
+        let today = Local::now().date_naive();
         assert_eq!(result, today);
     }
 
     #[test]
     fn test_date_parsing_yesterday() {
         let result = parse_date_shorthand("yesterday").unwrap();
-        let yesterday = Utc::now().date_naive() - Duration::days(1);
+        let yesterday = Local::now().date_naive() - Duration::days(1);
         assert_eq!(result, yesterday);
     }
 
@@ -416,11 +1190,11 @@ mod tests {
     #[test]
     fn test_date_parsing_case_insensitive() {
         let result_today = parse_date_shorthand("ToDaY").unwrap();
-        let today = Utc::now().date_naive();
+        let today = Local::now().date_naive();
         assert_eq!(result_today, today);
 
         let result_yesterday = parse_date_shorthand("yEsTeRdAy").unwrap();
-        let yesterday = Utc::now().date_naive() - Duration::days(1);
+        let yesterday = Local::now().date_naive() - Duration::days(1);
         assert_eq!(result_yesterday, yesterday);
     }
 
@@ -454,4 +1228,208 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid date format"));
     }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_date_parsing_weekdays() {
+        let wednesday = date(3, 5);
+        assert_eq!(resolve_date("monday", wednesday).unwrap(), date(3, 3));
+        assert_eq!(resolve_date("Wed", wednesday).unwrap(), wednesday);
+        assert_eq!(
+            resolve_date("last wednesday", wednesday).unwrap(),
+            date(2, 26)
+        );
+        assert_eq!(resolve_date("last friday", wednesday).unwrap(), date(2, 28));
+    }
+
+    #[test]
+    fn test_date_parsing_offsets() {
+        let today = date(3, 5);
+        assert_eq!(resolve_date("-3d", today).unwrap(), date(3, 2));
+        assert_eq!(resolve_date("2w ago", today).unwrap(), date(2, 19));
+        assert_eq!(resolve_date("1 day ago", today).unwrap(), date(3, 4));
+        assert_eq!(resolve_date("3 weeks ago", today).unwrap(), date(2, 12));
+        assert!(resolve_date("-3y", today).is_err());
+    }
+
+    #[test]
+    fn test_date_ranges() {
+        let today = date(3, 5);
+        assert_eq!(
+            resolve_range("this week", today),
+            Some((date(3, 3), date(3, 9)))
+        );
+        assert_eq!(
+            resolve_range("last week", today),
+            Some((date(2, 24), date(3, 2)))
+        );
+        assert_eq!(
+            resolve_range("this month", today),
+            Some((date(3, 1), date(3, 31)))
+        );
+        assert_eq!(
+            resolve_range("last month", today),
+            Some((date(2, 1), date(2, 28)))
+        );
+        assert_eq!(
+            resolve_range("last year", today),
+            Some((
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()
+            ))
+        );
+        // Ranges only where a range is accepted
+        assert!(resolve_date("this week", today)
+            .unwrap_err()
+            .contains("range"));
+        assert_eq!(
+            parse_date_range("2025-03-01").unwrap(),
+            (date(3, 1), date(3, 1))
+        );
+        assert_eq!(
+            parse_date_range("2025-01-01..2025-03-31").unwrap(),
+            (date(1, 1), date(3, 31))
+        );
+        assert!(parse_date_range("2025-03-31..2025-01-01").is_err());
+    }
+
+    #[test]
+    fn test_date_time_parsing() {
+        let today = Local::now().date_naive();
+        let at = |h, m| Some(NaiveTime::from_hms_opt(h, m, 0).unwrap());
+        assert_eq!(
+            parse_date_time("today 18:30").unwrap(),
+            DateArg {
+                date: today,
+                time: at(18, 30)
+            }
+        );
+        assert_eq!(parse_date_time("7:05").unwrap().time, at(7, 5));
+        assert_eq!(
+            parse_date_time("2025-03-01 06:00").unwrap(),
+            DateArg {
+                date: date(3, 1),
+                time: at(6, 0)
+            }
+        );
+        assert_eq!(parse_date_time("yesterday").unwrap().time, None);
+        assert!(parse_date_time("today 25:00").is_err());
+        assert!(parse_date_shorthand("today 18:30").is_err());
+    }
+
+    #[test]
+    fn test_list_window_args() {
+        let cli = Cli::try_parse_from([
+            "ta",
+            "list",
+            "--from",
+            "last year",
+            "--to",
+            "2025-03-01",
+            "--offset",
+            "40",
+            "-n",
+            "20",
+            "--sort",
+            "volume",
+            "--reverse",
+        ])
+        .unwrap();
+        let Commands::List { limit, window, .. } = cli.command else {
+            panic!("expected the list command");
+        };
+        let last_year = Local::now().date_naive().year() - 1;
+        assert_eq!(window.from, NaiveDate::from_ymd_opt(last_year, 1, 1));
+        assert_eq!(window.to, Some(date(3, 1)));
+        assert_eq!((limit, window.offset), (Some(20), 40));
+        assert_eq!(window.sort, Some(ListSort::Volume));
+        assert!(window.is_active() && window.reverse);
+
+        assert_eq!(
+            parse_range_end("last year").unwrap(),
+            NaiveDate::from_ymd_opt(last_year, 12, 31).unwrap()
+        );
+        assert!(
+            Cli::try_parse_from(["ta", "list", "--date", "today", "--from", "monday"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_stats_args() {
+        let cli = Cli::try_parse_from([
+            "ta", "stats", "-e", "bench", "-e", "squat", "--since", "90d",
+        ])
+        .unwrap();
+        let Commands::Stats {
+            exercise, since, ..
+        } = cli.command
+        else {
+            panic!("expected the stats command");
+        };
+        assert_eq!(exercise, ["bench", "squat"]);
+        assert_eq!(since, Some(Local::now().date_naive() - Duration::days(90)));
+        assert!(Cli::try_parse_from(["ta", "stats"]).is_err());
+        assert!(Cli::try_parse_from(["ta", "stats", "--all", "-e", "bench"]).is_err());
+    }
+
+    #[test]
+    fn test_age_parsing() {
+        assert_eq!(parse_age("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_age("4w").unwrap(), Duration::days(28));
+        assert_eq!(parse_age("7").unwrap(), Duration::days(7));
+        assert!(parse_age("-3d").is_err());
+        assert!(parse_age("1y").is_err());
+        let today = Local::now().date_naive();
+        assert_eq!(parse_since("90d").unwrap(), today - Duration::days(90));
+        assert_eq!(
+            parse_since("2025-03-01").unwrap(),
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_timer_duration_parsing() {
+        assert_eq!(parse_timer_duration("90").unwrap(), Duration::seconds(90));
+        assert_eq!(parse_timer_duration("90s").unwrap(), Duration::seconds(90));
+        assert_eq!(parse_timer_duration("2m").unwrap(), Duration::seconds(120));
+        assert_eq!(
+            parse_timer_duration("1m30s").unwrap(),
+            Duration::seconds(90)
+        );
+        assert_eq!(parse_timer_duration("1M30").unwrap(), Duration::seconds(90));
+        assert!(parse_timer_duration("").is_err());
+        assert!(parse_timer_duration("m").is_err());
+        assert!(parse_timer_duration("-5s").is_err());
+        assert!(parse_timer_duration("2 min").is_err());
+    }
+
+    #[test]
+    fn test_scripted_add_answers() {
+        let cli = Cli::try_parse_from([
+            "ta",
+            "add",
+            "-e",
+            "Pull-ups",
+            "-r",
+            "10",
+            "--bodyweight",
+            "82.5",
+            "--pb-notify",
+            "no",
+            "--no-input",
+        ])
+        .unwrap();
+        assert!(cli.no_input);
+        assert!(!cli.interactive());
+        let Commands::Add { answers, .. } = cli.command else {
+            panic!("expected the add command");
+        };
+        assert_eq!(answers.bodyweight, Some(82.5));
+        assert_eq!(answers.pb_notify, Some(false));
+
+        assert!(Cli::try_parse_from(["ta", "add", "-e", "Squat", "--pb-notify", "maybe"]).is_err());
+    }
 }
@@ -1,15 +1,45 @@
 //! This module contains handler functions for each CLI subcommand.
 
-use crate::{cli, output}; // Use local modules
+use crate::aggregate;
+use crate::backup::{self, UnpackedBackup};
+use crate::calendar::{self, CalendarMetric};
+use crate::chart::{self, ChartMetric, ChartOptions};
+use crate::import::apps::{self, App, AppImportOptions};
+use crate::import::gps::{self, TrackFormat, TrackImportOptions};
+use crate::import::{self, native::CsvKind};
+use crate::journal::{self, Change, Journal, Setting};
+use crate::load::{self, LoadBand};
+use crate::muscles::{self, SetRange};
+use crate::pbs;
+use crate::profile;
+use crate::report::{self, ReportPeriod};
+use crate::serialize::{RepMax, StatsRecord};
+use crate::session::{format_duration, Session};
+use crate::sets::{self, SetEntry};
+use crate::shell;
+use crate::store::CliStore;
+use crate::strength::{self, E1rmFormula, E1rmPb};
+use crate::template::{format_prescription, parse_prescription, Template, TemplateItem};
+use crate::timer::{self, TimerKind};
+use crate::trash::{self, TrashedRecord};
+use crate::{cli, output, serialize}; // Use local modules
 use anyhow::{bail, Context, Result};
-use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone, Utc};
 use comfy_table::Color;
-use std::io::{stdin, stdout, Write};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{stdin, stdout, IsTerminal, Write};
+use std::path::PathBuf;
 use task_athlete_lib::{
     sync_client, AddWorkoutParams, AppService, ConfigError, DbError, EditWorkoutParams,
-    ExerciseType, SyncSummary, Units, VolumeFilters, WorkoutFilters,
+    ExerciseStats, ExerciseType, SyncSummary, Units, VolumeFilters, Workout, WorkoutFilters,
 };
 
+/// Entries shown by `ta list` without a limit, date or range.
+const DEFAULT_LIST_LIMIT: u32 = 20;
+
+/// Number of recent logged days shown in the `ta stats` sparkline.
+const STATS_TREND_POINTS: usize = 20;
+
 // --- Helper Functions ---
 
 /// Converts CLI ExerciseType enum to DB ExerciseType enum (from lib)
@@ -36,6 +66,36 @@ fn get_header_color(service: &AppService, default: Color) -> Color {
         .unwrap_or(default)
 }
 
+/// Returned when a command would have to prompt but input is disabled (`--no-input`
+/// or stdin is not a terminal). `ta` exits with a distinct code for it.
+#[derive(Debug)]
+pub struct InputRequired(pub String);
+
+impl std::fmt::Display for InputRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Input required: {}", self.0)
+    }
+}
+
+impl std::error::Error for InputRequired {}
+
+/// Logs a bodyweight given up front with `--bodyweight` instead of prompting for it.
+fn log_answered_bodyweight(service: &mut AppService, weight: f64) -> Result<f64> {
+    if weight <= 0.0 {
+        bail!("--bodyweight must be a positive number.");
+    }
+    match service.add_bodyweight_entry(Utc::now(), weight) {
+        Ok(_) => {
+            println!(
+                "Logging bodyweight: {:.2} {:?}",
+                weight, service.config.units
+            );
+            Ok(weight)
+        }
+        Err(e) => bail!("Error logging bodyweight: {}", e),
+    }
+}
+
 /// Prompts user for current bodyweight if config allows.
 /// Logs it via the service if entered.
 /// Returns Ok(Some(weight)) if logged, Ok(None) if cancelled or 'N' entered, Err on failure.
@@ -93,35 +153,70 @@ fn prompt_and_log_bodyweight_cli(service: &mut AppService) -> Result<Option<f64>
     }
 }
 
+/// Whether a PB is one the user wants to hear about, per metric.
+/// e1RM PBs are computed by the CLI and follow the weight notification setting.
+pub fn relevant_pb_achieved(
+    config: &task_athlete_lib::Config,
+    pb_info: Option<&task_athlete_lib::PBInfo>,
+    e1rm_pb: Option<&E1rmPb>,
+) -> bool {
+    pb_info.is_some_and(|pb_info| {
+        (pb_info.weight.achieved && config.pb_notifications.notify_weight)
+            || (pb_info.reps.achieved && config.pb_notifications.notify_reps)
+            || (pb_info.duration.achieved && config.pb_notifications.notify_duration)
+            || (pb_info.distance.achieved && config.pb_notifications.notify_distance)
+    }) || (e1rm_pb.is_some() && config.pb_notifications.notify_weight)
+}
+
+/// Whether PB notifications are enabled, asking (or taking `--pb-notify`) if they are
+/// not configured yet. `None` when they aren't configured and there is no answer.
+pub fn pb_notifications_enabled(
+    service: &mut AppService,
+    answer: Option<bool>,
+    interactive: bool,
+) -> Result<Option<bool>> {
+    match service.check_pb_notification_config() {
+        Ok(enabled) => Ok(Some(enabled)),
+        Err(ConfigError::PbNotificationNotSet) => match answer {
+            Some(enabled) => {
+                println!("You achieved a Personal Best!");
+                set_pb_notification_answer(service, enabled)?;
+                Ok(Some(enabled))
+            }
+            // Prompt needs mutable access
+            None if interactive => Ok(Some(prompt_and_set_pb_notification_cli(service)?)),
+            None => Ok(None),
+        },
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// Handles PB notification logic, including prompting if config not set.
 /// Needs mutable service to potentially update config via prompt.
+/// Runs after the workout is saved, so without input it only warns instead of failing.
 fn handle_pb_notification(
     service: &mut AppService,
-    pb_info: &task_athlete_lib::PBInfo,
+    pb_info: Option<&task_athlete_lib::PBInfo>,
+    e1rm_pb: Option<&E1rmPb>,
+    answer: Option<bool>,
+    interactive: bool,
 ) -> Result<()> {
-    let config = &service.config; // Immutable borrow first
-    let relevant_pb_achieved = (pb_info.weight.achieved && config.pb_notifications.notify_weight)
-        || (pb_info.reps.achieved && config.pb_notifications.notify_reps)
-        || (pb_info.duration.achieved && config.pb_notifications.notify_duration)
-        || (pb_info.distance.achieved && config.pb_notifications.notify_distance);
-
-    if !relevant_pb_achieved {
+    if !relevant_pb_achieved(&service.config, pb_info, e1rm_pb) {
         return Ok(());
     }
 
-    // Now check global setting, may need mutable borrow if prompt needed
-    let global_notifications_enabled = match service.check_pb_notification_config() {
-        Ok(enabled) => enabled,
-        Err(ConfigError::PbNotificationNotSet) => {
-            // Prompt needs mutable access
-            prompt_and_set_pb_notification_cli(service)?
-        }
-        Err(e) => return Err(e.into()),
-    };
-
-    if global_notifications_enabled {
+    match pb_notifications_enabled(service, answer, interactive)? {
         // Pass immutable config borrow to output function
-        output::print_pb_message_details(pb_info, service.config.units, &service.config);
+        Some(true) => output::print_pb_message_details(
+            pb_info,
+            e1rm_pb,
+            service.config.units,
+            &service.config,
+        ),
+        Some(false) => {}
+        None => eprintln!(
+            "Warning: The workout set a PB, but PB notifications are not configured. Pass --pb-notify yes|no or run 'ta set-pb-notification'."
+        ),
     }
     Ok(())
 }
@@ -137,12 +232,10 @@ fn prompt_and_set_pb_notification_cli(service: &mut AppService) -> Result<bool,
     let trimmed_input = input.trim();
 
     if trimmed_input.eq_ignore_ascii_case("y") {
-        println!("Okay, enabling future PB notifications.");
-        service.set_pb_notification_enabled(true)?;
+        set_pb_notification_answer(service, true)?;
         Ok(true)
     } else if trimmed_input.eq_ignore_ascii_case("n") {
-        println!("Okay, disabling future PB notifications.");
-        service.set_pb_notification_enabled(false)?;
+        set_pb_notification_answer(service, false)?;
         Ok(false)
     } else {
         println!("Invalid input. PB notifications remain unset for now.");
@@ -150,6 +243,195 @@ fn prompt_and_set_pb_notification_cli(service: &mut AppService) -> Result<bool,
     }
 }
 
+/// Saves the answer to the PB notification question, from the prompt or `--pb-notify`.
+fn set_pb_notification_answer(service: &mut AppService, enabled: bool) -> Result<(), ConfigError> {
+    if enabled {
+        println!("Okay, enabling future PB notifications.");
+    } else {
+        println!("Okay, disabling future PB notifications.");
+    }
+    service.set_pb_notification_enabled(enabled)
+}
+
+/// Best estimated 1RM across every logged set of an exercise.
+/// Only Resistance exercises have one; others yield `None`.
+fn exercise_best_e1rm(
+    service: &AppService,
+    store: &CliStore,
+    identifier: &str,
+    formula: E1rmFormula,
+) -> Result<Option<f64>> {
+    let Some(definition) = service.get_exercise_by_identifier_service(identifier)? else {
+        return Ok(None);
+    };
+    if definition.type_ != ExerciseType::Resistance {
+        return Ok(None);
+    }
+    let workouts = service.list_workouts(&WorkoutFilters {
+        exercise_name: Some(&definition.name),
+        date: None,
+        exercise_type: None,
+        muscle: None,
+        limit: None,
+    })?;
+    Ok(strength::best_e1rm(&workouts, &store.workout_sets, formula))
+}
+
+/// The sets a workout consists of: the logged ones, or one set from `reps`/`weight`.
+pub fn performed_sets(
+    reps: Option<i64>,
+    weight: Option<f64>,
+    logged: &[SetEntry],
+) -> Vec<SetEntry> {
+    if !logged.is_empty() {
+        return logged.to_vec();
+    }
+    reps.map(|reps| {
+        vec![SetEntry {
+            weight,
+            reps,
+            rpe: None,
+        }]
+    })
+    .unwrap_or_default()
+}
+
+/// The e1RM PB a workout about to be added sets, if any (Resistance exercises only).
+/// Reads the exercise's best so far, so it must run before the workout is added.
+pub fn check_e1rm_pb(
+    service: &AppService,
+    store: &CliStore,
+    identifier: &str,
+    existing: bool,
+    is_resistance: bool,
+    performed: &[SetEntry],
+) -> Result<Option<E1rmPb>> {
+    if !is_resistance {
+        return Ok(None);
+    }
+    let formula = store.settings.e1rm_formula;
+    let previous = if existing {
+        exercise_best_e1rm(service, store, identifier, formula)?
+    } else {
+        None
+    };
+    Ok(strength::best_set_e1rm(performed, formula)
+        .filter(|new| !previous.is_some_and(|prev| *new <= prev))
+        .map(|new_value| E1rmPb {
+            new_value,
+            previous_value: previous,
+            formula,
+        }))
+}
+
+/// Bookkeeping after the library added a workout, shared by `ta add` and the
+/// dashboard form: keeps its logged sets, attaches it to the open session (as a PB if
+/// `pb`), drops matching tombstones and records it in the undo journal, with the
+/// exercise if it was created for it. Returns the label of the session it was attached to.
+pub fn record_added_workout(
+    service: &AppService,
+    store: &mut CliStore,
+    id: i64,
+    pb: bool,
+    set_entries: Vec<SetEntry>,
+    created_exercise: Option<&str>,
+) -> Result<Option<String>> {
+    let mut store_changed = false;
+    if !set_entries.is_empty() {
+        store.workout_sets.insert(id, set_entries);
+        store_changed = true;
+    }
+    let session_label = store.active_session_mut().map(|session| {
+        session.workout_ids.push(id);
+        if pb {
+            session.pb_workout_ids.push(id);
+        }
+        session.label()
+    });
+
+    let mut changes = Vec::new();
+    if let Some(name) = created_exercise {
+        changes.push(Change::Exercise {
+            before: None,
+            after: journal::snapshot_exercise(service, name)?,
+        });
+    }
+    for after in journal::snapshot_workouts(service, store, &[id])? {
+        changes.push(Change::Workout {
+            before: None,
+            after: Some(after),
+        });
+    }
+    // A workout or exercise that was deleted before must survive the next sync
+    store_changed |= trash::revive(store, &changes);
+    if store_changed || session_label.is_some() {
+        store.save()?;
+    }
+    journal::record(service, changes)?;
+    Ok(session_label)
+}
+
+/// Returns true if any metric of a PB check was a new personal best.
+pub fn any_pb_achieved(pb_info: &task_athlete_lib::PBInfo) -> bool {
+    pb_info.weight.achieved
+        || pb_info.reps.achieved
+        || pb_info.duration.achieved
+        || pb_info.distance.achieved
+}
+
+/// Lists workouts matching `filters` whose ID is in `ids`, keeping the service's order.
+fn list_workouts_with_ids(
+    service: &AppService,
+    filters: &WorkoutFilters,
+    ids: &[i64],
+) -> Result<Vec<Workout>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut workouts = service.list_workouts(filters)?;
+    workouts.retain(|w| ids.contains(&w.id));
+    Ok(workouts)
+}
+
+/// Replaces the library's sets*reps*weight estimate with the actual volume of
+/// individually logged sets, for every workout in `volume_data` that has them.
+fn apply_logged_set_volume(
+    service: &AppService,
+    store: &CliStore,
+    filters: &VolumeFilters,
+    volume_data: &mut [(NaiveDate, String, f64)],
+) -> Result<()> {
+    if store.workout_sets.is_empty() {
+        return Ok(());
+    }
+
+    let dates: BTreeSet<NaiveDate> = volume_data.iter().map(|(date, _, _)| *date).collect();
+    for date in dates {
+        let workouts = service.list_workouts(&WorkoutFilters {
+            exercise_name: filters.exercise_name,
+            date: Some(date),
+            exercise_type: filters.exercise_type,
+            muscle: filters.muscle,
+            limit: None,
+        })?;
+        for workout in workouts {
+            let Some(logged) = store.workout_sets.get(&workout.id) else {
+                continue;
+            };
+            let estimated = sets::workout_volume(&workout, None);
+            let actual = sets::workout_volume(&workout, Some(logged));
+
+            if let Some(row) = volume_data
+                .iter_mut()
+                .find(|(d, name, _)| *d == date && *name == workout.exercise_name)
+            {
+                row.2 += actual - estimated;
+            }
+        }
+    }
+    Ok(())
+}
+
 // --- Command Handlers ---
 
 pub fn handle_create_exercise(
@@ -170,7 +452,14 @@ pub fn handle_create_exercise(
         ),
         Err(e) => bail!("Error creating exercise: {}", e),
     }
-    Ok(())
+    let after = journal::snapshot_exercise(service, name.trim())?;
+    trash::record_and_revive(
+        service,
+        vec![Change::Exercise {
+            before: None,
+            after,
+        }],
+    )
 }
 
 pub fn handle_edit_exercise(
@@ -187,6 +476,7 @@ pub fn handle_edit_exercise(
         Some(ref s) => Some(Some(s.trim())),
         None => None,
     };
+    let before = journal::snapshot_exercise(service, &identifier)?;
 
     match service.edit_exercise(
         &identifier,
@@ -206,35 +496,73 @@ pub fn handle_edit_exercise(
         }
         Err(e) => bail!("Error editing exercise '{}': {}", identifier, e),
     }
+    if let Some(before) = before {
+        let after = journal::snapshot_exercise(service, name.as_deref().unwrap_or(&before.name))?;
+        trash::record_and_revive(
+            service,
+            vec![Change::Exercise {
+                before: Some(before),
+                after,
+            }],
+        )?;
+    }
     Ok(())
 }
 
 pub fn handle_delete_exercise(service: &mut AppService, identifiers: Vec<String>) -> Result<()> {
+    let mut snapshots = Vec::new();
+    for identifier in &identifiers {
+        if let Some(snapshot) = journal::snapshot_exercise(service, identifier)? {
+            snapshots.push(snapshot);
+        }
+    }
+    backup_before(service, "delete-exercise")?;
     match service.delete_exercise(&identifiers) {
         Ok(rows) => println!("Successfully deleted exercise definition '{:?}' ({} row(s) affected). Associated aliases were also deleted.", identifiers, rows),
         Err(e) => bail!("Error deleting exercise: {}", e),
     }
-    Ok(())
+
+    let mut store = CliStore::load(service)?;
+    let mut changes = Vec::new();
+    for snapshot in snapshots {
+        changes.extend(trash::discard(
+            &mut store,
+            TrashedRecord::Exercise(snapshot),
+        ));
+    }
+    store.save()?;
+    println!("Moved to the trash; 'ta trash restore <ID>' brings it back with its aliases.");
+    journal::record(service, changes)
 }
 
 pub fn handle_add_workout(
     service: &mut AppService,
     exercise: String,
-    date_arg: NaiveDate,
+    date_arg: cli::DateArg,
     sets: Option<i64>,
     reps: Option<i64>,
     weight: Option<f64>,
+    set_entries: Vec<SetEntry>,
     duration: Option<i64>,
     distance: Option<f64>,
     notes: Option<String>,
     implicit_type: Option<cli::ExerciseTypeCli>,
     implicit_muscles: Option<String>,
+    answers: &cli::PromptAnswers,
+    interactive: bool,
 ) -> Result<()> {
     let identifier_trimmed = exercise.trim();
     if identifier_trimmed.is_empty() {
         bail!("Exercise identifier cannot be empty for adding a workout.");
     }
 
+    // Individually logged sets are summarised onto the workout row
+    let (sets, reps, weight) = if set_entries.is_empty() {
+        (sets, reps, weight)
+    } else {
+        sets::summarize(&set_entries)
+    };
+
     let mut bodyweight_to_use: Option<f64> = None;
     let mut needs_bw_check = false;
 
@@ -248,8 +576,8 @@ pub fn handle_add_workout(
     }
 
     if needs_bw_check {
-        match service.get_latest_bodyweight() {
-            Ok(Some(bw)) => {
+        match (service.get_latest_bodyweight(), answers.bodyweight) {
+            (Ok(Some(bw)), _) => {
                 bodyweight_to_use = Some(bw);
                 println!(
                     "Using latest logged bodyweight: {:.2} {:?} (+ {} additional)",
@@ -258,29 +586,64 @@ pub fn handle_add_workout(
                     weight.unwrap_or(0.0)
                 );
             }
-            Ok(None) if service.config.prompt_for_bodyweight => {
+            (Ok(None), Some(answered)) => {
+                bodyweight_to_use = Some(log_answered_bodyweight(service, answered)?);
+            }
+            (Ok(None), None) if service.config.prompt_for_bodyweight && !interactive => {
+                return Err(InputRequired(format!(
+                    "'{}' needs your bodyweight, but none is logged. Pass --bodyweight <WEIGHT>, log one with 'ta log-bodyweight' or disable the prompt.",
+                    identifier_trimmed
+                ))
+                .into());
+            }
+            (Ok(None), None) if service.config.prompt_for_bodyweight => {
                 match prompt_and_log_bodyweight_cli(service) {
                     Ok(maybe_logged_bw) => bodyweight_to_use = maybe_logged_bw.or(Some(0.0)), // Use logged or 0 if skipped
                     Err(e) => bail!("Cannot add bodyweight exercise: {}", e),
                 }
             }
-            Ok(None) => {
+            (Ok(None), None) => {
                 println!("Bodyweight prompting disabled or skipped. Using 0 base weight for this exercise.");
                 bodyweight_to_use = Some(0.0);
             }
-            Err(e) => bail!("Error checking bodyweight configuration: {}", e),
+            (Err(e), _) => bail!("Error checking bodyweight configuration: {}", e),
         }
     }
 
-    // Adjust date to include current time if 'today', otherwise use noon UTC
-    let timestamp = if Utc::now().date_naive() == date_arg {
-        Utc::now()
-    } else {
-        date_arg
+    // Use the given local time; otherwise the current time if today (local), or noon UTC
+    let timestamp = match date_arg.time {
+        Some(time) => Local
+            .from_local_datetime(&date_arg.date.and_time(time))
+            .earliest()
+            .map(|local| local.with_timezone(&Utc))
+            .with_context(|| {
+                format!(
+                    "{} {} does not exist in the local time zone",
+                    date_arg.date,
+                    time.format("%H:%M")
+                )
+            })?,
+        None if Local::now().date_naive() == date_arg.date => Utc::now(),
+        None => date_arg
+            .date
             .and_hms_opt(12, 0, 0)
             .map(|naive| Utc.from_utc_datetime(&naive))
-            .context("Internal error creating timestamp from date")?
+            .context("Internal error creating timestamp from date")?,
+    };
+
+    let mut store = CliStore::load(service)?;
+    let is_resistance = match &exercise_def_peek {
+        Some(def) => def.type_ == ExerciseType::Resistance,
+        None => implicit_type == Some(cli::ExerciseTypeCli::Resistance),
     };
+    let e1rm_pb = check_e1rm_pb(
+        service,
+        &store,
+        identifier_trimmed,
+        exercise_def_peek.is_some(),
+        is_resistance,
+        &performed_sets(reps, weight, &set_entries),
+    )?;
 
     let db_implicit_type = implicit_type.map(cli_type_to_db_type);
     let units = service.config.units; // Capture units before potential mutable borrow
@@ -312,10 +675,32 @@ pub fn handle_add_workout(
                 id
             );
 
-            if let Some(pb_info) = pb_info_opt {
-                // Needs mutable service reference for potential prompt
-                handle_pb_notification(service, &pb_info)?;
+            if !set_entries.is_empty() {
+                println!("Sets: {}", sets::format_sets(&set_entries));
+            }
+            let pb = pb_info_opt.as_ref().is_some_and(any_pb_achieved) || e1rm_pb.is_some();
+            let session_label = record_added_workout(
+                service,
+                &mut store,
+                id,
+                pb,
+                set_entries,
+                exercise_def_peek
+                    .is_none()
+                    .then_some(final_exercise_name.as_str()),
+            )?;
+            if let Some(label) = session_label {
+                println!("Attached to session {}.", label);
             }
+
+            // Needs mutable service reference for potential prompt
+            handle_pb_notification(
+                service,
+                pb_info_opt.as_ref(),
+                e1rm_pb.as_ref(),
+                answers.pb_notify,
+                interactive,
+            )?;
         }
         Err(e) => bail!("Error adding workout: {}", e),
     }
@@ -329,12 +714,21 @@ pub fn handle_edit_workout(
     sets: Option<i64>,
     reps: Option<i64>,
     weight: Option<f64>,
+    set_entries: Vec<SetEntry>,
     duration: Option<i64>,
     distance: Option<f64>,
     notes: Option<String>,
     date: Option<NaiveDate>,
     body_weight: Option<f64>,
 ) -> Result<()> {
+    let replaces_triple = sets.is_some() || reps.is_some() || weight.is_some();
+    let before = journal::snapshot_workouts(service, &CliStore::load(service)?, &[id])?;
+    let (sets, reps, weight) = if set_entries.is_empty() {
+        (sets, reps, weight)
+    } else {
+        sets::summarize(&set_entries)
+    };
+
     match service.edit_workout(EditWorkoutParams {
         id,
         new_exercise_identifier: exercise,
@@ -353,25 +747,100 @@ pub fn handle_edit_workout(
         ),
         Err(e) => bail!("Error editing workout ID {}: {}", id, e),
     }
+
+    let mut store = CliStore::load(service)?;
+    if !set_entries.is_empty() {
+        println!("Sets: {}", sets::format_sets(&set_entries));
+        store.workout_sets.insert(id, set_entries);
+        store.save()?;
+    } else if replaces_triple && store.workout_sets.remove(&id).is_some() {
+        println!("Note: Individually logged sets were replaced by the edited sets/reps/weight.");
+        store.save()?;
+    }
+
+    let after = journal::snapshot_workouts(service, &store, &[id])?;
+    if let (Some(before), Some(after)) = (before.into_iter().next(), after.into_iter().next()) {
+        trash::record_and_revive(
+            service,
+            vec![Change::Workout {
+                before: Some(before),
+                after: Some(after),
+            }],
+        )?;
+    }
     Ok(())
 }
 
 pub fn handle_delete_workout(service: &mut AppService, ids: Vec<i64>) -> Result<()> {
+    let mut store = CliStore::load(service)?;
+    let before = journal::snapshot_workouts(service, &store, &ids)?;
     match service.delete_workouts(&ids) {
-        Ok(deleted_ids) => println!(
-            "Successfully deleted workout ID(s) {:?} ({} row(s) affected).",
-            deleted_ids,
-            deleted_ids.len()
-        ),
+        Ok(deleted_ids) => {
+            println!(
+                "Successfully deleted workout ID(s) {:?} ({} row(s) affected).",
+                deleted_ids,
+                deleted_ids.len()
+            );
+            store.forget_workouts(&deleted_ids);
+            let mut changes = Vec::new();
+            for snapshot in before.into_iter().filter(|w| deleted_ids.contains(&w.id)) {
+                changes.extend(trash::discard(&mut store, TrashedRecord::Workout(snapshot)));
+            }
+            store.save()?;
+            println!("Moved to the trash; see 'ta trash list'.");
+            journal::record(service, changes)?;
+        }
         Err(e) => bail!("Error deleting workout(s): {}", e),
     }
     Ok(())
 }
 
+/// Applies `--from/--to`, `--sort`, `--offset` and the limit to listed workouts.
+/// Without a limit, the default one applies unless a date range or `bounded` (a single
+/// day or session) already keeps the list short.
+fn apply_list_window(
+    workouts: &mut Vec<Workout>,
+    window: &cli::ListWindow,
+    limit: Option<u32>,
+    bounded: bool,
+    store: &CliStore,
+) {
+    workouts.retain(|w| {
+        let day = w.timestamp.date_naive();
+        window.from.is_none_or(|from| day >= from) && window.to.is_none_or(|to| day <= to)
+    });
+
+    // Ties keep the newest first
+    workouts.sort_by_key(|w| std::cmp::Reverse(w.timestamp));
+    match window.sort.unwrap_or_default() {
+        cli::ListSort::Date => {}
+        cli::ListSort::Exercise => {
+            workouts.sort_by_key(|w| w.exercise_name.to_lowercase());
+        }
+        cli::ListSort::Weight => {
+            workouts.sort_by(|a, b| b.weight.unwrap_or(0.0).total_cmp(&a.weight.unwrap_or(0.0)))
+        }
+        cli::ListSort::Volume => {
+            let volume = |w: &Workout| sets::workout_volume(w, store.workout_sets.get(&w.id));
+            workouts.sort_by(|a, b| volume(b).total_cmp(&volume(a)));
+        }
+    }
+    if window.reverse {
+        workouts.reverse();
+    }
+
+    workouts.drain(..window.offset.min(workouts.len()));
+    let ranged = bounded || window.from.is_some() || window.to.is_some();
+    if let Some(limit) = limit.or((!ranged).then_some(DEFAULT_LIST_LIMIT)) {
+        workouts.truncate(limit as usize);
+    }
+}
+
 pub fn handle_list_workouts(
     service: &AppService, // Immutable borrow sufficient
-    export_csv: bool,
-    limit: u32,
+    format: cli::OutputFormat,
+    limit: Option<u32>,
+    window: &cli::ListWindow,
     today_flag: bool,
     yesterday_flag: bool,
     date: Option<NaiveDate>,
@@ -380,22 +849,38 @@ pub fn handle_list_workouts(
     muscle: Option<String>,
     nth_last_day_exercise: Option<String>,
     nth_last_day_n: Option<u32>,
+    session: Option<i64>,
 ) -> Result<()> {
     let effective_date = if today_flag {
-        Some(Utc::now().date_naive())
+        Some(Local::now().date_naive())
     } else if yesterday_flag {
-        Some((Utc::now() - Duration::days(1)).date_naive())
+        Some(Local::now().date_naive() - Duration::days(1))
     } else {
         date
     };
 
-    let workouts_result = if let Some(ex_ident) = nth_last_day_exercise {
+    let bounded = session.is_some() || nth_last_day_exercise.is_some() || effective_date.is_some();
+    let workouts_result = if let Some(session_id) = session {
+        let store = CliStore::load(service)?;
+        let session = store
+            .session(session_id)
+            .with_context(|| format!("Session #{} not found.", session_id))?;
+        let filters = WorkoutFilters {
+            exercise_name: exercise.as_deref(),
+            date: None,
+            exercise_type: type_.map(cli_type_to_db_type),
+            muscle: muscle.as_deref(),
+            limit: None,
+        };
+        list_workouts_with_ids(service, &filters, &session.workout_ids)
+    } else if let Some(ex_ident) = nth_last_day_exercise {
         let n = nth_last_day_n.context("Missing N value for --nth-last-day")?;
         service.list_workouts_for_exercise_on_nth_last_day(&ex_ident, n)
     } else {
         let db_type_filter = type_.map(cli_type_to_db_type);
-        let effective_limit = if effective_date.is_none() && nth_last_day_n.is_none() {
-            Some(limit)
+        // The window needs every match; it applies the limit itself
+        let effective_limit = if effective_date.is_none() && !window.is_active() {
+            Some(limit.unwrap_or(DEFAULT_LIST_LIMIT))
         } else {
             None
         };
@@ -410,17 +895,30 @@ pub fn handle_list_workouts(
         service.list_workouts(&filters)
     };
 
+    let workouts_result = workouts_result.and_then(|mut workouts| {
+        if window.is_active() {
+            let store = CliStore::load(service)?;
+            apply_list_window(&mut workouts, window, limit, bounded, &store);
+        }
+        Ok(workouts)
+    });
+
     match workouts_result {
+        Ok(workouts) if format != cli::OutputFormat::Table => {
+            serialize::write_records(&workouts, format, service.config.units)?;
+        }
         Ok(workouts) if workouts.is_empty() => {
             println!("No workouts found matching the criteria.");
         }
         Ok(workouts) => {
-            if export_csv {
-                output::print_workout_csv(workouts, service.config.units)?;
-            } else {
-                let header_color = get_header_color(service, Color::Green);
-                output::print_workout_table(workouts, header_color, service.config.units);
-            }
+            let store = CliStore::load(service)?;
+            let header_color = get_header_color(service, Color::Green);
+            output::print_workout_table(
+                workouts,
+                header_color,
+                service.config.units,
+                &store.workout_sets,
+            );
         }
         Err(e) => {
             if let Some(DbError::ExerciseNotFound(ident)) = e.downcast_ref::<DbError>() {
@@ -436,18 +934,137 @@ pub fn handle_list_workouts(
     Ok(())
 }
 
+/// Stats and best e1RM of an exercise, over all history or, with `since`, from that
+/// date on. Unknown exercises and missing data are reported as the library's `DbError`s.
+fn exercise_stats(
+    service: &AppService,
+    store: &CliStore,
+    identifier: &str,
+    since: Option<NaiveDate>,
+) -> Result<(ExerciseStats, Option<f64>)> {
+    let formula = store.settings.e1rm_formula;
+    let Some(since) = since else {
+        let stats = service.get_exercise_stats(identifier)?;
+        let best_e1rm = exercise_best_e1rm(service, store, identifier, formula)?;
+        return Ok((stats, best_e1rm));
+    };
+
+    // The library's stats always cover all history, so bounded ones are computed here
+    let Some(definition) = service.get_exercise_by_identifier_service(identifier)? else {
+        return Err(DbError::ExerciseNotFound(identifier.to_string()).into());
+    };
+    let today = Local::now().date_naive();
+    let workouts: Vec<Workout> = service
+        .list_workouts(&WorkoutFilters {
+            exercise_name: Some(&definition.name),
+            date: None,
+            exercise_type: None,
+            muscle: None,
+            limit: None,
+        })?
+        .into_iter()
+        .filter(|w| aggregate::in_range(w, (since, today)))
+        .collect();
+    if workouts.is_empty() {
+        return Err(DbError::NoWorkoutDataFound(definition.name).into());
+    }
+    let stats = aggregate::exercise_stats(
+        &definition.name,
+        &workouts,
+        &store.workout_sets,
+        service.config.streak_interval_days,
+        today,
+    );
+    let best_e1rm = if definition.type_ == ExerciseType::Resistance {
+        strength::best_e1rm(&workouts, &store.workout_sets, formula)
+    } else {
+        None
+    };
+    Ok((stats, best_e1rm))
+}
+
 pub fn handle_stats(
     service: &AppService, // Immutable borrow sufficient
-    export_csv: bool,
-    exercise: String,
+    format: cli::OutputFormat,
+    exercises: Vec<String>,
+    all: bool,
+    since: Option<NaiveDate>,
+    rm_table: bool,
 ) -> Result<()> {
-    match service.get_exercise_stats(&exercise) {
-        Ok(stats) => {
-            if export_csv {
-                output::print_stats_csv(&stats, service.config.units)?;
-            } else {
+    if all || exercises.len() > 1 {
+        if rm_table {
+            bail!("--rm-table needs a single exercise.");
+        }
+        return handle_stats_summary(service, format, exercises, all, since);
+    }
+    let Some(exercise) = exercises.into_iter().next() else {
+        bail!("Specify an exercise with --exercise, or use --all.");
+    };
+    let store = CliStore::load(service)?;
+    match exercise_stats(service, &store, &exercise, since) {
+        Ok((stats, best_e1rm)) => {
+            let formula = store.settings.e1rm_formula;
+            let units = service.config.units;
+
+            if format == cli::OutputFormat::Table {
+                let trend = match service.get_exercise_by_identifier_service(&exercise)? {
+                    Some(definition) => {
+                        let metric = default_chart_metric(definition.type_);
+                        let mut series =
+                            exercise_chart_series(service, &store, &definition.name, metric)?;
+                        if let Some(since) = since {
+                            series.retain(|(date, _)| *date >= since);
+                        }
+                        let points = last_points(series, STATS_TREND_POINTS);
+                        Some((
+                            metric,
+                            points.into_iter().map(|(_, v)| v).collect::<Vec<_>>(),
+                        ))
+                    }
+                    None => None,
+                };
                 // Pass immutable config borrow to output function
-                output::print_exercise_stats(&stats, service.config.units);
+                output::print_exercise_stats(
+                    &stats,
+                    since,
+                    units,
+                    best_e1rm.map(|e| (e, formula)),
+                    trend
+                        .as_ref()
+                        .map(|(metric, values)| (*metric, values.as_slice())),
+                );
+                if rm_table {
+                    match best_e1rm {
+                        Some(one_rm) => {
+                            let header_color = get_header_color(service, Color::Green);
+                            output::print_rep_max_table(
+                                &strength::rep_max_table(one_rm, formula),
+                                formula,
+                                units,
+                                header_color,
+                            );
+                        }
+                        None => println!(
+                            "No weighted sets logged for '{}'. Cannot estimate rep maxes.",
+                            stats.canonical_name
+                        ),
+                    }
+                }
+            } else if rm_table {
+                let rows: Vec<RepMax> = best_e1rm
+                    .map(|one_rm| strength::rep_max_table(one_rm, formula))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(reps, load)| RepMax { reps, load })
+                    .collect();
+                serialize::write_records(&rows, format, units)?;
+            } else {
+                let record = StatsRecord {
+                    stats: &stats,
+                    best_e1rm,
+                    formula,
+                };
+                serialize::write_record(&record, format, units)?;
             }
         }
         Err(e) => {
@@ -472,139 +1089,604 @@ pub fn handle_stats(
     Ok(())
 }
 
-pub fn handle_volume(
-    service: &AppService, // Immutable borrow sufficient
-    export_csv: bool,
-    exercise: Option<String>,
-    date: Option<NaiveDate>,
-    type_: Option<cli::ExerciseTypeCli>,
-    muscle: Option<String>,
-    limit_days: u32,
-    start_date: Option<NaiveDate>,
-    end_date: Option<NaiveDate>,
+/// `ta stats` for several exercises, or every exercise with workouts, one row each.
+fn handle_stats_summary(
+    service: &AppService,
+    format: cli::OutputFormat,
+    exercises: Vec<String>,
+    all: bool,
+    since: Option<NaiveDate>,
 ) -> Result<()> {
-    let (eff_start_date, eff_end_date) = if let Some(d) = date {
-        (Some(d), Some(d))
+    let identifiers = if all {
+        match service.list_exercises(None, None) {
+            Ok(definitions) => definitions.into_iter().map(|d| d.name).collect(),
+            Err(e) => bail!("Error listing exercises: {}", e),
+        }
     } else {
-        (start_date, end_date)
+        exercises
     };
+    let store = CliStore::load(service)?;
+    let mut rows = Vec::new();
+    for identifier in &identifiers {
+        match exercise_stats(service, &store, identifier, since) {
+            Ok(row) => rows.push(row),
+            Err(e) => match e.downcast_ref::<DbError>() {
+                // --all lists exercises that were never trained; skip those quietly
+                Some(DbError::NoWorkoutDataFound(_)) if all => {}
+                Some(DbError::NoWorkoutDataFound(name)) => {
+                    eprintln!("No workout data found for exercise '{name}'.")
+                }
+                Some(DbError::ExerciseNotFound(ident)) => {
+                    bail!("Exercise '{}' not found.", ident)
+                }
+                _ => bail!("Error getting exercise stats for '{}': {}", identifier, e),
+            },
+        }
+    }
+    if all {
+        rows.sort_by_key(|(stats, _)| std::cmp::Reverse(stats.last_workout_date));
+    }
 
-    let db_type_filter = type_.map(cli_type_to_db_type);
-    let effective_limit = if eff_start_date.is_none() && eff_end_date.is_none() {
-        Some(limit_days)
+    let units = service.config.units;
+    if format != cli::OutputFormat::Table {
+        let formula = store.settings.e1rm_formula;
+        let records: Vec<StatsRecord> = rows
+            .iter()
+            .map(|(stats, best_e1rm)| StatsRecord {
+                stats,
+                best_e1rm: *best_e1rm,
+                formula,
+            })
+            .collect();
+        serialize::write_records(&records, format, units)?;
+    } else if rows.is_empty() {
+        println!("No workout data found.");
     } else {
-        None
-    };
-
-    let filters = VolumeFilters {
-        exercise_name: exercise.as_deref(),
-        start_date: eff_start_date,
-        end_date: eff_end_date,
-        exercise_type: db_type_filter,
-        muscle: muscle.as_deref(),
-        limit_days: effective_limit,
-    };
-
-    match service.calculate_daily_volume(&filters) {
-        Ok(volume_data) if volume_data.is_empty() => {
-            println!("No volume data found matching the criteria.");
-            // Still print header if CSV requested
-            if export_csv {
-                output::print_volume_csv(volume_data, service.config.units)?;
-            }
-        }
-        Ok(volume_data) => {
-            if export_csv {
-                output::print_volume_csv(volume_data, service.config.units)?;
-            } else {
-                let header_color = get_header_color(service, Color::Yellow);
-                output::print_volume_table(volume_data, service.config.units, header_color);
-            }
+        if let Some(since) = since {
+            println!("Workouts since {}:", since);
         }
-        Err(e) => bail!("Error calculating workout volume: {}", e),
+        let header_color = get_header_color(service, Color::Cyan);
+        output::print_stats_table(&rows, units, header_color);
     }
     Ok(())
 }
 
-pub fn handle_list_exercises(
-    service: &AppService, // Immutable borrow sufficient
-    export_csv: bool,
-    type_: Option<cli::ExerciseTypeCli>,
-    muscles: Option<Vec<String>>,
-) -> Result<()> {
-    let db_type_filter = type_.map(cli_type_to_db_type);
+/// Metric charted when none is given: the one the exercise type is usually tracked by.
+fn default_chart_metric(exercise_type: ExerciseType) -> ChartMetric {
+    match exercise_type {
+        ExerciseType::Resistance => ChartMetric::Weight,
+        ExerciseType::BodyWeight => ChartMetric::Reps,
+        ExerciseType::Cardio => ChartMetric::Distance,
+    }
+}
 
-    // Convert Option<Vec<String>> to Option<Vec<&str>> for list_exercises
-    let muscle_refs: Option<Vec<&str>> = muscles
-        .as_ref()
-        .map(|m| m.iter().map(|s| s.as_str()).collect());
+/// Per-day values of `metric` for every workout of an exercise, oldest first.
+fn exercise_chart_series(
+    service: &AppService,
+    store: &CliStore,
+    exercise_name: &str,
+    metric: ChartMetric,
+) -> Result<Vec<(NaiveDate, f64)>> {
+    let workouts = service.list_workouts(&WorkoutFilters {
+        exercise_name: Some(exercise_name),
+        date: None,
+        exercise_type: None,
+        muscle: None,
+        limit: None,
+    })?;
+    Ok(chart::daily_series(
+        &workouts,
+        &store.workout_sets,
+        metric,
+        store.settings.e1rm_formula,
+        service.config.units,
+    ))
+}
 
-    match service.list_exercises(db_type_filter, muscle_refs) {
-        Ok(exercises) if exercises.is_empty() => {
-            println!("No exercise definitions found matching the criteria.");
-            if export_csv {
-                output::print_exercise_definition_csv(exercises)?; // Print header only
-            }
-        }
-        Ok(exercises) => {
-            if export_csv {
-                output::print_exercise_definition_csv(exercises)?;
-            } else {
-                let header_color = get_header_color(service, Color::Cyan);
-                output::print_exercise_definition_table(exercises, header_color);
-            }
-        }
-        Err(e) => bail!("Error listing exercises: {}", e),
+fn chart_options(args: &cli::ChartArgs, target: Option<f64>) -> ChartOptions {
+    ChartOptions {
+        width: args
+            .width
+            .map_or_else(chart::terminal_width, |w| w as usize),
+        height: args.height as usize,
+        style: args.style,
+        moving_average: args.moving_average.map(|n| n as usize),
+        target,
     }
-    Ok(())
 }
 
-pub fn handle_alias(
-    service: &mut AppService,
-    alias_name: String,
-    exercise_identifier: String,
+/// Keeps the last `limit` points of a series.
+fn last_points(mut points: Vec<(NaiveDate, f64)>, limit: usize) -> Vec<(NaiveDate, f64)> {
+    let skip = points.len().saturating_sub(limit);
+    points.drain(..skip);
+    points
+}
+
+pub fn handle_chart(
+    service: &AppService,
+    exercise: Option<String>,
+    metric: Option<ChartMetric>,
+    args: cli::ChartArgs,
 ) -> Result<()> {
-    match service.create_alias(&alias_name, &exercise_identifier) {
-        Ok(()) => println!(
-            "Successfully created alias '{}' for exercise '{}'.",
-            alias_name, exercise_identifier
-        ),
-        Err(e) => bail!("Error creating alias: {}", e),
+    let Some(identifier) = exercise else {
+        bail!("Specify an exercise with --exercise, or use `ta chart bodyweight`.");
+    };
+    let Some(definition) = service.get_exercise_by_identifier_service(&identifier)? else {
+        bail!("Exercise '{}' not found.", identifier);
+    };
+    let metric = metric.unwrap_or_else(|| default_chart_metric(definition.type_));
+    let store = CliStore::load(service)?;
+    let points = last_points(
+        exercise_chart_series(service, &store, &definition.name, metric)?,
+        args.limit,
+    );
+
+    if points.is_empty() {
+        println!("No {} data logged for '{}'.", metric, definition.name);
+        return Ok(());
     }
+    let series_label = match metric.unit(service.config.units) {
+        Some(unit) => format!("{metric} ({unit})"),
+        None => metric.to_string(),
+    };
+    output::print_chart(
+        &format!("{}: {}", definition.name, series_label),
+        &points,
+        &chart_options(&args, None),
+        &series_label,
+        service.config.units,
+    );
     Ok(())
 }
 
-pub fn handle_unalias(service: &mut AppService, alias_name: String) -> Result<()> {
-    match service.delete_alias(&alias_name) {
-        Ok(rows) => println!(
-            "Successfully deleted alias '{}' ({} row(s) affected).",
-            alias_name, rows
-        ),
-        Err(e) => bail!("Error deleting alias '{}': {}", alias_name, e),
+pub fn handle_chart_bodyweight(service: &AppService, args: cli::ChartArgs) -> Result<()> {
+    let limit = u32::try_from(args.limit).unwrap_or(u32::MAX);
+    let entries = match service.list_bodyweights(limit) {
+        Ok(entries) => entries,
+        Err(e) => bail!("Error listing bodyweights: {}", e),
+    };
+    if entries.is_empty() {
+        println!("No bodyweight entries found.");
+        return Ok(());
     }
+
+    let mut points: Vec<(NaiveDate, f64)> = entries
+        .iter()
+        .map(|(_, timestamp, weight)| (timestamp.with_timezone(&Local).date_naive(), *weight))
+        .collect();
+    points.sort_by_key(|(date, _)| *date);
+
+    let units = service.config.units;
+    let series_label = format!("bodyweight ({})", units.weight_abbr());
+    output::print_chart(
+        "Bodyweight",
+        &points,
+        &chart_options(&args, service.config.target_bodyweight),
+        &series_label,
+        units,
+    );
     Ok(())
 }
 
-pub fn handle_list_aliases(
-    service: &AppService, // Immutable borrow sufficient
-    export_csv: bool,
+pub fn handle_calendar(
+    service: &AppService,
+    year: Option<i32>,
+    exercise: Option<String>,
+    muscle: Option<String>,
+    metric: CalendarMetric,
+    ascii: bool,
 ) -> Result<()> {
-    match service.list_aliases() {
-        Ok(aliases) if aliases.is_empty() => {
-            if export_csv {
-                output::print_alias_csv(aliases)?; // Print header only
-            } else {
-                println!("No aliases defined.");
+    let today = Local::now().date_naive();
+    let year = year.unwrap_or(today.year());
+    let (Some(start), Some(end)) = (
+        NaiveDate::from_ymd_opt(year, 1, 1),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) else {
+        bail!("Invalid year: {}", year);
+    };
+
+    let workouts = match service.list_workouts(&WorkoutFilters {
+        exercise_name: exercise.as_deref(),
+        date: None,
+        exercise_type: None,
+        muscle: muscle.as_deref(),
+        limit: None,
+    }) {
+        Ok(workouts) => workouts,
+        Err(e) => bail!("Error listing workouts: {}", e),
+    };
+    let mut workouts_per_day: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for workout in &workouts {
+        let date = workout.timestamp.with_timezone(&Local).date_naive();
+        if (start..=end).contains(&date) {
+            *workouts_per_day.entry(date).or_default() += 1.0;
+        }
+    }
+
+    let values = match metric {
+        CalendarMetric::Workouts => workouts_per_day.clone(),
+        CalendarMetric::Volume => {
+            let filters = VolumeFilters {
+                exercise_name: exercise.as_deref(),
+                start_date: Some(start),
+                end_date: Some(end),
+                exercise_type: None,
+                muscle: muscle.as_deref(),
+                limit_days: None,
+            };
+            let mut volume_data = match service.calculate_daily_volume(&filters) {
+                Ok(data) => data,
+                Err(e) => bail!("Error calculating volume: {}", e),
+            };
+            let store = CliStore::load(service)?;
+            apply_logged_set_volume(service, &store, &filters, &mut volume_data)?;
+            let mut volume_per_day: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+            for (date, _, volume) in volume_data {
+                *volume_per_day.entry(date).or_default() += volume;
             }
+            volume_per_day
         }
-        Ok(aliases) => {
-            if export_csv {
-                output::print_alias_csv(aliases)?;
-            } else {
-                let header_color = get_header_color(service, Color::Magenta);
-                output::print_alias_table(aliases, header_color);
+    };
+
+    // Streaks count training days, whatever the shading represents
+    let interval_days = service.config.streak_interval_days;
+    let active_days: BTreeSet<NaiveDate> = workouts_per_day.keys().copied().collect();
+    let streaks = calendar::find_streaks(&active_days, interval_days, today);
+
+    let color_available =
+        !ascii && std::env::var_os("NO_COLOR").is_none() && stdout().is_terminal();
+    let color = color_available.then(|| get_header_color(service, Color::Green));
+    output::print_calendar(year, &values, metric, streaks, interval_days, color);
+    Ok(())
+}
+
+pub fn handle_import_csv(
+    service: &mut AppService,
+    file: PathBuf,
+    kind: CsvKind,
+    dry_run: bool,
+) -> Result<()> {
+    if !dry_run {
+        backup_before(service, "import")?;
+    }
+    match import::native::import_csv(service, &file, kind, dry_run) {
+        Ok(report) => {
+            output::print_import_report(&report);
+            trash::record_and_revive(service, report.changes)?;
+        }
+        Err(e) => bail!("Error importing {:?}: {}", file, e),
+    }
+    Ok(())
+}
+
+/// Asks whether an exercise the database doesn't know should be logged as an existing
+/// one. Returns the exercise to map it to, or `None` to create it.
+fn prompt_exercise_mapping(
+    service: &AppService,
+    name: &str,
+    guessed: ExerciseType,
+) -> Result<Option<String>> {
+    loop {
+        println!("\nExercise '{}' is not defined.", name);
+        print!(
+            "Enter an existing exercise or alias to log it as, or press Enter to create it ({}): ",
+            guessed
+        );
+        stdout().flush()?;
+
+        let mut input = String::new();
+        if stdin().read_line(&mut input)? == 0 {
+            return Ok(None); // EOF
+        }
+        let target = input.trim();
+        if target.is_empty() {
+            return Ok(None);
+        }
+        match service.get_exercise_by_identifier_service(target)? {
+            Some(def) => {
+                println!("Logging '{}' as '{}'.", name, def.name);
+                return Ok(Some(def.name));
+            }
+            None => println!("Exercise '{}' not found. Try again.", target),
+        }
+    }
+}
+
+pub fn handle_import_app(
+    service: &mut AppService,
+    app: App,
+    args: cli::AppImportArgs,
+    interactive: bool,
+) -> Result<()> {
+    let units = service.config.units;
+    let (default_weight_unit, default_distance_unit) = match units {
+        Units::Metric => ("kg", "km"),
+        Units::Imperial => ("lbs", "mi"),
+    };
+    let options = AppImportOptions {
+        app,
+        weight_unit: args.weight_unit.as_deref().unwrap_or(default_weight_unit),
+        distance_unit: args
+            .distance_unit
+            .as_deref()
+            .unwrap_or(default_distance_unit),
+    };
+    let export = match apps::read_export(&args.file, &options, units) {
+        Ok(export) => export,
+        Err(e) => bail!("Error reading {} export {:?}: {}", app, args.file, e),
+    };
+
+    // Only ask about unknown exercises when someone is there to answer
+    let mut mappings: HashMap<String, String> = args.mappings.into_iter().collect();
+    if !args.create_missing && interactive {
+        for (name, guessed) in export.unknown_exercises(service, &mappings)? {
+            if let Some(target) = prompt_exercise_mapping(service, &name, guessed)? {
+                mappings.insert(name, target);
             }
         }
+    }
+
+    if !args.dry_run {
+        backup_before(service, "import")?;
+    }
+    match apps::import_export(service, export, &mappings, args.dry_run) {
+        Ok(report) => {
+            output::print_import_report(&report);
+            trash::record_and_revive(service, report.changes)?;
+        }
+        Err(e) => bail!("Error importing {} export {:?}: {}", app, args.file, e),
+    }
+    Ok(())
+}
+
+pub fn handle_import_tracks(
+    service: &mut AppService,
+    format: TrackFormat,
+    args: cli::TrackImportArgs,
+) -> Result<()> {
+    let options = TrackImportOptions {
+        format,
+        exercise: &args.exercise,
+        notes: args.notes.as_deref(),
+        dry_run: args.dry_run,
+    };
+    if !args.dry_run {
+        backup_before(service, "import")?;
+    }
+    match gps::import_tracks(service, &args.path, &options) {
+        Ok(report) => {
+            output::print_import_report(&report);
+            trash::record_and_revive(service, report.changes)?;
+        }
+        Err(e) => bail!("Error importing {} from {:?}: {}", format, args.path, e),
+    }
+    Ok(())
+}
+
+pub fn handle_volume(
+    service: &AppService, // Immutable borrow sufficient
+    format: cli::OutputFormat,
+    exercise: Option<String>,
+    date: Option<(NaiveDate, NaiveDate)>,
+    type_: Option<cli::ExerciseTypeCli>,
+    muscle: Option<String>,
+    limit_days: u32,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+) -> Result<()> {
+    // --date is a single day or a range such as "this week"
+    let (eff_start_date, eff_end_date) = if let Some((start, end)) = date {
+        (Some(start), Some(end))
+    } else {
+        (start_date, end_date)
+    };
+
+    let db_type_filter = type_.map(cli_type_to_db_type);
+    let effective_limit = if eff_start_date.is_none() && eff_end_date.is_none() {
+        Some(limit_days)
+    } else {
+        None
+    };
+
+    let filters = VolumeFilters {
+        exercise_name: exercise.as_deref(),
+        start_date: eff_start_date,
+        end_date: eff_end_date,
+        exercise_type: db_type_filter,
+        muscle: muscle.as_deref(),
+        limit_days: effective_limit,
+    };
+
+    let mut volume_data = match service.calculate_daily_volume(&filters) {
+        Ok(volume_data) => volume_data,
+        Err(e) => bail!("Error calculating workout volume: {}", e),
+    };
+    let store = CliStore::load(service)?;
+    apply_logged_set_volume(service, &store, &filters, &mut volume_data)?;
+
+    if format != cli::OutputFormat::Table {
+        // Machine formats still emit headers / an empty collection
+        serialize::write_records(&volume_data, format, service.config.units)?;
+    } else if volume_data.is_empty() {
+        println!("No volume data found matching the criteria.");
+    } else {
+        let header_color = get_header_color(service, Color::Yellow);
+        output::print_volume_table(volume_data, service.config.units, header_color);
+    }
+    Ok(())
+}
+
+pub fn handle_compare(
+    service: &AppService,
+    format: cli::OutputFormat,
+    from: (NaiveDate, NaiveDate),
+    to: (NaiveDate, NaiveDate),
+    exercise: Option<String>,
+    type_: Option<cli::ExerciseTypeCli>,
+    muscle: Option<String>,
+) -> Result<()> {
+    let workouts = match service.list_workouts(&WorkoutFilters {
+        exercise_name: exercise.as_deref(),
+        date: None,
+        exercise_type: type_.map(cli_type_to_db_type),
+        muscle: muscle.as_deref(),
+        limit: None,
+    }) {
+        Ok(workouts) => workouts,
+        Err(e) => bail!("Error listing workouts: {}", e),
+    };
+    let store = CliStore::load(service)?;
+    let formula = store.settings.e1rm_formula;
+    let first = aggregate::summarize(&workouts, from, &store.workout_sets, formula);
+    let second = aggregate::summarize(&workouts, to, &store.workout_sets, formula);
+    let units = service.config.units;
+    let rows = aggregate::compare(&first, &second, units);
+
+    if format != cli::OutputFormat::Table {
+        serialize::write_records(&rows, format, units)?;
+    } else {
+        let label = |(start, end): (NaiveDate, NaiveDate)| format!("{start} to {end}");
+        let header_color = get_header_color(service, Color::Yellow);
+        output::print_compare_table(&rows, &label(from), &label(to), units, header_color);
+    }
+    Ok(())
+}
+
+pub fn handle_report(
+    service: &AppService,
+    format: cli::OutputFormat,
+    period: ReportPeriod,
+    periods: u32,
+    exercise: Option<String>,
+    type_: Option<cli::ExerciseTypeCli>,
+    muscle: Option<String>,
+) -> Result<()> {
+    // PBs need each exercise's whole history, so no date filter here
+    let filters = WorkoutFilters {
+        exercise_name: exercise.as_deref(),
+        date: None,
+        exercise_type: type_.map(cli_type_to_db_type),
+        muscle: muscle.as_deref(),
+        limit: None,
+    };
+    let mut workouts = match service.list_workouts(&filters) {
+        Ok(workouts) => workouts,
+        Err(e) => bail!("Error listing workouts: {}", e),
+    };
+    workouts.sort_by_key(|w| w.timestamp);
+    let store = CliStore::load(service)?;
+    let pb_events = pbs::pb_events(&pbs::history(&workouts));
+    let reports = report::build(
+        period,
+        periods as usize,
+        &workouts,
+        &store,
+        &pb_events,
+        Local::now().date_naive(),
+    );
+
+    if format != cli::OutputFormat::Table {
+        serialize::write_records(&reports, format, service.config.units)?;
+    } else {
+        let header_color = get_header_color(service, Color::Yellow);
+        output::print_report_table(&reports, service.config.units, header_color);
+    }
+    Ok(())
+}
+
+pub fn handle_list_exercises(
+    service: &AppService, // Immutable borrow sufficient
+    format: cli::OutputFormat,
+    type_: Option<cli::ExerciseTypeCli>,
+    muscles: Option<Vec<String>>,
+) -> Result<()> {
+    let db_type_filter = type_.map(cli_type_to_db_type);
+
+    // Convert Option<Vec<String>> to Option<Vec<&str>> for list_exercises
+    let muscle_refs: Option<Vec<&str>> = muscles
+        .as_ref()
+        .map(|m| m.iter().map(|s| s.as_str()).collect());
+
+    match service.list_exercises(db_type_filter, muscle_refs) {
+        Ok(exercises) if format != cli::OutputFormat::Table => {
+            serialize::write_records(&exercises, format, service.config.units)?;
+        }
+        Ok(exercises) if exercises.is_empty() => {
+            println!("No exercise definitions found matching the criteria.");
+        }
+        Ok(exercises) => {
+            let header_color = get_header_color(service, Color::Cyan);
+            output::print_exercise_definition_table(exercises, header_color);
+        }
+        Err(e) => bail!("Error listing exercises: {}", e),
+    }
+    Ok(())
+}
+
+pub fn handle_alias(
+    service: &mut AppService,
+    alias_name: String,
+    exercise_identifier: String,
+) -> Result<()> {
+    match service.create_alias(&alias_name, &exercise_identifier) {
+        Ok(()) => println!(
+            "Successfully created alias '{}' for exercise '{}'.",
+            alias_name, exercise_identifier
+        ),
+        Err(e) => bail!("Error creating alias: {}", e),
+    }
+    let exercise = service
+        .get_exercise_by_identifier_service(&exercise_identifier)?
+        .map_or(exercise_identifier, |def| def.name);
+    journal::record(
+        service,
+        vec![Change::Alias {
+            alias: alias_name,
+            before: None,
+            after: Some(exercise),
+        }],
+    )
+}
+
+pub fn handle_unalias(service: &mut AppService, alias_name: String) -> Result<()> {
+    let before = service.list_aliases()?.remove(&alias_name);
+    match service.delete_alias(&alias_name) {
+        Ok(rows) => println!(
+            "Successfully deleted alias '{}' ({} row(s) affected).",
+            alias_name, rows
+        ),
+        Err(e) => bail!("Error deleting alias '{}': {}", alias_name, e),
+    }
+    if before.is_some() {
+        journal::record(
+            service,
+            vec![Change::Alias {
+                alias: alias_name,
+                before,
+                after: None,
+            }],
+        )?;
+    }
+    Ok(())
+}
+
+pub fn handle_list_aliases(
+    service: &AppService, // Immutable borrow sufficient
+    format: cli::OutputFormat,
+) -> Result<()> {
+    match service.list_aliases() {
+        Ok(aliases) if format != cli::OutputFormat::Table => {
+            let mut sorted_aliases: Vec<(String, String)> = aliases.into_iter().collect();
+            sorted_aliases.sort_by(|a, b| a.0.cmp(&b.0));
+            serialize::write_records(&sorted_aliases, format, service.config.units)?;
+        }
+        Ok(aliases) if aliases.is_empty() => {
+            println!("No aliases defined.");
+        }
+        Ok(aliases) => {
+            let header_color = get_header_color(service, Color::Magenta);
+            output::print_alias_table(aliases, header_color);
+        }
         Err(e) => bail!("Error listing aliases: {}", e),
     }
     Ok(())
@@ -612,6 +1694,7 @@ pub fn handle_list_aliases(
 
 pub fn handle_set_units(service: &mut AppService, units: cli::UnitsCli) -> Result<()> {
     let db_units = cli_units_to_db_units(units);
+    let before = Setting::Units.current(service)?;
     match service.set_units(db_units) {
         Ok(()) => {
             println!("Successfully set default units to: {:?}", db_units);
@@ -619,7 +1702,7 @@ pub fn handle_set_units(service: &mut AppService, units: cli::UnitsCli) -> Resul
         }
         Err(e) => bail!("Error setting units: {}", e),
     }
-    Ok(())
+    journal::record_setting(service, Setting::Units, before)
 }
 
 pub fn handle_log_bodyweight(service: &mut AppService, weight: f64, date: NaiveDate) -> Result<()> {
@@ -629,13 +1712,26 @@ pub fn handle_log_bodyweight(service: &mut AppService, weight: f64, date: NaiveD
         .context("Internal error creating timestamp from date")?;
 
     match service.add_bodyweight_entry(timestamp, weight) {
-        Ok(id) => println!(
-            "Successfully logged bodyweight {} {:?} on {} (ID: {})",
-            weight,
-            service.config.units,
-            date.format("%Y-%m-%d"),
-            id
-        ),
+        Ok(id) => {
+            println!(
+                "Successfully logged bodyweight {} {:?} on {} (ID: {})",
+                weight,
+                service.config.units,
+                date.format("%Y-%m-%d"),
+                id
+            );
+            trash::record_and_revive(
+                service,
+                vec![Change::Bodyweight {
+                    before: None,
+                    after: Some(journal::BodyweightSnapshot {
+                        id,
+                        timestamp,
+                        weight,
+                    }),
+                }],
+            )?;
+        }
         Err(e) => bail!("Error logging bodyweight: {}", e),
     }
     Ok(())
@@ -643,23 +1739,19 @@ pub fn handle_log_bodyweight(service: &mut AppService, weight: f64, date: NaiveD
 
 pub fn handle_list_bodyweights(
     service: &AppService, // Immutable borrow sufficient
-    export_csv: bool,
+    format: cli::OutputFormat,
     limit: u32,
 ) -> Result<()> {
     match service.list_bodyweights(limit) {
+        Ok(entries) if format != cli::OutputFormat::Table => {
+            serialize::write_records(&entries, format, service.config.units)?;
+        }
         Ok(entries) if entries.is_empty() => {
             println!("No bodyweight entries found.");
-            if export_csv {
-                output::print_bodyweight_csv(entries, service.config.units)?; // Print header only
-            }
         }
         Ok(entries) => {
-            if export_csv {
-                output::print_bodyweight_csv(entries, service.config.units)?;
-            } else {
-                let header_color = get_header_color(service, Color::Blue);
-                output::print_bodyweight_table(&entries, service.config.units, header_color);
-            }
+            let header_color = get_header_color(service, Color::Blue);
+            output::print_bodyweight_table(&entries, service.config.units, header_color);
         }
         Err(e) => bail!("Error listing bodyweights: {}", e),
     }
@@ -667,14 +1759,31 @@ pub fn handle_list_bodyweights(
 }
 
 pub fn handle_delete_bodyweight(service: &mut AppService, id: i64) -> Result<()> {
+    let before = service
+        .list_bodyweights(u32::MAX)?
+        .into_iter()
+        .find(|(entry_id, _, _)| *entry_id == id)
+        .map(|(id, timestamp, weight)| journal::BodyweightSnapshot {
+            id,
+            timestamp,
+            weight,
+        });
     match service.delete_bodyweight(id) {
         Ok(deleted_id) => println!("Successfully deleted body weight entry {deleted_id}"),
         Err(e) => bail!("Error deleting body weight entry: {}", e),
     }
+    if let Some(before) = before {
+        let mut store = CliStore::load(service)?;
+        let changes = trash::discard(&mut store, TrashedRecord::Bodyweight(before));
+        store.save()?;
+        println!("Moved to the trash; see 'ta trash list'.");
+        journal::record(service, changes)?;
+    }
     Ok(())
 }
 
 pub fn handle_set_target_weight(service: &mut AppService, weight: f64) -> Result<()> {
+    let before = Setting::TargetBodyweight.current(service)?;
     match service.set_target_bodyweight(Some(weight)) {
         Ok(()) => println!(
             "Successfully set target bodyweight to {} {:?}. Config updated.",
@@ -682,18 +1791,20 @@ pub fn handle_set_target_weight(service: &mut AppService, weight: f64) -> Result
         ),
         Err(e) => bail!("Error setting target bodyweight: {}", e),
     }
-    Ok(())
+    journal::record_setting(service, Setting::TargetBodyweight, before)
 }
 
 pub fn handle_clear_target_weight(service: &mut AppService) -> Result<()> {
+    let before = Setting::TargetBodyweight.current(service)?;
     match service.set_target_bodyweight(None) {
         Ok(()) => println!("Target bodyweight cleared. Config updated."),
         Err(e) => bail!("Error clearing target bodyweight: {}", e),
     }
-    Ok(())
+    journal::record_setting(service, Setting::TargetBodyweight, before)
 }
 
 pub fn handle_set_pb_notification(service: &mut AppService, enabled: bool) -> Result<()> {
+    let before = Setting::PbNotifications.current(service)?;
     match service.set_pb_notification_enabled(enabled) {
         Ok(()) => {
             println!(
@@ -704,15 +1815,17 @@ pub fn handle_set_pb_notification(service: &mut AppService, enabled: bool) -> Re
         }
         Err(e) => bail!("Error updating global PB notification setting: {}", e),
     }
-    Ok(())
+    journal::record_setting(service, Setting::PbNotifications, before)
 }
 
 pub fn handle_set_pb_notify_metric(
     service: &mut AppService,
     metric: &str,
+    setting: Setting,
     enabled: bool,
     setter: impl FnOnce(&mut AppService, bool) -> Result<(), ConfigError>,
 ) -> Result<()> {
+    let before = setting.current(service)?;
     match setter(service, enabled) {
         Ok(()) => println!(
             "Set {} PB notification to: {}. Config updated.",
@@ -720,14 +1833,1081 @@ pub fn handle_set_pb_notify_metric(
         ),
         Err(e) => bail!("Error setting {} PB notification: {}", metric, e),
     }
+    journal::record_setting(service, setting, before)
+}
+
+pub fn handle_set_e1rm_formula(service: &AppService, formula: E1rmFormula) -> Result<()> {
+    let before = Setting::E1rmFormula.current(service)?;
+    let mut store = CliStore::load(service)?;
+    store.settings.e1rm_formula = formula;
+    store.save()?;
+    println!("Set estimated 1RM formula to {}.", formula);
+    journal::record_setting(service, Setting::E1rmFormula, before)
+}
+
+/// Sets or (with `range` None) removes a muscle's weekly hard-set target.
+pub fn handle_set_muscle_target(
+    service: &AppService,
+    muscle: String,
+    range: Option<SetRange>,
+) -> Result<()> {
+    let muscle = muscle.trim().to_lowercase();
+    if muscle.is_empty() {
+        bail!("Muscle name cannot be empty.");
+    }
+    let before = Setting::MuscleTargets.current(service)?;
+    let mut store = CliStore::load(service)?;
+    match range {
+        Some(range) => {
+            store.settings.muscle_targets.insert(muscle.clone(), range);
+            println!(
+                "Set the weekly hard-set target for {} to {}.",
+                muscle, range
+            );
+        }
+        None => {
+            if store.settings.muscle_targets.remove(&muscle).is_none() {
+                println!("{} has no target of its own.", muscle);
+                return Ok(());
+            }
+            println!("Removed the target for {}.", muscle);
+        }
+    }
+    store.save()?;
+    journal::record_setting(service, Setting::MuscleTargets, before)
+}
+
+pub fn handle_muscles(service: &AppService, format: cli::OutputFormat, weeks: u32) -> Result<()> {
+    let exercises = match service.list_exercises(None, None) {
+        Ok(exercises) => exercises,
+        Err(e) => bail!("Error listing exercises: {}", e),
+    };
+    let muscles_of: HashMap<String, Vec<String>> = exercises
+        .iter()
+        .map(|e| {
+            (
+                e.name.to_lowercase(),
+                muscles::parse_muscles(e.muscles.as_deref()),
+            )
+        })
+        .collect();
+    let known: BTreeSet<String> = muscles_of.values().flatten().cloned().collect();
+
+    let workouts = match service.list_workouts(&WorkoutFilters {
+        exercise_name: None,
+        date: None,
+        exercise_type: None,
+        muscle: None,
+        limit: None,
+    }) {
+        Ok(workouts) => workouts,
+        Err(e) => bail!("Error listing workouts: {}", e),
+    };
+    let today = Local::now().date_naive();
+    let first_day = today - Duration::days(i64::from(weeks) * 7 - 1);
+    let store = CliStore::load(service)?;
+    let entries: Vec<muscles::Attributed> = workouts
+        .iter()
+        .filter(|w| w.exercise_type != Some(ExerciseType::Cardio))
+        .filter(|w| w.timestamp.with_timezone(&Local).date_naive() >= first_day)
+        .filter_map(|w| {
+            let muscles = muscles_of.get(&w.exercise_name.to_lowercase())?.clone();
+            let logged = store.workout_sets.get(&w.id);
+            Some(muscles::Attributed {
+                muscles,
+                hard_sets: muscles::hard_sets(&sets::sets_for_workout(w, logged)),
+                volume: sets::workout_volume(w, logged),
+            })
+        })
+        .collect();
+
+    let targets = &store.settings.muscle_targets;
+    let default_target = targets
+        .get(muscles::DEFAULT_TARGET_KEY)
+        .copied()
+        .unwrap_or(muscles::DEFAULT_TARGET);
+    let rows = muscles::rows(&entries, &known, weeks, targets, default_target);
+
+    if format != cli::OutputFormat::Table {
+        serialize::write_records(&rows, format, service.config.units)?;
+        return Ok(());
+    }
+    if rows.is_empty() {
+        println!("No muscles listed on any exercise. Add them with 'ta edit-exercise --muscles'.");
+        return Ok(());
+    }
+    let header_color = get_header_color(service, Color::Cyan);
+    output::print_muscle_table(&rows, weeks, service.config.units, header_color);
+    for balance in muscles::balances(&entries) {
+        println!(
+            "{}/{}: {} vs {} hard sets{}",
+            balance.first,
+            balance.second,
+            balance.first_sets,
+            balance.second_sets,
+            if balance.is_imbalanced() {
+                " - imbalanced"
+            } else {
+                ""
+            }
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_set_load_band(service: &AppService, band: LoadBand) -> Result<()> {
+    let before = Setting::LoadBand.current(service)?;
+    let mut store = CliStore::load(service)?;
+    store.settings.load_band = band;
+    store.save()?;
+    println!(
+        "Set the safe acute:chronic workload ratio band to {}.",
+        band
+    );
+    journal::record_setting(service, Setting::LoadBand, before)
+}
+
+pub fn handle_load(
+    service: &AppService,
+    format: cli::OutputFormat,
+    exercise: Option<String>,
+    type_: Option<cli::ExerciseTypeCli>,
+    muscle: Option<String>,
+    days: u32,
+    intensity: f64,
+) -> Result<()> {
+    // Dates follow the volume calculation; the first row needs 27 days of history
+    let today = Utc::now().date_naive();
+    let start = today - Duration::days(i64::from(days) - 1 + load::CHRONIC_DAYS - 1);
+    let db_type_filter = type_.map(cli_type_to_db_type);
+    let filters = VolumeFilters {
+        exercise_name: exercise.as_deref(),
+        start_date: Some(start),
+        end_date: Some(today),
+        exercise_type: db_type_filter,
+        muscle: muscle.as_deref(),
+        limit_days: None,
+    };
+
+    let mut volume_data = match service.calculate_daily_volume(&filters) {
+        Ok(volume_data) => volume_data,
+        Err(e) => bail!("Error calculating workout volume: {}", e),
+    };
+    let store = CliStore::load(service)?;
+    apply_logged_set_volume(service, &store, &filters, &mut volume_data)?;
+    let mut daily: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for (date, _, volume) in volume_data {
+        *daily.entry(date).or_default() += volume;
+    }
+
+    if db_type_filter.is_none_or(|t| t == ExerciseType::Cardio) {
+        let cardio = match service.list_workouts(&WorkoutFilters {
+            exercise_name: filters.exercise_name,
+            date: None,
+            exercise_type: Some(ExerciseType::Cardio),
+            muscle: filters.muscle,
+            limit: None,
+        }) {
+            Ok(workouts) => workouts,
+            Err(e) => bail!("Error listing workouts: {}", e),
+        };
+        for workout in cardio {
+            let date = workout.timestamp.date_naive();
+            if (start..=today).contains(&date) {
+                *daily.entry(date).or_default() +=
+                    load::cardio_load(workout.duration_minutes.unwrap_or(0), intensity);
+            }
+        }
+    }
+
+    let band = store.settings.load_band;
+    let rows = load::days(&daily, today, days, band);
+    if format != cli::OutputFormat::Table {
+        serialize::write_records(&rows, format, service.config.units)?;
+        return Ok(());
+    }
+    if daily.is_empty() {
+        println!(
+            "No workouts found matching the criteria in the last {} days.",
+            days
+        );
+        return Ok(());
+    }
+    let header_color = get_header_color(service, Color::Yellow);
+    output::print_load_table(&rows, header_color);
+    println!(
+        "Safe ratio band: {} (change it with 'ta set-load-band'). Cardio load is minutes x RPE {}.",
+        band, intensity
+    );
     Ok(())
 }
 
 pub fn handle_set_streak_interval(service: &mut AppService, days: u32) -> Result<()> {
+    let before = Setting::StreakInterval.current(service)?;
     match service.set_streak_interval(days) {
         Ok(()) => println!("Set streak interval to {} day(s). Config updated.", days),
         Err(e) => bail!("Error setting streak interval: {}", e),
     }
+    journal::record_setting(service, Setting::StreakInterval, before)
+}
+
+pub fn handle_session_start(
+    service: &AppService,
+    name: Option<String>,
+    notes: Option<String>,
+) -> Result<()> {
+    let mut store = CliStore::load(service)?;
+    if let Some(open) = store.active_session_mut() {
+        bail!(
+            "Session {} is already open. End it with 'ta session end' first.",
+            open.label()
+        );
+    }
+
+    let session = Session {
+        id: store.next_session_id(),
+        name: name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+        started_at: Utc::now(),
+        ended_at: None,
+        notes: notes
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty()),
+        workout_ids: Vec::new(),
+        pb_workout_ids: Vec::new(),
+    };
+    println!(
+        "Started session {} at {}. Workouts added now are attached to it.",
+        session.label(),
+        session.started_at.with_timezone(&Local).format("%H:%M")
+    );
+    store.sessions.push(session.clone());
+    store.save()?;
+    journal::record(
+        service,
+        vec![Change::Session {
+            before: None,
+            after: Some(session),
+        }],
+    )
+}
+
+pub fn handle_session_end(service: &AppService, notes: Option<String>) -> Result<()> {
+    let mut store = CliStore::load(service)?;
+    let Some(session) = store.active_session_mut() else {
+        bail!("No session is open. Start one with 'ta session start'.");
+    };
+    let before = session.clone();
+
+    session.ended_at = Some(Utc::now());
+    if let Some(notes) = notes {
+        session.add_note(&notes);
+    }
+    let id = session.id;
+    println!(
+        "Ended session {} after {} with {} workout(s).",
+        session.label(),
+        format_duration(session.duration()),
+        session.workout_ids.len()
+    );
+    let after = session.clone();
+    store.save()?;
+    journal::record(
+        service,
+        vec![Change::Session {
+            before: Some(before),
+            after: Some(after),
+        }],
+    )?;
+
+    handle_session_show(service, Some(id))
+}
+
+pub fn handle_session_show(service: &AppService, id: Option<i64>) -> Result<()> {
+    let store = CliStore::load(service)?;
+    let session = match id {
+        Some(id) => store
+            .session(id)
+            .with_context(|| format!("Session #{} not found.", id))?,
+        None => match store
+            .sessions
+            .iter()
+            .find(|s| s.is_open())
+            .or(store.sessions.last())
+        {
+            Some(session) => session,
+            None => {
+                println!("No sessions recorded yet. Start one with 'ta session start'.");
+                return Ok(());
+            }
+        },
+    };
+
+    let filters = WorkoutFilters {
+        exercise_name: None,
+        date: None,
+        exercise_type: None,
+        muscle: None,
+        limit: None,
+    };
+    let mut workouts = list_workouts_with_ids(service, &filters, &session.workout_ids)?;
+    workouts.sort_by_key(|w| w.timestamp);
+
+    // Per exercise, in the order they were first performed: (exercise, entries, sets, volume)
+    let mut exercise_rows: Vec<(String, usize, i64, f64)> = Vec::new();
+    let mut pb_exercises: Vec<String> = Vec::new();
+    for workout in &workouts {
+        let volume = sets::workout_volume(workout, store.workout_sets.get(&workout.id));
+        let set_count = workout.sets.unwrap_or(0);
+        match exercise_rows
+            .iter_mut()
+            .find(|(name, _, _, _)| *name == workout.exercise_name)
+        {
+            Some(row) => {
+                row.1 += 1;
+                row.2 += set_count;
+                row.3 += volume;
+            }
+            None => exercise_rows.push((workout.exercise_name.clone(), 1, set_count, volume)),
+        }
+        if session.pb_workout_ids.contains(&workout.id)
+            && !pb_exercises.contains(&workout.exercise_name)
+        {
+            pb_exercises.push(workout.exercise_name.clone());
+        }
+    }
+
+    let rests: Vec<i64> = workouts
+        .iter()
+        .filter_map(|w| store.workout_rests.get(&w.id))
+        .flatten()
+        .copied()
+        .collect();
+
+    let header_color = get_header_color(service, Color::Green);
+    output::print_session_summary(
+        session,
+        &exercise_rows,
+        &pb_exercises,
+        &rests,
+        service.config.units,
+        header_color,
+    );
+    Ok(())
+}
+
+pub fn handle_session_list(
+    service: &AppService,
+    format: cli::OutputFormat,
+    limit: usize,
+) -> Result<()> {
+    let store = CliStore::load(service)?;
+    let skip = store.sessions.len().saturating_sub(limit);
+    let sessions: Vec<Session> = store.sessions.into_iter().skip(skip).rev().collect();
+
+    if format != cli::OutputFormat::Table {
+        serialize::write_records(&sessions, format, service.config.units)?;
+    } else if sessions.is_empty() {
+        println!("No sessions recorded yet. Start one with 'ta session start'.");
+    } else {
+        let header_color = get_header_color(service, Color::Green);
+        output::print_session_table(&sessions, header_color);
+    }
+    Ok(())
+}
+
+pub fn handle_template_create(
+    service: &AppService,
+    name: String,
+    items: Vec<TemplateItem>,
+) -> Result<()> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        bail!("Template name cannot be empty.");
+    }
+    let mut store = CliStore::load(service)?;
+    if store.template_index(&name).is_some() {
+        bail!("A template named '{}' already exists.", name);
+    }
+
+    println!(
+        "Successfully created template '{}' with {} exercise(s).",
+        name,
+        items.len()
+    );
+    let template = Template { name, items };
+    store.templates.push(template.clone());
+    store.save()?;
+    journal::record(
+        service,
+        vec![Change::Template {
+            before: None,
+            after: Some(template),
+        }],
+    )
+}
+
+pub fn handle_template_edit(
+    service: &AppService,
+    name: String,
+    rename: Option<String>,
+    add: Vec<TemplateItem>,
+    remove: Vec<String>,
+    items: Vec<TemplateItem>,
+) -> Result<()> {
+    let mut store = CliStore::load(service)?;
+    let Some(index) = store.template_index(&name) else {
+        bail!("Template '{}' not found.", name);
+    };
+
+    if let Some(new_name) = rename.as_deref().map(str::trim) {
+        if new_name.is_empty() {
+            bail!("Template name cannot be empty.");
+        }
+        if store
+            .template_index(new_name)
+            .is_some_and(|other| other != index)
+        {
+            bail!("A template named '{}' already exists.", new_name);
+        }
+    }
+
+    let before = store.templates[index].clone();
+    let template = &mut store.templates[index];
+    if let Some(new_name) = rename {
+        template.name = new_name.trim().to_string();
+    }
+    if !items.is_empty() {
+        template.items = items;
+    }
+    for exercise in &remove {
+        let before = template.items.len();
+        template
+            .items
+            .retain(|item| !item.exercise.eq_ignore_ascii_case(exercise.trim()));
+        if template.items.len() == before {
+            bail!(
+                "Exercise '{}' is not part of template '{}'.",
+                exercise,
+                template.name
+            );
+        }
+    }
+    template.items.extend(add);
+    if template.items.is_empty() {
+        bail!("A template needs at least one exercise.");
+    }
+
+    println!(
+        "Successfully updated template '{}' ({} exercise(s)).",
+        template.name,
+        template.items.len()
+    );
+    let after = template.clone();
+    store.save()?;
+    journal::record(
+        service,
+        vec![Change::Template {
+            before: Some(before),
+            after: Some(after),
+        }],
+    )
+}
+
+pub fn handle_template_list(service: &AppService, format: cli::OutputFormat) -> Result<()> {
+    let store = CliStore::load(service)?;
+    if format != cli::OutputFormat::Table {
+        serialize::write_records(&store.templates, format, service.config.units)?;
+    } else if store.templates.is_empty() {
+        println!("No templates defined. Create one with 'ta template create'.");
+    } else {
+        let header_color = get_header_color(service, Color::Cyan);
+        output::print_template_table(&store.templates, header_color);
+    }
+    Ok(())
+}
+
+pub fn handle_template_delete(service: &AppService, name: String) -> Result<()> {
+    let mut store = CliStore::load(service)?;
+    let Some(index) = store.template_index(&name) else {
+        bail!("Template '{}' not found.", name);
+    };
+    let removed = store.templates.remove(index);
+    store.save()?;
+    println!("Successfully deleted template '{}'.", removed.name);
+    journal::record(
+        service,
+        vec![Change::Template {
+            before: Some(removed),
+            after: None,
+        }],
+    )
+}
+
+/// Sets/reps/weight of the most recent entry logged for an exercise, if any.
+pub fn last_logged_values(
+    service: &AppService,
+    exercise: &str,
+) -> Option<(Option<i64>, Option<i64>, Option<f64>)> {
+    let workouts = service
+        .list_workouts_for_exercise_on_nth_last_day(exercise, 1)
+        .ok()?;
+    workouts
+        .into_iter()
+        .max_by_key(|w| w.timestamp)
+        .map(|w| (w.sets, w.reps, w.weight))
+}
+
+pub fn handle_template_run(
+    service: &mut AppService,
+    name: String,
+    date: NaiveDate,
+    interactive: bool,
+) -> Result<()> {
+    if !interactive {
+        return Err(InputRequired(
+            "Running a template asks for each exercise. Log them with 'ta add' instead."
+                .to_string(),
+        )
+        .into());
+    }
+    let store = CliStore::load(service)?;
+    let Some(index) = store.template_index(&name) else {
+        bail!("Template '{}' not found.", name);
+    };
+    let template = store.templates[index].clone();
+
+    println!(
+        "Running template '{}' ({} exercise(s)).",
+        template.name,
+        template.items.len()
+    );
+    println!("Press Enter to log the suggested values, type SETSxREPS@WEIGHT to change them, 's' to skip or 'q' to stop.");
+
+    let total = template.items.len();
+    let mut logged = 0;
+    'items: for (position, item) in template.items.iter().enumerate() {
+        let last = last_logged_values(service, &item.exercise);
+        let (sets, reps, weight) = last.unwrap_or((item.sets, item.reps, item.weight));
+        let suggestion = format_prescription(sets, reps, weight);
+        let target = item.prescription();
+
+        let (sets, reps, weight) = loop {
+            println!(
+                "\n[{}/{}] {} (target: {}, last: {})",
+                position + 1,
+                total,
+                item.exercise,
+                if target.is_empty() {
+                    "-"
+                } else {
+                    target.as_str()
+                },
+                last.map_or("-".to_string(), |(s, r, w)| format_prescription(s, r, w)),
+            );
+            print!("Log [{}]: ", suggestion);
+            stdout().flush()?;
+
+            let mut input = String::new();
+            if stdin().read_line(&mut input)? == 0 {
+                println!();
+                break 'items; // EOF
+            }
+            let trimmed_input = input.trim();
+            if trimmed_input.is_empty() {
+                break (sets, reps, weight);
+            } else if trimmed_input.eq_ignore_ascii_case("s") {
+                println!("Skipped {}.", item.exercise);
+                continue 'items;
+            } else if trimmed_input.eq_ignore_ascii_case("q") {
+                println!("Stopping template.");
+                break 'items;
+            }
+            match parse_prescription(trimmed_input) {
+                Ok((new_sets, new_reps, new_weight)) => {
+                    break (new_sets.or(sets), new_reps.or(reps), new_weight.or(weight))
+                }
+                Err(e) => println!("{e}"),
+            }
+        };
+
+        match handle_add_workout(
+            service,
+            item.exercise.clone(),
+            date.into(),
+            sets,
+            reps,
+            weight,
+            Vec::new(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            &cli::PromptAnswers::default(),
+            interactive,
+        ) {
+            Ok(()) => logged += 1,
+            Err(e) => eprintln!("{e}"),
+        }
+    }
+
+    println!(
+        "\nTemplate '{}' finished: logged {} of {} exercise(s).",
+        template.name, logged, total
+    );
+    Ok(())
+}
+
+/// Workout a rest is recorded against: the given ID, else the last workout of the
+/// open session, else the last workout logged today.
+fn rest_target(service: &AppService, store: &CliStore, id: Option<i64>) -> Result<Option<i64>> {
+    if let Some(id) = id {
+        if journal::snapshot_workouts(service, store, &[id])?.is_empty() {
+            bail!("Workout ID {} not found.", id);
+        }
+        return Ok(Some(id));
+    }
+    let in_session = store
+        .sessions
+        .iter()
+        .find(|s| s.is_open())
+        .and_then(|s| s.workout_ids.last().copied());
+    if in_session.is_some() {
+        return Ok(in_session);
+    }
+    let today = service.list_workouts(&WorkoutFilters {
+        exercise_name: None,
+        date: Some(Local::now().date_naive()),
+        exercise_type: None,
+        muscle: None,
+        limit: None,
+    })?;
+    Ok(today.iter().max_by_key(|w| w.timestamp).map(|w| w.id))
+}
+
+pub async fn handle_rest(
+    service: &mut AppService,
+    duration: Duration,
+    workout_id: Option<i64>,
+    record: bool,
+) -> Result<()> {
+    let target = if record {
+        rest_target(service, &CliStore::load(service)?, workout_id)?
+    } else {
+        None
+    };
+    if duration.num_seconds() == 0 {
+        bail!("The rest must be longer than 0 seconds.");
+    }
+    println!(
+        "Resting for {}. Press Ctrl-C to stop early.",
+        timer::format_clock(duration.num_seconds())
+    );
+    let outcome = timer::run(&[timer::Phase::rest(duration)]).await;
+    let Some(id) = target else {
+        return Ok(());
+    };
+    if outcome.elapsed_seconds == 0 {
+        return Ok(());
+    }
+
+    let mut store = CliStore::load(service)?;
+    let before = journal::snapshot_workouts(service, &store, &[id])?;
+    store
+        .workout_rests
+        .entry(id)
+        .or_default()
+        .push(outcome.elapsed_seconds);
+    store.save()?;
+    let after = journal::snapshot_workouts(service, &store, &[id])?;
+    if let (Some(before), Some(after)) = (before.into_iter().next(), after.into_iter().next()) {
+        println!(
+            "Recorded a {} rest after workout ID {} ({}).",
+            timer::format_clock(outcome.elapsed_seconds),
+            id,
+            after.exercise_name
+        );
+        journal::record(
+            service,
+            vec![Change::Workout {
+                before: Some(before),
+                after: Some(after),
+            }],
+        )?;
+    }
+    Ok(())
+}
+
+pub async fn handle_timer(
+    service: &mut AppService,
+    kind: TimerKind,
+    phases: &[timer::Phase],
+    log: Option<String>,
+    notes: Option<String>,
+    answers: &cli::PromptAnswers,
+    interactive: bool,
+) -> Result<()> {
+    // Check the exercise up front rather than after the workout
+    if let Some(exercise) = &log {
+        match service.get_exercise_by_identifier_service(exercise) {
+            Ok(Some(def)) if def.type_ == ExerciseType::Resistance => bail!(
+                "'{}' is a Resistance exercise. Interval timers log Cardio or BodyWeight exercises.",
+                def.name
+            ),
+            Ok(Some(_)) => {}
+            Ok(None) => bail!(
+                "Exercise '{}' not found. Create it first with 'ta create-exercise'.",
+                exercise
+            ),
+            Err(e) => bail!("Error looking up exercise '{}': {}", exercise, e),
+        }
+    }
+
+    let total: i64 = phases.iter().map(|p| p.seconds).sum();
+    println!(
+        "{} timer, {} in total. Press Ctrl-C to stop early.",
+        kind,
+        timer::format_clock(total)
+    );
+    let outcome = timer::run(phases).await;
+    let Some(exercise) = log else {
+        return Ok(());
+    };
+    if outcome.elapsed_seconds == 0 {
+        println!("Nothing to log.");
+        return Ok(());
+    }
+    // Rounds only count as sets where the timer defines them
+    let sets = match kind {
+        TimerKind::Amrap => None,
+        TimerKind::Emom | TimerKind::Tabata => {
+            Some(i64::from(outcome.rounds_completed)).filter(|n| *n > 0)
+        }
+    };
+    let minutes = (outcome.elapsed_seconds + 59) / 60;
+    handle_add_workout(
+        service,
+        exercise,
+        Local::now().date_naive().into(),
+        sets,
+        None,
+        None,
+        Vec::new(),
+        Some(minutes),
+        None,
+        notes,
+        None,
+        None,
+        answers,
+        interactive,
+    )
+}
+
+pub fn handle_shell(service: &mut AppService, interactive: bool) -> Result<()> {
+    if !interactive {
+        return Err(InputRequired(
+            "The shell reads commands from a terminal. Use the individual 'ta' commands in scripts."
+                .to_string(),
+        )
+        .into());
+    }
+    shell::run(service)
+}
+
+#[cfg(feature = "tui")]
+pub fn handle_tui(service: &mut AppService, interactive: bool) -> Result<()> {
+    if !interactive {
+        return Err(InputRequired("The dashboard needs a terminal.".to_string()).into());
+    }
+    crate::tui::run(service)
+}
+
+#[cfg(not(feature = "tui"))]
+pub fn handle_tui(_service: &mut AppService, _interactive: bool) -> Result<()> {
+    bail!("This build of ta has no dashboard. Rebuild it with the `tui` feature: cargo install --path . --features tui");
+}
+
+pub fn handle_undo(
+    service: &mut AppService,
+    count: u32,
+    yes: bool,
+    interactive: bool,
+) -> Result<()> {
+    let mut journal = Journal::load(service)?;
+    if journal.entries.is_empty() {
+        println!("Nothing to undo.");
+        return Ok(());
+    }
+    let count = (count as usize).min(journal.entries.len());
+    let pending: Vec<_> = journal.entries.iter().rev().take(count).collect();
+
+    println!("The following will be undone:");
+    for entry in &pending {
+        println!("  #{} {}", entry.id, entry.command);
+        for change in &entry.changes {
+            println!("      {}", change.describe());
+        }
+    }
+    if !yes {
+        if !interactive {
+            return Err(InputRequired(
+                "Undoing needs confirmation. Pass --yes to undo non-interactively.".to_string(),
+            )
+            .into());
+        }
+        print!("Undo {} operation(s)? (y/N): ", count);
+        stdout().flush()?;
+        let mut input = String::new();
+        stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Nothing was undone.");
+            return Ok(());
+        }
+    }
+
+    let undone = journal.undo(service, count)?;
+    for entry in &undone {
+        println!("Undid #{} ({}).", entry.id, entry.command);
+    }
+    Ok(())
+}
+
+pub fn handle_history(service: &AppService, limit: usize) -> Result<()> {
+    let journal = Journal::load(service)?;
+    if journal.entries.is_empty() {
+        println!("No changes recorded yet.");
+        return Ok(());
+    }
+    let start = journal.entries.len().saturating_sub(limit);
+    let header_color = get_header_color(service, Color::Cyan);
+    output::print_history(&journal.entries[start..], header_color);
+    Ok(())
+}
+
+pub fn handle_history_prune(
+    service: &AppService,
+    keep: Option<usize>,
+    older_than: Option<Duration>,
+) -> Result<()> {
+    let mut journal = Journal::load(service)?;
+    let removed = journal.prune(keep, older_than.map(|age| Utc::now() - age));
+    journal.save()?;
+    println!(
+        "Removed {} operation(s) from the history; {} remain.",
+        removed,
+        journal.entries.len()
+    );
+    Ok(())
+}
+
+pub fn handle_profile_create(name: String) -> Result<()> {
+    let root = profile::profiles_dir()?;
+    match profile::create(&root, &name) {
+        Ok(dir) => println!(
+            "Created profile '{}' at {:?}. Use it with '--profile {}' or 'ta profile switch {}'.",
+            name, dir, name, name
+        ),
+        Err(e) => bail!("Error creating profile: {}", e),
+    }
+    Ok(())
+}
+
+pub fn handle_profile_list() -> Result<()> {
+    let root = profile::profiles_dir()?;
+    let active = profile::active(&root)?;
+    let mut names = vec![profile::DEFAULT_PROFILE.to_string()];
+    names.extend(profile::list(&root)?);
+    for name in names {
+        let is_active = match &active {
+            Some(active) => *active == name,
+            None => name == profile::DEFAULT_PROFILE,
+        };
+        println!("{} {}", if is_active { "*" } else { " " }, name);
+    }
+    if let Some(dir) = std::env::var_os(profile::DATA_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        println!(
+            "Note: {} is set, so commands without --profile use {:?}.",
+            profile::DATA_DIR_ENV,
+            dir
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_profile_switch(name: String) -> Result<()> {
+    let root = profile::profiles_dir()?;
+    match profile::switch(&root, &name) {
+        Ok(()) => println!("Switched to profile '{}'.", name),
+        Err(e) => bail!("Error switching profile: {}", e),
+    }
+    Ok(())
+}
+
+pub fn handle_profile_delete(name: String, yes: bool, interactive: bool) -> Result<()> {
+    if !yes {
+        if !interactive {
+            return Err(InputRequired(
+                "Deleting a profile needs confirmation. Pass --yes to delete it non-interactively."
+                    .to_string(),
+            )
+            .into());
+        }
+        print!(
+            "Permanently delete profile '{}' with all its workouts and settings? (y/N): ",
+            name
+        );
+        stdout().flush()?;
+        let mut input = String::new();
+        stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("The profile was kept.");
+            return Ok(());
+        }
+    }
+    let root = profile::profiles_dir()?;
+    match profile::delete(&root, &name) {
+        Ok(dir) => println!("Deleted profile '{}' ({:?}).", name, dir),
+        Err(e) => bail!("Error deleting profile: {}", e),
+    }
+    Ok(())
+}
+
+pub fn handle_trash_list(service: &AppService) -> Result<()> {
+    let store = CliStore::load(service)?;
+    if store.trash.is_empty() {
+        println!("The trash is empty.");
+    } else {
+        let header_color = get_header_color(service, Color::Red);
+        output::print_trash_table(&store.trash, header_color);
+    }
+    Ok(())
+}
+
+pub fn handle_trash_restore(service: &mut AppService, id: i64) -> Result<()> {
+    let mut store = CliStore::load(service)?;
+    let changes = match trash::restore(service, &mut store, id) {
+        Ok(changes) => changes,
+        Err(e) => bail!("Error restoring trash item {}: {}", id, e),
+    };
+    store.save()?;
+    if let Some(restored) = changes.last() {
+        println!("Restored trash item {}. {}.", id, restored.describe());
+    }
+    journal::record(service, changes)
+}
+
+pub fn handle_trash_empty(
+    service: &AppService,
+    older_than: Option<Duration>,
+    yes: bool,
+    interactive: bool,
+) -> Result<()> {
+    let mut store = CliStore::load(service)?;
+    let cutoff = older_than.map(|age| Utc::now() - age);
+    let count = store
+        .trash
+        .iter()
+        .filter(|item| cutoff.is_none_or(|cutoff| item.deleted_at < cutoff))
+        .count();
+    if count == 0 {
+        println!("Nothing to remove from the trash.");
+        return Ok(());
+    }
+    if !yes {
+        if !interactive {
+            return Err(InputRequired(
+                "Emptying the trash needs confirmation. Pass --yes to do it non-interactively."
+                    .to_string(),
+            )
+            .into());
+        }
+        print!(
+            "Permanently remove {} item(s) from the trash? (y/N): ",
+            count
+        );
+        stdout().flush()?;
+        let mut input = String::new();
+        stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("The trash was left as it is.");
+            return Ok(());
+        }
+    }
+
+    let removed = trash::empty(&mut store, cutoff);
+    store.save()?;
+    println!("Removed {} item(s) from the trash.", removed.len());
+    let changes = removed
+        .into_iter()
+        .map(|item| Change::Trash {
+            before: Some(item),
+            after: None,
+        })
+        .collect();
+    journal::record(service, changes)
+}
+
+/// Takes an automatic backup before a risky command and says where it went.
+fn backup_before(service: &AppService, reason: &str) -> Result<()> {
+    let path = backup::auto_backup(service, reason)?;
+    println!("Backed up current data to {:?}.", path);
+    Ok(())
+}
+
+pub fn handle_backup(service: &AppService, to: Option<PathBuf>) -> Result<()> {
+    let dir = to.unwrap_or_else(|| backup::default_backup_dir(service));
+    match backup::create_backup(service, &dir, None) {
+        Ok(path) => println!("Backup written to {:?}", path),
+        Err(e) => bail!("Error creating backup: {}", e),
+    }
+    Ok(())
+}
+
+pub fn handle_restore(
+    service: &AppService,
+    archive: PathBuf,
+    yes: bool,
+    interactive: bool,
+) -> Result<()> {
+    let unpacked = match UnpackedBackup::open(&archive) {
+        Ok(unpacked) => unpacked,
+        Err(e) => bail!("Error reading backup {:?}: {}", archive, e),
+    };
+    let current = backup::table_counts(service.get_db_path())?;
+    println!(
+        "Backup {:?} (taken {}):",
+        archive,
+        unpacked
+            .created_at
+            .as_deref()
+            .unwrap_or("at an unknown time")
+    );
+    let header_color = get_header_color(service, Color::Yellow);
+    output::print_restore_preview(&current, &unpacked.tables, header_color);
+
+    if !yes {
+        if !interactive {
+            return Err(InputRequired(
+                "Restoring needs confirmation. Pass --yes to restore non-interactively."
+                    .to_string(),
+            )
+            .into());
+        }
+        print!("Replace your current data with this backup? (y/N): ");
+        stdout().flush()?;
+        let mut input = String::new();
+        stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Nothing was restored.");
+            return Ok(());
+        }
+    }
+
+    backup_before(service, "restore")?;
+    match unpacked.restore(service) {
+        Ok(()) => println!("Restored data from {:?}.", archive),
+        Err(e) => bail!("Error restoring backup {:?}: {}", archive, e),
+    }
     Ok(())
 }
 
@@ -769,7 +2949,8 @@ pub async fn handle_sync(
         .context("Sync communication with the server failed")?;
 
     // Phase 3: Write data received from the server. `service` is borrowed mutably again.
-    let summary_received = {
+    let (summary_received, kept_deleted) = {
+        backup_before(service, "sync")?;
         println!("Applying server changes...");
         let summary = service
             .apply_server_changes(server_response.data_to_client)
@@ -779,7 +2960,12 @@ pub async fn handle_sync(
             .set_last_sync_timestamp(server_response.server_current_ts)
             .context("Failed to update the last sync timestamp in the config file")?;
 
-        summary
+        // The server doesn't know about local deletions and may have sent them back
+        let store = CliStore::load(service)?;
+        let kept_deleted = trash::delete_resurrected(service, &store)
+            .context("Failed to delete records that were pulled back from the trash")?;
+
+        (summary, kept_deleted)
     };
 
     // Display the results of the sync operation
@@ -806,7 +2992,64 @@ pub async fn handle_sync(
     println!("  Workouts:       {}", summary_received.workouts);
     println!("  Aliases:        {}", summary_received.aliases);
     println!("  Bodyweights:    {}", summary_received.bodyweights);
+    if kept_deleted > 0 {
+        println!("  Kept deleted:   {} (deleted here)", kept_deleted);
+    }
     println!("------------------------------------");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn workouts(count: i64) -> Vec<Workout> {
+        (0..count)
+            .map(|id| Workout {
+                id,
+                timestamp: Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap() + Duration::days(id),
+                exercise_name: "Bench Press".to_string(),
+                sets: Some(3),
+                reps: Some(8),
+                weight: Some(60.0),
+                duration_minutes: None,
+                distance: None,
+                notes: None,
+                exercise_type: Some(ExerciseType::Resistance),
+                bodyweight: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_list_window_default_limit() {
+        let store = CliStore::default();
+        let window = cli::ListWindow {
+            offset: 5,
+            ..Default::default()
+        };
+        let mut listed = workouts(40);
+        apply_list_window(&mut listed, &window, None, false, &store);
+        assert_eq!(listed.len(), DEFAULT_LIST_LIMIT as usize);
+        // Newest first, after skipping the five newest
+        assert_eq!(listed[0].id, 34);
+
+        let mut listed = workouts(40);
+        apply_list_window(&mut listed, &window, Some(3), false, &store);
+        assert_eq!(listed.len(), 3);
+
+        // A date range, or a single day or session, lists everything in it
+        let mut listed = workouts(40);
+        apply_list_window(&mut listed, &window, None, true, &store);
+        assert_eq!(listed.len(), 35);
+        let ranged = cli::ListWindow {
+            from: NaiveDate::from_ymd_opt(2025, 3, 1),
+            ..Default::default()
+        };
+        let mut listed = workouts(40);
+        apply_list_window(&mut listed, &ranged, None, false, &store);
+        assert_eq!(listed.len(), 40);
+    }
+}
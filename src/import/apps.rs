@@ -0,0 +1,687 @@
+//! Importers for CSV exports of workout apps (`ta import strong|hevy|fitnotes`).
+//!
+//! The apps export one row per set. Rows of the same exercise in the same workout
+//! become one workout, with the individual sets kept in the CLI store like
+//! `ta add --set` does. Exercises the database doesn't know are either mapped onto
+//! an existing exercise or alias, or created with a type guessed from their name and data.
+
+use super::{
+    bodyweight_at, convert_distance, convert_weight, field, line_of, parse_number, Columns,
+    ImportReport,
+};
+use crate::journal::{Change, WorkoutSnapshot};
+use crate::sets::{self, SetEntry};
+use crate::store::CliStore;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use clap::ValueEnum;
+use csv::StringRecord;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use task_athlete_lib::{AddWorkoutParams, AppService, ExerciseType, Units, WorkoutFilters};
+
+/// Name fragments of exercises that are usually cardio.
+const CARDIO_KEYWORDS: &[&str] = &[
+    "run",
+    "jog",
+    "walk",
+    "hike",
+    "cycl",
+    "bike",
+    "rowing",
+    "row machine",
+    "ergometer",
+    "elliptical",
+    "swim",
+    "treadmill",
+    "stair",
+    "skipping",
+    "jump rope",
+];
+/// Name fragments of exercises that are usually done with bodyweight.
+const BODYWEIGHT_KEYWORDS: &[&str] = &[
+    "pull up",
+    "pull-up",
+    "pullup",
+    "chin up",
+    "chin-up",
+    "chinup",
+    "push up",
+    "push-up",
+    "pushup",
+    "dip",
+    "plank",
+    "sit up",
+    "sit-up",
+    "situp",
+    "crunch",
+    "burpee",
+    "muscle up",
+    "muscle-up",
+    "bodyweight",
+    "leg raise",
+    "pistol",
+];
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum App {
+    /// Strong (Settings > Export Data)
+    Strong,
+    /// Hevy (Settings > Export & Import Data > Export Workouts)
+    Hevy,
+    /// FitNotes (Settings > Spreadsheet Export)
+    Fitnotes,
+}
+
+impl fmt::Display for App {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            App::Strong => "Strong",
+            App::Hevy => "Hevy",
+            App::Fitnotes => "FitNotes",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How to read an app export.
+pub struct AppImportOptions<'a> {
+    pub app: App,
+    /// Unit of weights without a unit in the file (Strong).
+    pub weight_unit: &'a str,
+    /// Unit of distances without a unit in the file (Strong, FitNotes).
+    pub distance_unit: &'a str,
+}
+
+/// A parsed export: sets grouped into workouts, plus the problems found while reading.
+pub struct AppExport {
+    /// Sets keyed by workout start time and exercise name.
+    workouts: GroupedSets,
+    report: ImportReport,
+}
+
+/// One set as exported by an app, with values converted to the configured units.
+struct SetRow {
+    line: u64,
+    /// Start of the workout the set belongs to.
+    started: DateTime<Utc>,
+    exercise: String,
+    category: Option<String>,
+    weight: Option<f64>,
+    reps: Option<i64>,
+    distance: Option<f64>,
+    seconds: Option<f64>,
+    rpe: Option<f64>,
+    notes: Option<String>,
+}
+
+/// Reads an app export. Rows that can't be parsed are recorded in the export's report.
+pub fn read_export(path: &Path, options: &AppImportOptions, units: Units) -> Result<AppExport> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    // Older Strong exports use semicolons
+    let first_line = contents.lines().next().unwrap_or_default();
+    let delimiter = if first_line.matches(';').count() > first_line.matches(',').count() {
+        b';'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(contents.trim_start_matches('\u{feff}').as_bytes());
+    let columns = Columns::new(reader.headers().context("Failed to read CSV headers")?);
+    check_columns(options.app, &columns)?;
+    let parse_row: fn(&StringRecord, &Columns, &AppImportOptions, Units) -> RowResult =
+        match options.app {
+            App::Strong => parse_strong_row,
+            App::Hevy => parse_hevy_row,
+            App::Fitnotes => parse_fitnotes_row,
+        };
+
+    let mut export = AppExport {
+        workouts: BTreeMap::new(),
+        report: ImportReport::default(),
+    };
+    for result in reader.records() {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                export.report.error(line, format!("Malformed row: {}", e));
+                continue;
+            }
+        };
+        match parse_row(&record, &columns, options, units) {
+            Ok(Some(row)) => export
+                .workouts
+                .entry((row.started, row.exercise.clone()))
+                .or_default()
+                .push(row),
+            Ok(None) => export.report.skipped_rows += 1,
+            Err(message) => export.report.error(line_of(&record), message),
+        }
+    }
+    Ok(export)
+}
+
+type GroupedSets = BTreeMap<(DateTime<Utc>, String), Vec<SetRow>>;
+
+/// Rows of each distinct exercise name in the file.
+fn rows_by_exercise(workouts: &GroupedSets) -> BTreeMap<&str, Vec<&SetRow>> {
+    let mut rows_by_exercise: BTreeMap<&str, Vec<&SetRow>> = BTreeMap::new();
+    for ((_, exercise), rows) in workouts {
+        rows_by_exercise.entry(exercise).or_default().extend(rows);
+    }
+    rows_by_exercise
+}
+
+impl AppExport {
+    /// Exercise names that are neither mapped nor defined, with their guessed type.
+    pub fn unknown_exercises(
+        &self,
+        service: &AppService,
+        mappings: &HashMap<String, String>,
+    ) -> Result<Vec<(String, ExerciseType)>> {
+        let mut unknown = Vec::new();
+        for (name, rows) in rows_by_exercise(&self.workouts) {
+            if find_mapping(mappings, name).is_none()
+                && service.get_exercise_by_identifier_service(name)?.is_none()
+            {
+                unknown.push((name.to_string(), guess_exercise_type(name, &rows)));
+            }
+        }
+        Ok(unknown)
+    }
+}
+
+/// Target of a name mapping, matching the file's name case-insensitively.
+fn find_mapping<'a>(mappings: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    mappings
+        .iter()
+        .find(|(from, _)| from.eq_ignore_ascii_case(name))
+        .map(|(_, to)| to.as_str())
+}
+
+/// Imports a parsed export. Exercise names in `mappings` are logged as the mapped
+/// exercise; other unknown names are created with a guessed type.
+pub fn import_export(
+    service: &mut AppService,
+    export: AppExport,
+    mappings: &HashMap<String, String>,
+    dry_run: bool,
+) -> Result<ImportReport> {
+    let AppExport {
+        workouts,
+        mut report,
+    } = export;
+    report.dry_run = dry_run;
+    let units = service.config.units;
+
+    // Resolve every distinct exercise name once
+    let mut resolved: HashMap<String, (String, ExerciseType)> = HashMap::new();
+    for (name, rows) in rows_by_exercise(&workouts) {
+        let guessed = guess_exercise_type(name, &rows);
+        let target = match find_mapping(mappings, name) {
+            Some(target) => Some(target.to_string()),
+            None if service.get_exercise_by_identifier_service(name)?.is_some() => {
+                Some(name.to_string())
+            }
+            None => None,
+        };
+        let first_line = rows.first().map_or(0, |r| r.line);
+
+        match target {
+            Some(target) => match service.get_exercise_by_identifier_service(&target)? {
+                Some(def) => {
+                    resolved.insert(name.to_string(), (def.name, def.type_));
+                }
+                None => report.error(
+                    first_line,
+                    format!(
+                        "Exercise '{}' (mapped from '{}') not found; its sets were skipped.",
+                        target, name
+                    ),
+                ),
+            },
+            None => {
+                let category = rows.iter().find_map(|r| r.category.as_deref());
+                // Categories that are muscle groups become the exercise's muscles
+                let muscles = category.filter(|c| !c.eq_ignore_ascii_case("cardio"));
+                if !dry_run {
+                    if let Err(e) = service.create_exercise(name, guessed, None, muscles) {
+                        report.error(
+                            first_line,
+                            format!("Could not create exercise '{}': {}", name, e),
+                        );
+                        continue;
+                    }
+                    report
+                        .changes
+                        .push(Change::exercise_created(name, guessed, muscles));
+                }
+                report
+                    .created_exercises
+                    .push(format!("{} ({})", name, guessed));
+                resolved.insert(name.to_string(), (name.to_string(), guessed));
+            }
+        }
+    }
+
+    let mut existing: HashSet<(i64, String)> = service
+        .list_workouts(&WorkoutFilters {
+            exercise_name: None,
+            date: None,
+            exercise_type: None,
+            muscle: None,
+            limit: None,
+        })?
+        .iter()
+        .map(|w| (w.timestamp.timestamp(), w.exercise_name.to_lowercase()))
+        .collect();
+    let mut bodyweights = service.list_bodyweights(u32::MAX)?;
+    bodyweights.sort_by_key(|(_, ts, _)| *ts);
+    let mut store = CliStore::load(service)?;
+
+    for ((started, exercise), rows) in workouts {
+        let Some((canonical_name, exercise_type)) = resolved.get(&exercise) else {
+            continue; // Reported while resolving
+        };
+        let key = (started.timestamp(), canonical_name.to_lowercase());
+        if existing.contains(&key) {
+            report.duplicates += 1;
+            continue;
+        }
+
+        let set_entries: Vec<SetEntry> = rows
+            .iter()
+            .filter_map(|r| {
+                Some(SetEntry {
+                    weight: r.weight.filter(|w| *w > 0.0),
+                    reps: r.reps.filter(|reps| *reps > 0)?,
+                    rpe: r.rpe,
+                })
+            })
+            .collect();
+        let (sets, reps, weight) = sets::summarize(&set_entries);
+        let distance = sum_present(rows.iter().map(|r| r.distance));
+        let duration = sum_present(rows.iter().map(|r| r.seconds))
+            .map(|seconds| (seconds / 60.0).round() as i64);
+        let mut notes: Vec<&str> = Vec::new();
+        for note in rows.iter().filter_map(|r| r.notes.as_deref()) {
+            if !notes.contains(&note) {
+                notes.push(note);
+            }
+        }
+
+        if !dry_run {
+            let params = AddWorkoutParams {
+                exercise_identifier: canonical_name,
+                date: started,
+                sets,
+                reps,
+                weight,
+                distance,
+                duration,
+                notes: (!notes.is_empty()).then(|| notes.join("; ")),
+                bodyweight_to_use: (*exercise_type == ExerciseType::BodyWeight)
+                    .then(|| bodyweight_at(&bodyweights, started)),
+                implicit_type: None,
+                implicit_muscles: None,
+            };
+            let mut snapshot = WorkoutSnapshot::from_params(&params, units);
+            match service.add_workout(params) {
+                Ok((id, _)) => {
+                    snapshot.id = id;
+                    snapshot.logged_sets = set_entries.clone();
+                    report.changes.push(Change::workout_added(snapshot));
+                    if !set_entries.is_empty() {
+                        store.workout_sets.insert(id, set_entries);
+                    }
+                }
+                Err(e) => {
+                    let line = rows.first().map_or(0, |r| r.line);
+                    report.error(line, format!("Could not add workout: {}", e));
+                    continue;
+                }
+            }
+        }
+        existing.insert(key);
+        report.imported += 1;
+    }
+
+    if !dry_run {
+        store.save()?;
+    }
+    report.errors.sort_by_key(|e| e.line);
+    Ok(report)
+}
+
+type RowResult = Result<Option<SetRow>, String>;
+
+fn check_columns(app: App, columns: &Columns) -> Result<()> {
+    let required: &[&str] = match app {
+        App::Strong => &["Date", "Exercise Name"],
+        App::Hevy => &["start_time", "exercise_title"],
+        App::Fitnotes => &["Date", "Exercise"],
+    };
+    for name in required {
+        if columns.find(&[name]).is_none() {
+            anyhow::bail!("Missing '{}' column. Is this a {} export?", name, app);
+        }
+    }
+    Ok(())
+}
+
+fn parse_strong_row(
+    record: &StringRecord,
+    columns: &Columns,
+    options: &AppImportOptions,
+    units: Units,
+) -> RowResult {
+    // Warm-up sets and rest timer rows are not part of the workout
+    let set_order = field(record, columns.find(&["Set Order"])).unwrap_or_default();
+    if set_order.eq_ignore_ascii_case("w") || set_order.eq_ignore_ascii_case("rest timer") {
+        return Ok(None);
+    }
+
+    let weight_unit = field(record, columns.find(&["Weight Unit"]))
+        .unwrap_or(options.weight_unit)
+        .to_lowercase();
+    let distance_unit = field(record, columns.find(&["Distance Unit"]))
+        .unwrap_or(options.distance_unit)
+        .to_lowercase();
+    let row = SetRow {
+        line: line_of(record),
+        started: parse_local_timestamp(
+            field(record, columns.find(&["Date"])).ok_or("Missing date.")?,
+            &["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"],
+        )?,
+        exercise: field(record, columns.find(&["Exercise Name"]))
+            .ok_or("Missing exercise name.")?
+            .to_string(),
+        category: None,
+        weight: parse_number::<f64>(field(record, columns.find(&["Weight"])), "weight")?
+            .map(|w| convert_weight(w, &weight_unit, units))
+            .transpose()?,
+        reps: parse_reps(field(record, columns.find(&["Reps"])))?,
+        distance: parse_number::<f64>(field(record, columns.find(&["Distance"])), "distance")?
+            .map(|d| convert_distance(d, &distance_unit, units))
+            .transpose()?,
+        seconds: parse_number(field(record, columns.find(&["Seconds"])), "seconds")?,
+        rpe: parse_number(field(record, columns.find(&["RPE"])), "RPE")?,
+        notes: field(record, columns.find(&["Notes"])).map(str::to_string),
+    };
+    Ok(row.has_data().then_some(row))
+}
+
+fn parse_hevy_row(
+    record: &StringRecord,
+    columns: &Columns,
+    _options: &AppImportOptions,
+    units: Units,
+) -> RowResult {
+    let set_type = field(record, columns.find(&["set_type"])).unwrap_or_default();
+    if set_type.eq_ignore_ascii_case("warmup") {
+        return Ok(None);
+    }
+
+    let weight = match columns.find(&["weight_kg"]) {
+        Some(i) => (Some(i), "kg"),
+        None => (columns.find(&["weight_lbs"]), "lbs"),
+    };
+    let distance = match columns.find(&["distance_km"]) {
+        Some(i) => (Some(i), "km"),
+        None => (columns.find(&["distance_miles", "distance_mi"]), "mi"),
+    };
+    let row = SetRow {
+        line: line_of(record),
+        started: parse_local_timestamp(
+            field(record, columns.find(&["start_time"])).ok_or("Missing start time.")?,
+            &["%d %b %Y, %H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"],
+        )?,
+        exercise: field(record, columns.find(&["exercise_title"]))
+            .ok_or("Missing exercise title.")?
+            .to_string(),
+        category: None,
+        weight: parse_number::<f64>(field(record, weight.0), "weight")?
+            .map(|w| convert_weight(w, weight.1, units))
+            .transpose()?,
+        reps: parse_reps(field(record, columns.find(&["reps"])))?,
+        distance: parse_number::<f64>(field(record, distance.0), "distance")?
+            .map(|d| convert_distance(d, distance.1, units))
+            .transpose()?,
+        seconds: parse_number(
+            field(record, columns.find(&["duration_seconds"])),
+            "duration",
+        )?,
+        rpe: parse_number(field(record, columns.find(&["rpe"])), "RPE")?,
+        notes: field(record, columns.find(&["exercise_notes"])).map(str::to_string),
+    };
+    Ok(row.has_data().then_some(row))
+}
+
+fn parse_fitnotes_row(
+    record: &StringRecord,
+    columns: &Columns,
+    options: &AppImportOptions,
+    units: Units,
+) -> RowResult {
+    let weight = match columns.find(&["Weight (kgs)", "Weight (kg)"]) {
+        Some(i) => (Some(i), "kg"),
+        None => (columns.find(&["Weight (lbs)", "Weight (lb)"]), "lbs"),
+    };
+    let distance_unit = field(record, columns.find(&["Distance Unit"]))
+        .unwrap_or(options.distance_unit)
+        .to_lowercase();
+    let date = field(record, columns.find(&["Date"])).ok_or("Missing date.")?;
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}'.", date))?;
+    let seconds = field(record, columns.find(&["Time"]))
+        .map(|t| parse_clock_duration(t).ok_or_else(|| format!("Invalid time '{}'.", t)))
+        .transpose()?;
+
+    let row = SetRow {
+        line: line_of(record),
+        // FitNotes only records the day; use noon UTC like `ta add --date`
+        started: Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap_or_default()),
+        exercise: field(record, columns.find(&["Exercise"]))
+            .ok_or("Missing exercise name.")?
+            .to_string(),
+        category: field(record, columns.find(&["Category"])).map(str::to_string),
+        weight: parse_number::<f64>(field(record, weight.0), "weight")?
+            .map(|w| convert_weight(w, weight.1, units))
+            .transpose()?,
+        reps: parse_reps(field(record, columns.find(&["Reps"])))?,
+        distance: parse_number::<f64>(field(record, columns.find(&["Distance"])), "distance")?
+            .map(|d| convert_distance(d, &distance_unit, units))
+            .transpose()?,
+        seconds,
+        rpe: None,
+        notes: field(record, columns.find(&["Comment", "Notes"])).map(str::to_string),
+    };
+    Ok(row.has_data().then_some(row))
+}
+
+impl SetRow {
+    /// Rows without any performance data (e.g. notes-only rows) are skipped.
+    fn has_data(&self) -> bool {
+        self.reps.is_some_and(|r| r > 0)
+            || self.distance.is_some_and(|d| d > 0.0)
+            || self.seconds.is_some_and(|s| s > 0.0)
+    }
+}
+
+/// Parses reps, accepting decimal notation (`8.0`) some exports use.
+fn parse_reps(value: Option<&str>) -> Result<Option<i64>, String> {
+    parse_number::<f64>(value, "reps").map(|r| r.map(|r| r.round() as i64))
+}
+
+/// Parses a local date-time in one of `formats` and converts it to UTC.
+fn parse_local_timestamp(value: &str, formats: &[&str]) -> Result<DateTime<Utc>, String> {
+    formats
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .map(|local| local.with_timezone(&Utc))
+        .ok_or_else(|| format!("Invalid date '{}'.", value))
+}
+
+/// Parses `H:MM:SS` or `MM:SS` into seconds.
+fn parse_clock_duration(value: &str) -> Option<f64> {
+    let parts: Vec<f64> = value
+        .split(':')
+        .map(|p| p.trim().parse::<f64>().ok())
+        .collect::<Option<_>>()?;
+    match parts.as_slice() {
+        [h, m, s] => Some(h * 3600.0 + m * 60.0 + s),
+        [m, s] => Some(m * 60.0 + s),
+        _ => None,
+    }
+}
+
+fn sum_present(values: impl Iterator<Item = Option<f64>>) -> Option<f64> {
+    values
+        .flatten()
+        .fold(None, |sum, v| Some(sum.unwrap_or(0.0) + v))
+        .filter(|sum| *sum > 0.0)
+}
+
+/// Guesses the type of an unknown exercise from its name and logged values.
+fn guess_exercise_type(name: &str, rows: &[&SetRow]) -> ExerciseType {
+    let lower = name.to_lowercase();
+    let is_cardio_category = rows.iter().any(|r| {
+        r.category
+            .as_deref()
+            .is_some_and(|c| c.eq_ignore_ascii_case("cardio"))
+    });
+    let has_distance = rows.iter().any(|r| r.distance.is_some_and(|d| d > 0.0));
+    let has_weight = rows.iter().any(|r| r.weight.is_some_and(|w| w > 0.0));
+    let has_reps = rows.iter().any(|r| r.reps.is_some_and(|r| r > 0));
+
+    if is_cardio_category
+        || has_distance
+        || (!has_reps && CARDIO_KEYWORDS.iter().any(|k| lower.contains(k)))
+    {
+        ExerciseType::Cardio
+    } else if BODYWEIGHT_KEYWORDS.iter().any(|k| lower.contains(k))
+        || (has_reps && !has_weight && !lower.contains("barbell") && !lower.contains("dumbbell"))
+    {
+        ExerciseType::BodyWeight
+    } else {
+        ExerciseType::Resistance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_row(weight: Option<f64>, reps: Option<i64>, distance: Option<f64>) -> SetRow {
+        SetRow {
+            line: 2,
+            started: Utc::now(),
+            exercise: String::new(),
+            category: None,
+            weight,
+            reps,
+            distance,
+            seconds: None,
+            rpe: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn test_guess_exercise_type() {
+        let weighted = set_row(Some(100.0), Some(5), None);
+        let unweighted = set_row(None, Some(12), None);
+        let run = set_row(None, None, Some(5.0));
+
+        assert_eq!(
+            guess_exercise_type("Bench Press (Barbell)", &[&weighted]),
+            ExerciseType::Resistance
+        );
+        assert_eq!(
+            guess_exercise_type("Pull Up (Weighted)", &[&weighted]),
+            ExerciseType::BodyWeight
+        );
+        assert_eq!(
+            guess_exercise_type("Hanging Knee Raise", &[&unweighted]),
+            ExerciseType::BodyWeight
+        );
+        assert_eq!(
+            guess_exercise_type("Running (Outdoor)", &[&run]),
+            ExerciseType::Cardio
+        );
+    }
+
+    #[test]
+    fn test_parse_clock_duration() {
+        assert_eq!(parse_clock_duration("0:30:00"), Some(1800.0));
+        assert_eq!(parse_clock_duration("05:30"), Some(330.0));
+        assert_eq!(parse_clock_duration("soon"), None);
+    }
+
+    #[test]
+    fn test_parse_strong_and_hevy_rows() {
+        let options = AppImportOptions {
+            app: App::Strong,
+            weight_unit: "lbs",
+            distance_unit: "km",
+        };
+
+        let headers =
+            StringRecord::from(vec!["Date", "Exercise Name", "Set Order", "Weight", "Reps"]);
+        let columns = Columns::new(&headers);
+        let row = StringRecord::from(vec![
+            "2023-01-05 18:03:12",
+            "Squat (Barbell)",
+            "1",
+            "225",
+            "5",
+        ]);
+        let set = parse_strong_row(&row, &columns, &options, Units::Imperial)
+            .unwrap()
+            .unwrap();
+        assert_eq!(set.exercise, "Squat (Barbell)");
+        assert_eq!(set.reps, Some(5));
+        assert!((set.weight.unwrap() - 225.0).abs() < 1e-9);
+        let warmup = StringRecord::from(vec![
+            "2023-01-05 18:03:12",
+            "Squat (Barbell)",
+            "W",
+            "95",
+            "5",
+        ]);
+        assert!(
+            parse_strong_row(&warmup, &columns, &options, Units::Imperial)
+                .unwrap()
+                .is_none()
+        );
+
+        let headers = StringRecord::from(vec![
+            "start_time",
+            "exercise_title",
+            "set_type",
+            "weight_kg",
+            "reps",
+        ]);
+        let columns = Columns::new(&headers);
+        let row = StringRecord::from(vec![
+            "26 Mar 2024, 18:03",
+            "Bench Press",
+            "normal",
+            "80",
+            "8",
+        ]);
+        let set = parse_hevy_row(&row, &columns, &options, Units::Metric)
+            .unwrap()
+            .unwrap();
+        assert!((set.weight.unwrap() - 80.0).abs() < 1e-9);
+        let bad = StringRecord::from(vec!["whenever", "Bench Press", "normal", "80", "8"]);
+        assert!(parse_hevy_row(&bad, &columns, &options, Units::Metric).is_err());
+    }
+}
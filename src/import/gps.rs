@@ -0,0 +1,382 @@
+//! GPX and TCX import for cardio workouts (`ta import gpx|tcx`).
+//!
+//! Each file becomes one Cardio workout. Distance, moving time and elevation gain are
+//! computed from the track points; the route summary is kept in the workout notes.
+//! A directory imports every file with the matching extension, skipping files whose
+//! start time is already logged for the exercise.
+
+use super::ImportReport;
+use crate::journal::{self, Change, WorkoutSnapshot};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use roxmltree::{Document, Node};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use task_athlete_lib::{AddWorkoutParams, AppService, ExerciseType, Units, WorkoutFilters};
+
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+/// Slower segments (in m/s) count as stopped when computing moving time.
+const MOVING_SPEED_THRESHOLD: f64 = 0.5;
+/// Elevation changes smaller than this (in m) are treated as GPS noise.
+const ELEVATION_NOISE_M: f64 = 3.0;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackFormat {
+    Gpx,
+    Tcx,
+}
+
+impl TrackFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            TrackFormat::Gpx => "gpx",
+            TrackFormat::Tcx => "tcx",
+        }
+    }
+}
+
+impl fmt::Display for TrackFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.extension().to_uppercase())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrackPoint {
+    pub time: Option<DateTime<Utc>>,
+    /// Latitude and longitude in degrees; missing for indoor recordings.
+    pub position: Option<(f64, f64)>,
+    /// Elevation in metres.
+    pub elevation: Option<f64>,
+    /// Cumulative distance in metres as recorded by the device (TCX only).
+    pub distance: Option<f64>,
+}
+
+/// Totals of a recorded route.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RouteSummary {
+    pub start: DateTime<Utc>,
+    pub distance_km: f64,
+    pub moving_seconds: f64,
+    pub elevation_gain_m: f64,
+}
+
+impl RouteSummary {
+    /// One-line description stored in the workout notes.
+    pub fn describe(&self, source: &str) -> String {
+        format!(
+            "Route from {}: {:.2} km, {} moving, {:.0} m elevation gain",
+            source,
+            self.distance_km,
+            crate::session::format_duration(chrono::Duration::seconds(
+                self.moving_seconds.round() as i64
+            )),
+            self.elevation_gain_m
+        )
+    }
+}
+
+/// Options of a GPX/TCX import.
+pub struct TrackImportOptions<'a> {
+    pub format: TrackFormat,
+    /// Exercise to log the workouts as; created as Cardio if it doesn't exist.
+    pub exercise: &'a str,
+    /// Notes added before the route summary.
+    pub notes: Option<&'a str>,
+    pub dry_run: bool,
+}
+
+/// Imports a track file, or every track file of the format in a directory.
+pub fn import_tracks(
+    service: &mut AppService,
+    path: &Path,
+    options: &TrackImportOptions,
+) -> Result<ImportReport> {
+    let files = track_files(path, options.format)?;
+    let mut report = ImportReport::new(options.dry_run);
+
+    let canonical_name = service
+        .get_exercise_by_identifier_service(options.exercise)?
+        .map(|def| def.name);
+    let mut existing_starts: HashSet<i64> = match &canonical_name {
+        Some(name) => service
+            .list_workouts(&WorkoutFilters {
+                exercise_name: Some(name),
+                date: None,
+                exercise_type: None,
+                muscle: None,
+                limit: None,
+            })?
+            .iter()
+            .map(|w| w.timestamp.timestamp())
+            .collect(),
+        None => HashSet::new(),
+    };
+
+    for file in files {
+        let file_name = file.file_name().map_or_else(
+            || file.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        let summary = fs::read_to_string(&file)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| match options.format {
+                TrackFormat::Gpx => parse_gpx(&contents),
+                TrackFormat::Tcx => parse_tcx(&contents),
+            })
+            .and_then(|points| summarize(&points));
+        let summary = match summary {
+            Ok(summary) => summary,
+            Err(e) => {
+                report.error(0, format!("{}: {}", file_name, e));
+                continue;
+            }
+        };
+
+        if existing_starts.contains(&summary.start.timestamp()) {
+            report.duplicates += 1;
+            continue;
+        }
+
+        let description = summary.describe(&file_name);
+        if !options.dry_run {
+            let notes = match options.notes {
+                Some(notes) => format!("{}\n{}", notes, description),
+                None => description.clone(),
+            };
+            let distance = match service.config.units {
+                Units::Metric => summary.distance_km,
+                Units::Imperial => summary.distance_km * task_athlete_lib::KM_TO_MILE,
+            };
+            let params = AddWorkoutParams {
+                exercise_identifier: options.exercise,
+                date: summary.start,
+                sets: None,
+                reps: None,
+                weight: None,
+                distance: Some(distance),
+                duration: Some(((summary.moving_seconds / 60.0).round() as i64).max(1)),
+                notes: Some(notes),
+                bodyweight_to_use: None,
+                implicit_type: Some(ExerciseType::Cardio),
+                implicit_muscles: None,
+            };
+            let mut snapshot = WorkoutSnapshot::from_params(&params, service.config.units);
+            match service.add_workout(params) {
+                Ok((id, _)) => snapshot.id = id,
+                Err(e) => {
+                    report.error(0, format!("{}: could not add workout: {}", file_name, e));
+                    continue;
+                }
+            }
+            if report.changes.is_empty() && canonical_name.is_none() {
+                report.changes.push(Change::Exercise {
+                    before: None,
+                    after: journal::snapshot_exercise(service, options.exercise)?,
+                });
+            }
+            report.changes.push(Change::workout_added(snapshot));
+        }
+        existing_starts.insert(summary.start.timestamp());
+        report.imported += 1;
+        report.imported_items.push(description);
+    }
+    Ok(report)
+}
+
+/// The file itself, or the files with the format's extension in a directory (sorted).
+fn track_files(path: &Path, format: TrackFormat) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(path)
+        .with_context(|| format!("Failed to read directory {:?}", path))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case(format.extension()))
+        })
+        .collect();
+    if files.is_empty() {
+        bail!("No .{} files found in {:?}", format.extension(), path);
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Text of the first child element named `name`.
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|c| c.has_tag_name(name))
+        .and_then(|c| c.text())
+        .map(str::trim)
+}
+
+fn parse_time(value: Option<&str>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Track points of all tracks and segments in a GPX document.
+pub fn parse_gpx(contents: &str) -> Result<Vec<TrackPoint>> {
+    let document = Document::parse(contents).context("Invalid GPX file")?;
+    let points = document
+        .descendants()
+        .filter(|n| n.has_tag_name("trkpt"))
+        .map(|node| {
+            let lat = node.attribute("lat").and_then(|v| v.parse::<f64>().ok());
+            let lon = node.attribute("lon").and_then(|v| v.parse::<f64>().ok());
+            TrackPoint {
+                time: parse_time(child_text(node, "time")),
+                position: lat.zip(lon),
+                elevation: child_text(node, "ele").and_then(|v| v.parse().ok()),
+                distance: None,
+            }
+        })
+        .collect();
+    Ok(points)
+}
+
+/// Track points of all laps in a TCX document.
+pub fn parse_tcx(contents: &str) -> Result<Vec<TrackPoint>> {
+    let document = Document::parse(contents).context("Invalid TCX file")?;
+    let points = document
+        .descendants()
+        .filter(|n| n.has_tag_name("Trackpoint"))
+        .map(|node| {
+            let position = node
+                .children()
+                .find(|c| c.has_tag_name("Position"))
+                .and_then(|p| {
+                    let lat = child_text(p, "LatitudeDegrees")?.parse::<f64>().ok()?;
+                    let lon = child_text(p, "LongitudeDegrees")?.parse::<f64>().ok()?;
+                    Some((lat, lon))
+                });
+            TrackPoint {
+                time: parse_time(child_text(node, "Time")),
+                position,
+                elevation: child_text(node, "AltitudeMeters").and_then(|v| v.parse().ok()),
+                distance: child_text(node, "DistanceMeters").and_then(|v| v.parse().ok()),
+            }
+        })
+        .collect();
+    Ok(points)
+}
+
+/// Great-circle distance between two positions in metres.
+fn haversine_m((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+/// Computes the route totals. Device-recorded distances (TCX) are preferred over
+/// distances computed from positions.
+pub fn summarize(points: &[TrackPoint]) -> Result<RouteSummary> {
+    let Some(start) = points.iter().find_map(|p| p.time) else {
+        bail!("No timestamped track points found");
+    };
+
+    let mut distance_m = 0.0;
+    let mut moving_seconds = 0.0;
+    for pair in points.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        let segment_m = match (a.distance, b.distance, a.position, b.position) {
+            (Some(da), Some(db), _, _) => (db - da).max(0.0),
+            (_, _, Some(pa), Some(pb)) => haversine_m(pa, pb),
+            _ => 0.0,
+        };
+        distance_m += segment_m;
+
+        if let (Some(ta), Some(tb)) = (a.time, b.time) {
+            let seconds = (tb - ta).num_milliseconds() as f64 / 1000.0;
+            if seconds > 0.0 && segment_m / seconds >= MOVING_SPEED_THRESHOLD {
+                moving_seconds += seconds;
+            }
+        }
+    }
+
+    // Count climbs only once they exceed the noise threshold
+    let mut elevation_gain_m = 0.0;
+    let mut reference: Option<f64> = None;
+    for elevation in points.iter().filter_map(|p| p.elevation) {
+        match reference {
+            None => reference = Some(elevation),
+            Some(r) if elevation - r >= ELEVATION_NOISE_M => {
+                elevation_gain_m += elevation - r;
+                reference = Some(elevation);
+            }
+            Some(r) if r - elevation >= ELEVATION_NOISE_M => reference = Some(elevation),
+            Some(_) => {}
+        }
+    }
+
+    Ok(RouteSummary {
+        start,
+        distance_km: distance_m / 1000.0,
+        moving_seconds,
+        elevation_gain_m,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <trk><name>Morning Run</name><trkseg>
+    <trkpt lat="52.0000" lon="13.0000"><ele>30.0</ele><time>2024-05-01T06:00:00Z</time></trkpt>
+    <trkpt lat="52.0010" lon="13.0000"><ele>35.0</ele><time>2024-05-01T06:00:30Z</time></trkpt>
+    <trkpt lat="52.0010" lon="13.0000"><ele>34.0</ele><time>2024-05-01T06:05:30Z</time></trkpt>
+    <trkpt lat="52.0020" lon="13.0000"><ele>40.0</ele><time>2024-05-01T06:06:00Z</time></trkpt>
+  </trkseg></trk>
+</gpx>"#;
+
+    const TCX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<TrainingCenterDatabase xmlns="http://www.garmin.com/xmlschemas/TrainingCenterDatabase/v2">
+  <Activities><Activity Sport="Running"><Lap StartTime="2024-05-02T17:00:00Z"><Track>
+    <Trackpoint><Time>2024-05-02T17:00:00Z</Time><DistanceMeters>0</DistanceMeters></Trackpoint>
+    <Trackpoint><Time>2024-05-02T17:10:00Z</Time><DistanceMeters>2000</DistanceMeters></Trackpoint>
+  </Track></Lap></Activity></Activities>
+</TrainingCenterDatabase>"#;
+
+    #[test]
+    fn test_gpx_summary() {
+        let points = parse_gpx(GPX).unwrap();
+        assert_eq!(points.len(), 4);
+        let summary = summarize(&points).unwrap();
+        assert_eq!(summary.start.to_rfc3339(), "2024-05-01T06:00:00+00:00");
+        // Two segments of 0.001 degrees latitude (~111 m each)
+        assert!((summary.distance_km - 0.2224).abs() < 0.001);
+        // The five minutes standing still don't count as moving time
+        assert_eq!(summary.moving_seconds, 60.0);
+        // +5 and +6 m climbs count; the 1 m dip is noise
+        assert_eq!(summary.elevation_gain_m, 10.0);
+    }
+
+    #[test]
+    fn test_tcx_uses_recorded_distance() {
+        let summary = summarize(&parse_tcx(TCX).unwrap()).unwrap();
+        assert!((summary.distance_km - 2.0).abs() < 1e-9);
+        assert_eq!(summary.moving_seconds, 600.0);
+        assert_eq!(summary.elevation_gain_m, 0.0);
+    }
+
+    #[test]
+    fn test_invalid_tracks() {
+        assert!(parse_gpx("<gpx><trk>").is_err());
+        let untimed = vec![TrackPoint {
+            position: Some((52.0, 13.0)),
+            ..Default::default()
+        }];
+        assert!(summarize(&untimed).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! Importing data from files (`ta import ...`).
+//!
+//! Importers parse a file row by row and hand each row to the library. A bad row is
+//! recorded in the [`ImportReport`] with its line number instead of aborting the import.
+
+pub mod apps;
+pub mod gps;
+pub mod native;
+
+use crate::journal::Change;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use csv::StringRecord;
+use task_athlete_lib::{ExerciseType, Units};
+
+/// Pounds per kilogram, for converting weights between unit systems.
+pub const LBS_PER_KG: f64 = 2.204_622_621_8;
+
+/// Outcome of an import. In a dry run the counts describe what would have happened.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    /// Entries imported (or importable, in a dry run).
+    pub imported: usize,
+    /// Entries skipped because the same entry already exists.
+    pub duplicates: usize,
+    /// Rows deliberately left out, e.g. warm-up sets.
+    pub skipped_rows: usize,
+    /// Exercises defined by the import.
+    pub created_exercises: Vec<String>,
+    /// One-line descriptions of imported entries, for importers that list them.
+    pub imported_items: Vec<String>,
+    pub errors: Vec<RowError>,
+    /// Everything the import changed, for the undo journal. Empty in a dry run.
+    pub changes: Vec<Change>,
+}
+
+impl ImportReport {
+    pub fn new(dry_run: bool) -> Self {
+        ImportReport {
+            dry_run,
+            ..Default::default()
+        }
+    }
+
+    pub fn error(&mut self, line: u64, message: impl Into<String>) {
+        self.errors.push(RowError {
+            line,
+            message: message.into(),
+        });
+    }
+}
+
+/// A row that could not be imported.
+#[derive(Debug)]
+pub struct RowError {
+    /// 1-based line number in the file, or 0 for errors about a whole file.
+    pub line: u64,
+    pub message: String,
+}
+
+/// Column lookup by header name, ignoring case and surrounding whitespace.
+pub struct Columns {
+    headers: Vec<String>,
+}
+
+impl Columns {
+    pub fn new(headers: &StringRecord) -> Self {
+        Columns {
+            headers: headers.iter().map(|h| h.trim().to_lowercase()).collect(),
+        }
+    }
+
+    /// Index of the first column named like one of `names`.
+    pub fn find(&self, names: &[&str]) -> Option<usize> {
+        names.iter().find_map(|name| {
+            let name = name.to_lowercase();
+            self.headers.iter().position(|h| *h == name)
+        })
+    }
+
+    /// Index and lowercase suffix of the first column named `<prefix>_<suffix>`,
+    /// e.g. `Weight_lbs` for prefix `weight`.
+    pub fn find_suffixed(&self, prefix: &str) -> Option<(usize, String)> {
+        let prefix = format!("{}_", prefix.to_lowercase());
+        self.headers.iter().enumerate().find_map(|(i, h)| {
+            h.strip_prefix(&prefix)
+                .map(|suffix| (i, suffix.to_string()))
+        })
+    }
+}
+
+/// Trimmed value of a column, `None` if the column is missing or the cell is empty.
+pub fn field(record: &StringRecord, index: Option<usize>) -> Option<&str> {
+    index
+        .and_then(|i| record.get(i))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+/// Line number of a record for error reports.
+pub fn line_of(record: &StringRecord) -> u64 {
+    record.position().map_or(0, |p| p.line())
+}
+
+pub fn parse_number<T: std::str::FromStr>(
+    value: Option<&str>,
+    column: &str,
+) -> Result<Option<T>, String> {
+    value
+        .map(|v| {
+            v.parse::<T>()
+                .map_err(|_| format!("Invalid {} '{}'.", column, v))
+        })
+        .transpose()
+}
+
+/// Parses an RFC 3339 timestamp, or a plain date (taken as noon UTC).
+pub fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(12, 0, 0))
+        .map(|naive| Utc.from_utc_datetime(&naive))
+        .ok_or_else(|| format!("Invalid timestamp '{}'.", value))
+}
+
+/// Parses an exercise type as written by the export (`Resistance`, `Cardio`, `BodyWeight`),
+/// ignoring case, spaces, dashes and underscores.
+pub fn parse_exercise_type(value: &str) -> Option<ExerciseType> {
+    let normalized: String = value
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .collect::<String>()
+        .to_lowercase();
+    match normalized.as_str() {
+        "resistance" => Some(ExerciseType::Resistance),
+        "cardio" => Some(ExerciseType::Cardio),
+        "bodyweight" => Some(ExerciseType::BodyWeight),
+        _ => None,
+    }
+}
+
+/// Bodyweight to use for a bodyweight exercise logged at `timestamp`: the entry logged
+/// closest before it, else the earliest entry, else 0. `bodyweights` must be sorted by time.
+pub fn bodyweight_at(bodyweights: &[(i64, DateTime<Utc>, f64)], timestamp: DateTime<Utc>) -> f64 {
+    bodyweights
+        .iter()
+        .rev()
+        .find(|(_, ts, _)| *ts <= timestamp)
+        .or(bodyweights.first())
+        .map_or(0.0, |(_, _, weight)| *weight)
+}
+
+/// Converts a weight written in `unit` (`kg`, `lb` or `lbs`) to the configured units.
+pub fn convert_weight(value: f64, unit: &str, units: Units) -> Result<f64, String> {
+    let in_kg = match unit {
+        "kg" | "kgs" => value,
+        "lb" | "lbs" => value / LBS_PER_KG,
+        other => return Err(format!("Unknown weight unit '{}'.", other)),
+    };
+    Ok(match units {
+        Units::Metric => in_kg,
+        Units::Imperial => in_kg * LBS_PER_KG,
+    })
+}
+
+/// Converts a distance written in `unit` (`km`, `mi` or `m`) to the configured units.
+pub fn convert_distance(value: f64, unit: &str, units: Units) -> Result<f64, String> {
+    let in_km = match unit {
+        "km" => value,
+        "mi" | "mile" | "miles" => value / task_athlete_lib::KM_TO_MILE,
+        "m" => value / 1000.0,
+        other => return Err(format!("Unknown distance unit '{}'.", other)),
+    };
+    Ok(match units {
+        Units::Metric => in_km,
+        Units::Imperial => in_km * task_athlete_lib::KM_TO_MILE,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_columns() {
+        let headers = StringRecord::from(vec!["ID", " Timestamp_UTC", "Weight_lbs", "Distance_mi"]);
+        let columns = Columns::new(&headers);
+        assert_eq!(columns.find(&["timestamp_utc"]), Some(1));
+        assert_eq!(columns.find(&["Date", "ID"]), Some(0));
+        assert_eq!(
+            columns.find_suffixed("Weight"),
+            Some((2, "lbs".to_string()))
+        );
+        assert_eq!(
+            columns.find_suffixed("distance"),
+            Some((3, "mi".to_string()))
+        );
+        assert_eq!(columns.find_suffixed("duration"), None);
+    }
+
+    #[test]
+    fn test_unit_conversion() {
+        assert!(approx(
+            convert_weight(220.462_262_18, "lbs", Units::Metric).unwrap(),
+            100.0
+        ));
+        assert!(approx(
+            convert_weight(100.0, "kg", Units::Metric).unwrap(),
+            100.0
+        ));
+        assert!(approx(
+            convert_weight(100.0, "kg", Units::Imperial).unwrap(),
+            220.462_262_18
+        ));
+        assert!(approx(
+            convert_distance(task_athlete_lib::KM_TO_MILE, "mi", Units::Metric).unwrap(),
+            1.0
+        ));
+        assert!(approx(
+            convert_distance(5000.0, "m", Units::Metric).unwrap(),
+            5.0
+        ));
+        assert!(convert_weight(1.0, "stone", Units::Metric).is_err());
+    }
+
+    #[test]
+    fn test_parse_timestamp_and_type() {
+        let ts = parse_timestamp("2024-03-01T18:30:00+00:00").unwrap();
+        assert_eq!(ts.to_rfc3339(), "2024-03-01T18:30:00+00:00");
+        let noon = parse_timestamp("2024-03-01").unwrap();
+        assert_eq!(noon.to_rfc3339(), "2024-03-01T12:00:00+00:00");
+        assert!(parse_timestamp("yesterday-ish").is_err());
+
+        assert_eq!(
+            parse_exercise_type("BodyWeight"),
+            Some(ExerciseType::BodyWeight)
+        );
+        assert_eq!(
+            parse_exercise_type("body-weight"),
+            Some(ExerciseType::BodyWeight)
+        );
+        assert_eq!(parse_exercise_type("cardio"), Some(ExerciseType::Cardio));
+        assert_eq!(parse_exercise_type("yoga"), None);
+    }
+}
@@ -0,0 +1,434 @@
+//! Import of the CLI's own CSV/TSV export (`ta import csv`).
+//!
+//! Columns are matched by header name, so their order doesn't matter and the `ID`
+//! column is ignored (the database assigns new IDs). Unit-suffixed headers such as
+//! `Weight_lbs` or `Distance_mi` are converted to the configured units.
+
+use super::{
+    bodyweight_at, convert_distance, convert_weight, field, line_of, parse_exercise_type,
+    parse_number, parse_timestamp, Columns, ImportReport,
+};
+use crate::journal::{self, BodyweightSnapshot, Change, WorkoutSnapshot};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use csv::StringRecord;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use task_athlete_lib::{AddWorkoutParams, AppService, ExerciseType, Units, WorkoutFilters};
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsvKind {
+    /// Output of `ta list --format csv`
+    Workouts,
+    /// Output of `ta list-bodyweights --format csv`
+    Bodyweights,
+    /// Output of `ta list-exercises --format csv`
+    Exercises,
+    /// Output of `ta list-aliases --format csv`
+    Aliases,
+}
+
+/// Imports a CSV or TSV file written by the CLI's export.
+pub fn import_csv(
+    service: &mut AppService,
+    path: &Path,
+    kind: CsvKind,
+    dry_run: bool,
+) -> Result<ImportReport> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    // TSV exports are detected from a header line with tabs but no commas
+    let first_line = contents.lines().next().unwrap_or_default();
+    let delimiter = if first_line.contains('\t') && !first_line.contains(',') {
+        b'\t'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+    let columns = Columns::new(reader.headers().context("Failed to read CSV headers")?);
+
+    let mut records = Vec::new();
+    let mut report = ImportReport::new(dry_run);
+    for result in reader.records() {
+        match result {
+            Ok(record) => records.push(record),
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                report.error(line, format!("Malformed row: {}", e));
+            }
+        }
+    }
+
+    match kind {
+        CsvKind::Workouts => import_workouts(service, &columns, &records, &mut report)?,
+        CsvKind::Bodyweights => import_bodyweights(service, &columns, &records, &mut report)?,
+        CsvKind::Exercises => import_exercises(service, &columns, &records, &mut report)?,
+        CsvKind::Aliases => import_aliases(service, &columns, &records, &mut report)?,
+    }
+    report.errors.sort_by_key(|e| e.line);
+    Ok(report)
+}
+
+/// Key used to detect a workout that was already imported.
+fn workout_key(timestamp: DateTime<Utc>, exercise_name: &str) -> (i64, String) {
+    (timestamp.timestamp(), exercise_name.to_lowercase())
+}
+
+/// Column positions of a workout export.
+struct WorkoutColumns {
+    timestamp: usize,
+    exercise: usize,
+    type_: Option<usize>,
+    sets: Option<usize>,
+    reps: Option<usize>,
+    weight: Option<(usize, String)>,
+    duration: Option<usize>,
+    distance: Option<(usize, String)>,
+    notes: Option<usize>,
+}
+
+impl WorkoutColumns {
+    fn new(columns: &Columns) -> Result<Self> {
+        Ok(WorkoutColumns {
+            timestamp: columns
+                .find(&["Timestamp_UTC", "Timestamp", "Date"])
+                .context("Missing Timestamp_UTC column")?,
+            exercise: columns
+                .find(&["Exercise", "Exercise_Name"])
+                .context("Missing Exercise column")?,
+            type_: columns.find(&["Type"]),
+            sets: columns.find(&["Sets"]),
+            reps: columns.find(&["Reps"]),
+            weight: columns.find_suffixed("Weight"),
+            duration: columns.find(&["Duration_min", "Duration"]),
+            distance: columns.find_suffixed("Distance"),
+            notes: columns.find(&["Notes"]),
+        })
+    }
+}
+
+/// A workout row with values converted to the configured units.
+struct WorkoutRow {
+    timestamp: DateTime<Utc>,
+    exercise: String,
+    implicit_type: Option<ExerciseType>,
+    sets: Option<i64>,
+    reps: Option<i64>,
+    weight: Option<f64>,
+    duration: Option<i64>,
+    distance: Option<f64>,
+    notes: Option<String>,
+}
+
+fn parse_workout_row(
+    record: &StringRecord,
+    cols: &WorkoutColumns,
+    units: Units,
+) -> Result<WorkoutRow, String> {
+    let timestamp =
+        parse_timestamp(field(record, Some(cols.timestamp)).ok_or("Missing timestamp.")?)?;
+    let exercise = field(record, Some(cols.exercise))
+        .ok_or("Missing exercise name.")?
+        .to_string();
+    let implicit_type = field(record, cols.type_)
+        .map(|t| parse_exercise_type(t).ok_or_else(|| format!("Unknown exercise type '{}'.", t)))
+        .transpose()?;
+    let weight = match &cols.weight {
+        Some((i, unit)) => parse_number::<f64>(field(record, Some(*i)), "weight")?
+            .map(|w| convert_weight(w, unit, units))
+            .transpose()?,
+        None => None,
+    };
+    let distance = match &cols.distance {
+        Some((i, unit)) => parse_number::<f64>(field(record, Some(*i)), "distance")?
+            .map(|d| convert_distance(d, unit, units))
+            .transpose()?,
+        None => None,
+    };
+    Ok(WorkoutRow {
+        timestamp,
+        exercise,
+        implicit_type,
+        sets: parse_number(field(record, cols.sets), "sets")?,
+        reps: parse_number(field(record, cols.reps), "reps")?,
+        weight,
+        duration: parse_number(field(record, cols.duration), "duration")?,
+        distance,
+        notes: field(record, cols.notes).map(str::to_string),
+    })
+}
+
+fn import_workouts(
+    service: &mut AppService,
+    columns: &Columns,
+    records: &[StringRecord],
+    report: &mut ImportReport,
+) -> Result<()> {
+    let units = service.config.units;
+    let cols = WorkoutColumns::new(columns)?;
+
+    let mut existing: HashSet<(i64, String)> = service
+        .list_workouts(&WorkoutFilters {
+            exercise_name: None,
+            date: None,
+            exercise_type: None,
+            muscle: None,
+            limit: None,
+        })?
+        .iter()
+        .map(|w| workout_key(w.timestamp, &w.exercise_name))
+        .collect();
+    let mut bodyweights = service.list_bodyweights(u32::MAX)?;
+    bodyweights.sort_by_key(|(_, ts, _)| *ts);
+    // Exercise definitions looked up so far, by the name used in the file
+    let mut definitions: HashMap<String, Option<(String, ExerciseType)>> = HashMap::new();
+
+    for record in records {
+        let line = line_of(record);
+        let row = match parse_workout_row(record, &cols, units) {
+            Ok(row) => row,
+            Err(message) => {
+                report.error(line, message);
+                continue;
+            }
+        };
+        let (timestamp, implicit_type) = (row.timestamp, row.implicit_type);
+        let exercise = row.exercise.clone();
+
+        if !definitions.contains_key(&exercise) {
+            let definition = service
+                .get_exercise_by_identifier_service(&exercise)?
+                .map(|def| (def.name, def.type_));
+            definitions.insert(exercise.clone(), definition);
+        }
+        let definition = definitions[&exercise].clone();
+        let canonical_name = definition
+            .as_ref()
+            .map_or(exercise.as_str(), |(name, _)| name.as_str());
+        let key = workout_key(timestamp, canonical_name);
+        if existing.contains(&key) {
+            report.duplicates += 1;
+            continue;
+        }
+
+        if definition.is_none() && implicit_type.is_none() {
+            report.error(
+                line,
+                format!(
+                    "Exercise '{}' is not defined and the row has no Type to create it with.",
+                    exercise
+                ),
+            );
+            continue;
+        }
+
+        // Bodyweight exercises use the bodyweight logged closest before the workout
+        let exercise_type = definition.as_ref().map(|(_, t)| *t).or(implicit_type);
+        let bodyweight_to_use = (exercise_type == Some(ExerciseType::BodyWeight))
+            .then(|| bodyweight_at(&bodyweights, timestamp));
+
+        if !report.dry_run {
+            let params = AddWorkoutParams {
+                exercise_identifier: &exercise,
+                date: timestamp,
+                sets: row.sets,
+                reps: row.reps,
+                weight: row.weight,
+                distance: row.distance,
+                duration: row.duration,
+                notes: row.notes,
+                bodyweight_to_use,
+                implicit_type,
+                implicit_muscles: None,
+            };
+            let mut snapshot = WorkoutSnapshot::from_params(&params, units);
+            match service.add_workout(params) {
+                Ok((id, _)) => snapshot.id = id,
+                Err(e) => {
+                    report.error(line, format!("Could not add workout: {}", e));
+                    continue;
+                }
+            }
+            if definition.is_none() {
+                // The exercise was created by this row; resolve it again next time
+                definitions.remove(&exercise);
+                report.changes.push(Change::Exercise {
+                    before: None,
+                    after: journal::snapshot_exercise(service, &exercise)?,
+                });
+            }
+            report.changes.push(Change::workout_added(snapshot));
+        }
+        existing.insert(key);
+        report.imported += 1;
+    }
+    Ok(())
+}
+
+fn import_bodyweights(
+    service: &mut AppService,
+    columns: &Columns,
+    records: &[StringRecord],
+    report: &mut ImportReport,
+) -> Result<()> {
+    let units = service.config.units;
+    let timestamp_col = columns
+        .find(&["Timestamp_UTC", "Timestamp", "Date"])
+        .context("Missing Timestamp_UTC column")?;
+    let (weight_col, weight_unit) = columns
+        .find_suffixed("Weight")
+        .context("Missing Weight_<unit> column")?;
+
+    let mut existing: HashSet<i64> = service
+        .list_bodyweights(u32::MAX)?
+        .iter()
+        .map(|(_, ts, _)| ts.timestamp())
+        .collect();
+
+    for record in records {
+        let line = line_of(record);
+        let parsed = (|| -> Result<_, String> {
+            let timestamp =
+                parse_timestamp(field(record, Some(timestamp_col)).ok_or("Missing timestamp.")?)?;
+            let weight = parse_number::<f64>(field(record, Some(weight_col)), "weight")?
+                .ok_or("Missing weight.")?;
+            Ok((timestamp, convert_weight(weight, &weight_unit, units)?))
+        })();
+        let (timestamp, weight) = match parsed {
+            Ok(row) => row,
+            Err(message) => {
+                report.error(line, message);
+                continue;
+            }
+        };
+
+        if existing.contains(&timestamp.timestamp()) {
+            report.duplicates += 1;
+            continue;
+        }
+        if !report.dry_run {
+            match service.add_bodyweight_entry(timestamp, weight) {
+                Ok(id) => report.changes.push(Change::Bodyweight {
+                    before: None,
+                    after: Some(BodyweightSnapshot {
+                        id,
+                        timestamp,
+                        weight,
+                    }),
+                }),
+                Err(e) => {
+                    report.error(line, format!("Could not log bodyweight: {}", e));
+                    continue;
+                }
+            }
+        }
+        existing.insert(timestamp.timestamp());
+        report.imported += 1;
+    }
+    Ok(())
+}
+
+fn import_exercises(
+    service: &mut AppService,
+    columns: &Columns,
+    records: &[StringRecord],
+    report: &mut ImportReport,
+) -> Result<()> {
+    let name_col = columns.find(&["Name"]).context("Missing Name column")?;
+    let type_col = columns.find(&["Type"]).context("Missing Type column")?;
+    let muscles_col = columns.find(&["Muscles"]);
+
+    let mut seen: HashSet<String> = HashSet::new();
+    for record in records {
+        let line = line_of(record);
+        let Some(name) = field(record, Some(name_col)) else {
+            report.error(line, "Missing exercise name.");
+            continue;
+        };
+        let Some(exercise_type) = field(record, Some(type_col)).and_then(parse_exercise_type)
+        else {
+            report.error(line, format!("Missing or unknown type for '{}'.", name));
+            continue;
+        };
+
+        let exists = seen.contains(&name.to_lowercase())
+            || service.get_exercise_by_identifier_service(name)?.is_some();
+        if exists {
+            report.duplicates += 1;
+            continue;
+        }
+        if !report.dry_run {
+            let muscles = field(record, muscles_col);
+            if let Err(e) = service.create_exercise(name, exercise_type, None, muscles) {
+                report.error(line, format!("Could not create exercise: {}", e));
+                continue;
+            }
+            report
+                .changes
+                .push(Change::exercise_created(name, exercise_type, muscles));
+        }
+        seen.insert(name.to_lowercase());
+        report.imported += 1;
+    }
+    Ok(())
+}
+
+fn import_aliases(
+    service: &mut AppService,
+    columns: &Columns,
+    records: &[StringRecord],
+    report: &mut ImportReport,
+) -> Result<()> {
+    let alias_col = columns.find(&["Alias"]).context("Missing Alias column")?;
+    let exercise_col = columns
+        .find(&["Canonical_Exercise_Name", "Exercise"])
+        .context("Missing Canonical_Exercise_Name column")?;
+
+    let mut existing: HashSet<String> = service
+        .list_aliases()?
+        .into_keys()
+        .map(|alias| alias.to_lowercase())
+        .collect();
+
+    for record in records {
+        let line = line_of(record);
+        let (Some(alias), Some(exercise)) = (
+            field(record, Some(alias_col)),
+            field(record, Some(exercise_col)),
+        ) else {
+            report.error(line, "Missing alias or exercise name.");
+            continue;
+        };
+
+        if existing.contains(&alias.to_lowercase()) {
+            report.duplicates += 1;
+            continue;
+        }
+        if service
+            .get_exercise_by_identifier_service(exercise)?
+            .is_none()
+        {
+            report.error(line, format!("Exercise '{}' not found.", exercise));
+            continue;
+        }
+        if !report.dry_run {
+            if let Err(e) = service.create_alias(alias, exercise) {
+                report.error(line, format!("Could not create alias: {}", e));
+                continue;
+            }
+            report.changes.push(Change::Alias {
+                alias: alias.to_string(),
+                before: None,
+                after: Some(exercise.to_string()),
+            });
+        }
+        existing.insert(alias.to_lowercase());
+        report.imported += 1;
+    }
+    Ok(())
+}
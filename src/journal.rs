@@ -0,0 +1,1129 @@
+//! Operation journal behind `ta undo` and `ta history`.
+//!
+//! Every mutating command appends one entry with before/after snapshots of what it
+//! changed: `before` is `None` for something created, `after` is `None` for something
+//! deleted. Undo reverts entries newest first through the library, like Taskwarrior's
+//! undo. The journal is a JSON file next to the config file, kept apart from the CLI
+//! store so pruning it never touches other data.
+
+use crate::import::parse_exercise_type;
+use crate::load::parse_load_band;
+use crate::muscles::parse_set_range;
+use crate::session::Session;
+use crate::sets::{self, SetEntry};
+use crate::store::CliStore;
+use crate::strength::E1rmFormula;
+use crate::template::Template;
+use crate::timer;
+use crate::trash::TrashItem;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use task_athlete_lib::{
+    AddWorkoutParams, AppService, EditWorkoutParams, ExerciseDefinition, ExerciseType, Units,
+    Workout, WorkoutFilters, KM_TO_MILE,
+};
+
+pub const JOURNAL_FILE_NAME: &str = "journal.json";
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct Journal {
+    #[serde(skip)]
+    path: PathBuf,
+
+    /// Recorded operations, oldest first.
+    #[serde(default)]
+    pub entries: Vec<JournalEntry>,
+}
+
+/// One mutating command and everything it changed.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub timestamp: DateTime<Utc>,
+    /// The command line that made the changes, e.g. `delete-workout 12`.
+    pub command: String,
+    /// Changes in the order they were made.
+    pub changes: Vec<Change>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    Workout {
+        before: Option<WorkoutSnapshot>,
+        after: Option<WorkoutSnapshot>,
+    },
+    Exercise {
+        before: Option<ExerciseSnapshot>,
+        after: Option<ExerciseSnapshot>,
+    },
+    /// An alias and the exercise it points to.
+    Alias {
+        alias: String,
+        before: Option<String>,
+        after: Option<String>,
+    },
+    Bodyweight {
+        before: Option<BodyweightSnapshot>,
+        after: Option<BodyweightSnapshot>,
+    },
+    Setting {
+        setting: Setting,
+        before: String,
+        after: String,
+    },
+    Session {
+        before: Option<Session>,
+        after: Option<Session>,
+    },
+    Template {
+        before: Option<Template>,
+        after: Option<Template>,
+    },
+    Trash {
+        before: Option<TrashItem>,
+        after: Option<TrashItem>,
+    },
+}
+
+/// A workout as stored by the library, plus its individually logged sets.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WorkoutSnapshot {
+    pub id: i64,
+    pub timestamp: DateTime<Utc>,
+    pub exercise_name: String,
+    pub exercise_type: Option<String>,
+    pub sets: Option<i64>,
+    pub reps: Option<i64>,
+    /// Additional weight in the units configured when the workout was logged.
+    pub weight: Option<f64>,
+    pub duration_minutes: Option<i64>,
+    /// Distance in km, as stored by the library.
+    pub distance_km: Option<f64>,
+    pub bodyweight: Option<f64>,
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logged_sets: Vec<SetEntry>,
+    /// Rests recorded with `ta rest`, in seconds.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rests: Vec<i64>,
+    /// Training session the workout belonged to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<i64>,
+    /// Whether the workout is listed among its session's PBs.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub session_pb: bool,
+}
+
+/// An exercise definition together with the aliases pointing to it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExerciseSnapshot {
+    pub name: String,
+    pub type_: String,
+    pub muscles: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BodyweightSnapshot {
+    pub id: i64,
+    pub timestamp: DateTime<Utc>,
+    pub weight: f64,
+}
+
+/// A configuration value changed by a `set-*` command.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Setting {
+    Units,
+    TargetBodyweight,
+    PbNotifications,
+    PbNotifyWeight,
+    PbNotifyReps,
+    PbNotifyDuration,
+    PbNotifyDistance,
+    StreakInterval,
+    E1rmFormula,
+    MuscleTargets,
+    LoadBand,
+}
+
+/// Written in place of a value that is not set.
+const UNSET: &str = "none";
+
+impl fmt::Display for Setting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Setting::Units => "units",
+            Setting::TargetBodyweight => "target bodyweight",
+            Setting::PbNotifications => "PB notifications",
+            Setting::PbNotifyWeight => "weight PB notifications",
+            Setting::PbNotifyReps => "reps PB notifications",
+            Setting::PbNotifyDuration => "duration PB notifications",
+            Setting::PbNotifyDistance => "distance PB notifications",
+            Setting::StreakInterval => "streak interval",
+            Setting::E1rmFormula => "e1RM formula",
+            Setting::MuscleTargets => "muscle targets",
+            Setting::LoadBand => "load ratio band",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl Setting {
+    /// The setting's current value as written to the journal.
+    pub fn current(self, service: &AppService) -> Result<String> {
+        let config = &service.config;
+        let value = match self {
+            Setting::Units => format!("{:?}", config.units),
+            Setting::TargetBodyweight => config
+                .target_bodyweight
+                .map_or(UNSET.to_string(), |w| w.to_string()),
+            Setting::PbNotifications => config
+                .pb_notifications
+                .enabled
+                .map_or(UNSET.to_string(), |e| e.to_string()),
+            Setting::PbNotifyWeight => config.pb_notifications.notify_weight.to_string(),
+            Setting::PbNotifyReps => config.pb_notifications.notify_reps.to_string(),
+            Setting::PbNotifyDuration => config.pb_notifications.notify_duration.to_string(),
+            Setting::PbNotifyDistance => config.pb_notifications.notify_distance.to_string(),
+            Setting::StreakInterval => config.streak_interval_days.to_string(),
+            Setting::E1rmFormula => CliStore::load(service)?.settings.e1rm_formula.to_string(),
+            Setting::MuscleTargets => {
+                let targets = CliStore::load(service)?.settings.muscle_targets;
+                if targets.is_empty() {
+                    UNSET.to_string()
+                } else {
+                    targets
+                        .iter()
+                        .map(|(muscle, range)| format!("{muscle}={range}"))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                }
+            }
+            Setting::LoadBand => CliStore::load(service)?.settings.load_band.to_string(),
+        };
+        Ok(value)
+    }
+
+    /// Writes a value previously returned by [`Setting::current`].
+    fn restore(self, service: &mut AppService, store: &mut CliStore, value: &str) -> Result<()> {
+        let parse_bool = |v: &str| -> Result<bool> {
+            v.parse()
+                .with_context(|| format!("Invalid value '{}' for {}", v, self))
+        };
+        let result = match self {
+            Setting::Units => match value {
+                "Metric" => service.set_units(Units::Metric),
+                "Imperial" => service.set_units(Units::Imperial),
+                _ => bail!("Invalid value '{}' for {}", value, self),
+            },
+            Setting::TargetBodyweight => {
+                let weight = match value {
+                    UNSET => None,
+                    w => Some(
+                        w.parse::<f64>()
+                            .with_context(|| format!("Invalid value '{}' for {}", w, self))?,
+                    ),
+                };
+                service.set_target_bodyweight(weight)
+            }
+            Setting::PbNotifications => match value {
+                // The library has no way to go back to "not asked yet"
+                UNSET => service.set_pb_notification_enabled(false),
+                v => service.set_pb_notification_enabled(parse_bool(v)?),
+            },
+            Setting::PbNotifyWeight => service.set_pb_notify_weight(parse_bool(value)?),
+            Setting::PbNotifyReps => service.set_pb_notify_reps(parse_bool(value)?),
+            Setting::PbNotifyDuration => service.set_pb_notify_duration(parse_bool(value)?),
+            Setting::PbNotifyDistance => service.set_pb_notify_distance(parse_bool(value)?),
+            Setting::StreakInterval => {
+                let days = value
+                    .parse::<u32>()
+                    .with_context(|| format!("Invalid value '{}' for {}", value, self))?;
+                service.set_streak_interval(days)
+            }
+            Setting::E1rmFormula => {
+                store.settings.e1rm_formula = E1rmFormula::from_str(value, true).map_err(|e| {
+                    anyhow::anyhow!("Invalid value '{}' for {}: {}", value, self, e)
+                })?;
+                Ok(())
+            }
+            Setting::MuscleTargets => {
+                let mut targets = BTreeMap::new();
+                for target in value.split(',').filter(|t| !t.is_empty() && *t != UNSET) {
+                    let parsed = target.split_once('=').and_then(|(muscle, range)| {
+                        Some((muscle.to_string(), parse_set_range(range).ok()?))
+                    });
+                    let (muscle, range) = parsed
+                        .with_context(|| format!("Invalid value '{}' for {}", value, self))?;
+                    targets.insert(muscle, range);
+                }
+                store.settings.muscle_targets = targets;
+                Ok(())
+            }
+            Setting::LoadBand => {
+                store.settings.load_band = parse_load_band(value).map_err(|e| {
+                    anyhow::anyhow!("Invalid value '{}' for {}: {}", value, self, e)
+                })?;
+                Ok(())
+            }
+        };
+        result.map_err(|e| anyhow::anyhow!("Could not restore {}: {}", self, e))
+    }
+}
+
+impl WorkoutSnapshot {
+    pub fn new(workout: &Workout, logged_sets: Option<&Vec<SetEntry>>) -> Self {
+        WorkoutSnapshot {
+            id: workout.id,
+            timestamp: workout.timestamp,
+            exercise_name: workout.exercise_name.clone(),
+            exercise_type: workout.exercise_type.map(|t| t.to_string()),
+            sets: workout.sets,
+            reps: workout.reps,
+            weight: workout.weight,
+            duration_minutes: workout.duration_minutes,
+            distance_km: workout.distance,
+            bodyweight: workout.bodyweight,
+            notes: workout.notes.clone(),
+            logged_sets: logged_sets.cloned().unwrap_or_default(),
+            rests: Vec::new(),
+            session_id: None,
+            session_pb: false,
+        }
+    }
+
+    /// Snapshot of a workout about to be added with `params`; `id` is 0 until the
+    /// library has assigned one.
+    pub fn from_params(params: &AddWorkoutParams, units: Units) -> Self {
+        WorkoutSnapshot {
+            id: 0,
+            timestamp: params.date,
+            exercise_name: params.exercise_identifier.to_string(),
+            exercise_type: params.implicit_type.map(|t| t.to_string()),
+            sets: params.sets,
+            reps: params.reps,
+            weight: params.weight,
+            duration_minutes: params.duration,
+            distance_km: params.distance.map(|d| match units {
+                Units::Metric => d,
+                Units::Imperial => d / KM_TO_MILE,
+            }),
+            bodyweight: params.bodyweight_to_use,
+            notes: params.notes.clone(),
+            logged_sets: Vec::new(),
+            rests: Vec::new(),
+            session_id: None,
+            session_pb: false,
+        }
+    }
+
+    /// Short description, e.g. `#12 Bench Press on 2025-03-01`.
+    pub fn label(&self) -> String {
+        format!(
+            "#{} {} on {}",
+            self.id,
+            self.exercise_name,
+            self.timestamp.format("%Y-%m-%d")
+        )
+    }
+
+    /// Human-readable list of the fields that differ from `other`.
+    fn differences(&self, other: &WorkoutSnapshot) -> Vec<String> {
+        fn show<T: fmt::Display>(value: &Option<T>) -> String {
+            value.as_ref().map_or("-".to_string(), |v| v.to_string())
+        }
+        let mut diffs = Vec::new();
+        let mut compare = |field: &str, from: String, to: String| {
+            if from != to {
+                diffs.push(format!("{field} {from} -> {to}"));
+            }
+        };
+        compare(
+            "exercise",
+            self.exercise_name.clone(),
+            other.exercise_name.clone(),
+        );
+        compare(
+            "date",
+            self.timestamp.format("%Y-%m-%d").to_string(),
+            other.timestamp.format("%Y-%m-%d").to_string(),
+        );
+        compare("sets", show(&self.sets), show(&other.sets));
+        compare("reps", show(&self.reps), show(&other.reps));
+        compare("weight", show(&self.weight), show(&other.weight));
+        compare(
+            "duration",
+            show(&self.duration_minutes),
+            show(&other.duration_minutes),
+        );
+        compare(
+            "distance (km)",
+            show(&self.distance_km),
+            show(&other.distance_km),
+        );
+        compare(
+            "bodyweight",
+            show(&self.bodyweight),
+            show(&other.bodyweight),
+        );
+        compare("notes", show(&self.notes), show(&other.notes));
+        compare(
+            "logged sets",
+            sets::format_sets(&self.logged_sets),
+            sets::format_sets(&other.logged_sets),
+        );
+        let show_rests = |rests: &[i64]| {
+            let rests: Vec<String> = rests.iter().map(|r| timer::format_clock(*r)).collect();
+            if rests.is_empty() {
+                "-".to_string()
+            } else {
+                rests.join(", ")
+            }
+        };
+        compare("rests", show_rests(&self.rests), show_rests(&other.rests));
+        diffs
+    }
+
+    /// Whether `edit_workout` can turn `current` back into this snapshot. It can't move
+    /// a workout within a day or clear a field.
+    fn restorable_by_edit(&self, current: &WorkoutSnapshot) -> bool {
+        self.timestamp == current.timestamp
+            && !(self.sets.is_none() && current.sets.is_some())
+            && !(self.reps.is_none() && current.reps.is_some())
+            && !(self.weight.is_none() && current.weight.is_some())
+            && !(self.duration_minutes.is_none() && current.duration_minutes.is_some())
+            && !(self.distance_km.is_none() && current.distance_km.is_some())
+            && !(self.bodyweight.is_none() && current.bodyweight.is_some())
+            && !(self.notes.is_none() && current.notes.is_some())
+    }
+}
+
+impl ExerciseSnapshot {
+    pub fn new(definition: &ExerciseDefinition, aliases: Vec<String>) -> Self {
+        ExerciseSnapshot {
+            name: definition.name.clone(),
+            type_: definition.type_.to_string(),
+            muscles: definition.muscles.clone(),
+            aliases,
+        }
+    }
+}
+
+impl Change {
+    /// A workout added by the running command.
+    pub fn workout_added(snapshot: WorkoutSnapshot) -> Self {
+        Change::Workout {
+            before: None,
+            after: Some(snapshot),
+        }
+    }
+
+    /// An exercise created by the running command.
+    pub fn exercise_created(name: &str, type_: ExerciseType, muscles: Option<&str>) -> Self {
+        Change::Exercise {
+            before: None,
+            after: Some(ExerciseSnapshot {
+                name: name.trim().to_string(),
+                type_: type_.to_string(),
+                muscles: muscles.map(str::to_string),
+                aliases: Vec::new(),
+            }),
+        }
+    }
+
+    /// One-line description for `ta history`.
+    pub fn describe(&self) -> String {
+        match self {
+            Change::Workout {
+                before: None,
+                after: Some(after),
+            } => format!("Added workout {}", after.label()),
+            Change::Workout {
+                before: Some(before),
+                after: None,
+            } => format!("Deleted workout {}", before.label()),
+            Change::Workout {
+                before: Some(before),
+                after: Some(after),
+            } => format!(
+                "Edited workout {}: {}",
+                after.label(),
+                before.differences(after).join(", ")
+            ),
+            Change::Exercise {
+                before: None,
+                after: Some(after),
+            } => format!("Created exercise '{}' ({})", after.name, after.type_),
+            Change::Exercise {
+                before: Some(before),
+                after: None,
+            } if before.aliases.is_empty() => format!("Deleted exercise '{}'", before.name),
+            Change::Exercise {
+                before: Some(before),
+                after: None,
+            } => format!(
+                "Deleted exercise '{}' and its aliases {}",
+                before.name,
+                before.aliases.join(", ")
+            ),
+            Change::Exercise {
+                before: Some(before),
+                after: Some(after),
+            } => {
+                let mut diffs = Vec::new();
+                if before.name != after.name {
+                    diffs.push(format!("name {} -> {}", before.name, after.name));
+                }
+                if before.type_ != after.type_ {
+                    diffs.push(format!("type {} -> {}", before.type_, after.type_));
+                }
+                if before.muscles != after.muscles {
+                    diffs.push(format!(
+                        "muscles {} -> {}",
+                        before.muscles.as_deref().unwrap_or("-"),
+                        after.muscles.as_deref().unwrap_or("-")
+                    ));
+                }
+                format!("Edited exercise '{}': {}", before.name, diffs.join(", "))
+            }
+            Change::Alias {
+                alias,
+                before,
+                after,
+            } => match (before, after) {
+                (None, Some(exercise)) => format!("Created alias '{}' for '{}'", alias, exercise),
+                (Some(exercise), None) => format!("Deleted alias '{}' for '{}'", alias, exercise),
+                (Some(from), Some(to)) => {
+                    format!("Pointed alias '{}' from '{}' to '{}'", alias, from, to)
+                }
+                (None, None) => format!("Touched alias '{}'", alias),
+            },
+            Change::Bodyweight { before, after } => match (before, after) {
+                (None, Some(after)) => format!(
+                    "Logged bodyweight {} on {}",
+                    after.weight,
+                    after.timestamp.format("%Y-%m-%d")
+                ),
+                (Some(before), _) => format!(
+                    "Deleted bodyweight entry #{} ({} on {})",
+                    before.id,
+                    before.weight,
+                    before.timestamp.format("%Y-%m-%d")
+                ),
+                (None, None) => "Touched bodyweight entry".to_string(),
+            },
+            Change::Setting {
+                setting,
+                before,
+                after,
+            } => format!("Changed {}: {} -> {}", setting, before, after),
+            Change::Session { before, after } => match (before, after) {
+                (None, Some(after)) => format!("Started session {}", after.label()),
+                (Some(before), Some(after)) if before.is_open() && !after.is_open() => {
+                    format!("Ended session {}", after.label())
+                }
+                (_, Some(after)) => format!("Edited session {}", after.label()),
+                (Some(before), None) => format!("Removed session {}", before.label()),
+                (None, None) => "Touched session".to_string(),
+            },
+            Change::Template { before, after } => match (before, after) {
+                (None, Some(after)) => format!("Created template '{}'", after.name),
+                (Some(_), Some(after)) => format!("Edited template '{}'", after.name),
+                (Some(before), None) => format!("Deleted template '{}'", before.name),
+                (None, None) => "Touched template".to_string(),
+            },
+            Change::Trash { before, after } => match (before, after) {
+                (None, Some(after)) => format!("Moved {} to the trash", after.record.describe()),
+                (Some(before), _) => {
+                    format!("Removed {} from the trash", before.record.describe())
+                }
+                (None, None) => "Touched trash".to_string(),
+            },
+            Change::Workout {
+                before: None,
+                after: None,
+            }
+            | Change::Exercise {
+                before: None,
+                after: None,
+            } => "No change".to_string(),
+        }
+    }
+
+    /// Reverts the change. Workouts and bodyweight entries that are restored get new
+    /// IDs from the library; those are recorded in `ids`.
+    fn revert(
+        &self,
+        service: &mut AppService,
+        store: &mut CliStore,
+        ids: &mut IdChanges,
+    ) -> Result<()> {
+        match self {
+            Change::Workout { before, after } => {
+                if let Some(after) = after {
+                    if let Some(before) = before.as_ref().filter(|b| b.restorable_by_edit(after)) {
+                        edit_workout_back(service, store, before)?;
+                        return Ok(());
+                    }
+                    service
+                        .delete_workouts(&vec![after.id])
+                        .with_context(|| format!("Could not delete workout {}", after.label()))?;
+                    store.forget_workouts(&[after.id]);
+                }
+                if let Some(before) = before {
+                    let new_id = restore_workout(service, store, before)?;
+                    ids.workouts.push((before.id, new_id));
+                }
+            }
+            Change::Exercise { before, after } => match (before, after) {
+                (None, Some(after)) => {
+                    service
+                        .delete_exercise(&vec![after.name.clone()])
+                        .with_context(|| format!("Could not delete exercise '{}'", after.name))?;
+                }
+                (Some(before), None) => restore_exercise(service, before)?,
+                (Some(before), Some(after)) => {
+                    let new_name = (before.name != after.name).then_some(before.name.as_str());
+                    service
+                        .edit_exercise(
+                            &after.name,
+                            new_name,
+                            Some(exercise_type_of(&before.type_)?),
+                            None,
+                            Some(before.muscles.as_deref()),
+                        )
+                        .with_context(|| format!("Could not restore exercise '{}'", before.name))?;
+                }
+                (None, None) => {}
+            },
+            Change::Alias {
+                alias,
+                before,
+                after,
+            } => {
+                if after.is_some() {
+                    service
+                        .delete_alias(alias)
+                        .with_context(|| format!("Could not delete alias '{}'", alias))?;
+                }
+                if let Some(exercise) = before {
+                    service
+                        .create_alias(alias, exercise)
+                        .with_context(|| format!("Could not recreate alias '{}'", alias))?;
+                }
+            }
+            Change::Bodyweight { before, after } => {
+                if let Some(after) = after {
+                    service.delete_bodyweight(after.id).with_context(|| {
+                        format!("Could not delete bodyweight entry #{}", after.id)
+                    })?;
+                }
+                if let Some(before) = before {
+                    let new_id = service
+                        .add_bodyweight_entry(before.timestamp, before.weight)
+                        .with_context(|| {
+                            format!("Could not restore bodyweight entry #{}", before.id)
+                        })?;
+                    ids.bodyweights.push((before.id, new_id));
+                }
+            }
+            Change::Setting {
+                setting, before, ..
+            } => setting.restore(service, store, before)?,
+            Change::Session { before, after } => {
+                let id = before.as_ref().or(after.as_ref()).map(|s| s.id);
+                store.sessions.retain(|s| Some(s.id) != id);
+                if let Some(before) = before {
+                    store.sessions.push(before.clone());
+                    store.sessions.sort_by_key(|s| s.id);
+                }
+            }
+            Change::Trash { before, after } => {
+                // The record is back (or gone again), and so is its tombstone
+                if let Some(after) = after {
+                    store.trash.retain(|item| item.id != after.id);
+                    store.remove_tombstone(&after.record.tombstone());
+                }
+                if let Some(before) = before {
+                    store.add_tombstone(before.record.tombstone());
+                    store.trash.push(before.clone());
+                    store.trash.sort_by_key(|item| item.id);
+                }
+            }
+            Change::Template { before, after } => {
+                let position = after
+                    .as_ref()
+                    .and_then(|after| store.template_index(&after.name));
+                match (before, position) {
+                    (Some(before), Some(index)) => store.templates[index] = before.clone(),
+                    (Some(before), None) => store.templates.push(before.clone()),
+                    (None, Some(index)) => {
+                        store.templates.remove(index);
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Points snapshots of restored records at their new IDs.
+    fn apply_id_changes(&mut self, ids: &IdChanges) {
+        match self {
+            Change::Workout { before, after } => {
+                for snapshot in [before, after].into_iter().flatten() {
+                    if let Some(&(_, new)) =
+                        ids.workouts.iter().find(|(old, _)| *old == snapshot.id)
+                    {
+                        snapshot.id = new;
+                    }
+                }
+            }
+            Change::Bodyweight { before, after } => {
+                for snapshot in [before, after].into_iter().flatten() {
+                    if let Some(&(_, new)) =
+                        ids.bodyweights.iter().find(|(old, _)| *old == snapshot.id)
+                    {
+                        snapshot.id = new;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Reverts `changes` newest first. If one fails, returns the error with the changes
+/// that were not reverted, their IDs updated for records recreated by the others.
+fn revert_changes(
+    changes: &[Change],
+    ids: &mut IdChanges,
+    mut revert: impl FnMut(&Change, &mut IdChanges) -> Result<()>,
+) -> std::result::Result<(), (anyhow::Error, Vec<Change>)> {
+    for (index, change) in changes.iter().enumerate().rev() {
+        if let Err(e) = revert(change, ids) {
+            let mut remaining = changes[..=index].to_vec();
+            for change in &mut remaining {
+                change.apply_id_changes(ids);
+            }
+            return Err((e, remaining));
+        }
+    }
+    Ok(())
+}
+
+/// Old and new IDs of records recreated by an undo.
+#[derive(Default)]
+struct IdChanges {
+    workouts: Vec<(i64, i64)>,
+    bodyweights: Vec<(i64, i64)>,
+}
+
+fn exercise_type_of(name: &str) -> Result<ExerciseType> {
+    parse_exercise_type(name).with_context(|| format!("Unknown exercise type '{}'", name))
+}
+
+fn distance_in_units(distance_km: Option<f64>, units: Units) -> Option<f64> {
+    distance_km.map(|km| match units {
+        Units::Metric => km,
+        Units::Imperial => km * KM_TO_MILE,
+    })
+}
+
+fn edit_workout_back(
+    service: &mut AppService,
+    store: &mut CliStore,
+    before: &WorkoutSnapshot,
+) -> Result<()> {
+    let units = service.config.units;
+    service
+        .edit_workout(EditWorkoutParams {
+            id: before.id,
+            new_exercise_identifier: Some(before.exercise_name.clone()),
+            new_sets: before.sets,
+            new_reps: before.reps,
+            new_weight: before.weight,
+            new_duration: before.duration_minutes,
+            new_distance_arg: distance_in_units(before.distance_km, units),
+            new_notes: before.notes.clone(),
+            new_date: None,
+            new_bodyweight: before.bodyweight,
+        })
+        .with_context(|| format!("Could not restore workout {}", before.label()))?;
+    if before.logged_sets.is_empty() {
+        store.workout_sets.remove(&before.id);
+    } else {
+        store
+            .workout_sets
+            .insert(before.id, before.logged_sets.clone());
+    }
+    if before.rests.is_empty() {
+        store.workout_rests.remove(&before.id);
+    } else {
+        store.workout_rests.insert(before.id, before.rests.clone());
+    }
+    Ok(())
+}
+
+/// Recreates a deleted exercise together with its aliases.
+pub fn restore_exercise(service: &AppService, snapshot: &ExerciseSnapshot) -> Result<()> {
+    let type_ = exercise_type_of(&snapshot.type_)?;
+    service
+        .create_exercise(&snapshot.name, type_, None, snapshot.muscles.as_deref())
+        .with_context(|| format!("Could not recreate exercise '{}'", snapshot.name))?;
+    for alias in &snapshot.aliases {
+        service
+            .create_alias(alias, &snapshot.name)
+            .with_context(|| format!("Could not recreate alias '{}'", alias))?;
+    }
+    Ok(())
+}
+
+/// Adds a deleted workout back and returns its new ID.
+pub fn restore_workout(
+    service: &mut AppService,
+    store: &mut CliStore,
+    snapshot: &WorkoutSnapshot,
+) -> Result<i64> {
+    let units = service.config.units;
+    let params = AddWorkoutParams {
+        exercise_identifier: &snapshot.exercise_name,
+        date: snapshot.timestamp,
+        sets: snapshot.sets,
+        reps: snapshot.reps,
+        weight: snapshot.weight,
+        distance: distance_in_units(snapshot.distance_km, units),
+        duration: snapshot.duration_minutes,
+        notes: snapshot.notes.clone(),
+        bodyweight_to_use: snapshot.bodyweight,
+        implicit_type: snapshot
+            .exercise_type
+            .as_deref()
+            .and_then(parse_exercise_type),
+        implicit_muscles: None,
+    };
+    let (id, _) = service
+        .add_workout(params)
+        .with_context(|| format!("Could not restore workout {}", snapshot.label()))?;
+    if !snapshot.logged_sets.is_empty() {
+        store.workout_sets.insert(id, snapshot.logged_sets.clone());
+    }
+    if !snapshot.rests.is_empty() {
+        store.workout_rests.insert(id, snapshot.rests.clone());
+    }
+    if let Some(session_id) = snapshot.session_id {
+        store.rejoin_session(session_id, id, snapshot.session_pb);
+    }
+    Ok(id)
+}
+
+/// Snapshots of the workouts with the given IDs, including their logged sets, rests
+/// and session.
+pub fn snapshot_workouts(
+    service: &AppService,
+    store: &CliStore,
+    ids: &[i64],
+) -> Result<Vec<WorkoutSnapshot>> {
+    let workouts = service.list_workouts(&WorkoutFilters {
+        exercise_name: None,
+        date: None,
+        exercise_type: None,
+        muscle: None,
+        limit: None,
+    })?;
+    Ok(workouts
+        .iter()
+        .filter(|w| ids.contains(&w.id))
+        .map(|w| {
+            let session = store
+                .sessions
+                .iter()
+                .find(|s| s.workout_ids.contains(&w.id));
+            WorkoutSnapshot {
+                rests: store.workout_rests.get(&w.id).cloned().unwrap_or_default(),
+                session_id: session.map(|s| s.id),
+                session_pb: session.is_some_and(|s| s.pb_workout_ids.contains(&w.id)),
+                ..WorkoutSnapshot::new(w, store.workout_sets.get(&w.id))
+            }
+        })
+        .collect())
+}
+
+/// Snapshot of an exercise (by name, ID or alias) and its aliases, if it exists.
+pub fn snapshot_exercise(
+    service: &AppService,
+    identifier: &str,
+) -> Result<Option<ExerciseSnapshot>> {
+    let Some(definition) = service.get_exercise_by_identifier_service(identifier)? else {
+        return Ok(None);
+    };
+    let mut aliases: Vec<String> = service
+        .list_aliases()?
+        .into_iter()
+        .filter(|(_, exercise)| *exercise == definition.name)
+        .map(|(alias, _)| alias)
+        .collect();
+    aliases.sort();
+    Ok(Some(ExerciseSnapshot::new(&definition, aliases)))
+}
+
+thread_local! {
+    /// Command recorded instead of the process arguments, set by `ta shell`.
+    static COMMAND_OVERRIDE: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Runs `f` with changes recorded under `command` rather than the process arguments.
+pub fn with_command<T>(command: String, f: impl FnOnce() -> T) -> T {
+    COMMAND_OVERRIDE.with(|cell| *cell.borrow_mut() = Some(command));
+    let result = f();
+    COMMAND_OVERRIDE.with(|cell| *cell.borrow_mut() = None);
+    result
+}
+
+/// The command line of this invocation, without the program name.
+fn invoked_command() -> String {
+    COMMAND_OVERRIDE
+        .with(|cell| cell.borrow().clone())
+        .unwrap_or_else(|| std::env::args().skip(1).collect::<Vec<_>>().join(" "))
+}
+
+/// Appends an entry for the running command. Does nothing if nothing changed.
+pub fn record(service: &AppService, changes: Vec<Change>) -> Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let mut journal = Journal::load(service)?;
+    journal.push(invoked_command(), changes);
+    journal.save()
+}
+
+/// Records a setting change, given the value it had before the command ran.
+pub fn record_setting(service: &AppService, setting: Setting, before: String) -> Result<()> {
+    let after = setting.current(service)?;
+    if after == before {
+        return Ok(());
+    }
+    record(
+        service,
+        vec![Change::Setting {
+            setting,
+            before,
+            after,
+        }],
+    )
+}
+
+impl Journal {
+    /// Loads the journal that lives next to the service's config file.
+    /// A missing file yields an empty journal.
+    pub fn load(service: &AppService) -> Result<Self> {
+        let path = service.get_config_path().with_file_name(JOURNAL_FILE_NAME);
+        let mut journal: Journal = if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read journal file {:?}", path))?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse journal file {:?}", path))?
+        } else {
+            Journal::default()
+        };
+        journal.path = path;
+        Ok(journal)
+    }
+
+    /// Writes the journal back to disk via a temporary file and rename.
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(&tmp_path, contents)
+            .with_context(|| format!("Failed to write journal file {:?}", tmp_path))?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Failed to replace journal file {:?}", self.path))?;
+        Ok(())
+    }
+
+    fn push(&mut self, command: String, changes: Vec<Change>) {
+        let id = self.entries.last().map_or(1, |e| e.id + 1);
+        self.entries.push(JournalEntry {
+            id,
+            timestamp: Utc::now(),
+            command,
+            changes,
+        });
+    }
+
+    /// Reverts the last `count` entries, newest first, and removes them from the
+    /// journal. Stops at the first entry that can't be reverted; entries undone until
+    /// then stay undone, and of the failed entry only the changes not reverted are kept.
+    pub fn undo(&mut self, service: &mut AppService, count: usize) -> Result<Vec<JournalEntry>> {
+        let mut store = CliStore::load(service)?;
+        let mut undone = Vec::new();
+        for _ in 0..count {
+            let Some(entry) = self.entries.last().cloned() else {
+                break;
+            };
+            let mut ids = IdChanges::default();
+            let result = revert_changes(&entry.changes, &mut ids, |change, ids| {
+                change.revert(service, &mut store, ids)
+            });
+            self.entries.pop();
+            for change in self.entries.iter_mut().flat_map(|e| e.changes.iter_mut()) {
+                change.apply_id_changes(&ids);
+            }
+            if let Err((e, remaining)) = result {
+                // Keep what was not reverted, so the next undo continues from there
+                self.entries.push(JournalEntry {
+                    changes: remaining,
+                    ..entry.clone()
+                });
+                store.save()?;
+                self.save()?;
+                return Err(e.context(format!(
+                    "Failed to undo #{} ({}); it was only partly reverted",
+                    entry.id, entry.command
+                )));
+            }
+            undone.push(entry);
+        }
+        store.save()?;
+        self.save()?;
+        Ok(undone)
+    }
+
+    /// Drops entries older than `before` and all but the newest `keep` entries.
+    /// Returns the number of entries removed.
+    pub fn prune(&mut self, keep: Option<usize>, before: Option<DateTime<Utc>>) -> usize {
+        let original = self.entries.len();
+        if let Some(cutoff) = before {
+            self.entries.retain(|e| e.timestamp >= cutoff);
+        }
+        if let Some(keep) = keep {
+            let excess = self.entries.len().saturating_sub(keep);
+            self.entries.drain(..excess);
+        }
+        original - self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn workout(id: i64, reps: Option<i64>, notes: Option<&str>) -> WorkoutSnapshot {
+        WorkoutSnapshot {
+            id,
+            timestamp: Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap(),
+            exercise_name: "Bench Press".to_string(),
+            exercise_type: Some("Resistance".to_string()),
+            sets: Some(3),
+            reps,
+            weight: Some(60.0),
+            duration_minutes: None,
+            distance_km: None,
+            bodyweight: None,
+            notes: notes.map(str::to_string),
+            logged_sets: Vec::new(),
+            rests: Vec::new(),
+            session_id: None,
+            session_pb: false,
+        }
+    }
+
+    #[test]
+    fn test_describe_workout_edit() {
+        let change = Change::Workout {
+            before: Some(workout(4, Some(8), None)),
+            after: Some(workout(4, Some(10), Some("felt easy"))),
+        };
+        assert_eq!(
+            change.describe(),
+            "Edited workout #4 Bench Press on 2025-03-01: reps 8 -> 10, notes - -> felt easy"
+        );
+    }
+
+    #[test]
+    fn test_restorable_by_edit() {
+        let before = workout(4, Some(8), None);
+        assert!(before.restorable_by_edit(&workout(4, Some(10), None)));
+        // Notes can't be cleared through an edit
+        assert!(!before.restorable_by_edit(&workout(4, Some(8), Some("x"))));
+    }
+
+    #[test]
+    fn test_id_changes_and_prune() {
+        let mut journal = Journal::default();
+        journal.push(
+            "add".to_string(),
+            vec![Change::Workout {
+                before: None,
+                after: Some(workout(4, Some(8), None)),
+            }],
+        );
+        journal.push("alias".to_string(), Vec::new());
+        assert_eq!(journal.entries[1].id, 2);
+
+        let ids = IdChanges {
+            workouts: vec![(4, 9)],
+            bodyweights: Vec::new(),
+        };
+        journal.entries[0].changes[0].apply_id_changes(&ids);
+        assert!(matches!(
+            &journal.entries[0].changes[0],
+            Change::Workout { after: Some(w), .. } if w.id == 9
+        ));
+
+        assert_eq!(journal.prune(Some(1), None), 1);
+        assert_eq!(journal.entries[0].command, "alias");
+    }
+
+    #[test]
+    fn test_partly_failed_revert() {
+        // An edit of workout 3 followed by its deletion, as in one entry
+        let changes = vec![
+            Change::Workout {
+                before: Some(workout(3, Some(5), None)),
+                after: Some(workout(3, Some(6), None)),
+            },
+            Change::Workout {
+                before: Some(workout(3, Some(6), None)),
+                after: None,
+            },
+        ];
+        let mut ids = IdChanges::default();
+        let mut reverted = 0;
+        let result = revert_changes(&changes, &mut ids, |change, ids| match change {
+            // Undoing the deletion recreates the workout under a new ID
+            Change::Workout { after: None, .. } => {
+                ids.workouts.push((3, 10));
+                reverted += 1;
+                Ok(())
+            }
+            _ => bail!("edit failed"),
+        });
+        assert_eq!(reverted, 1);
+        let (error, remaining) = result.unwrap_err();
+        assert_eq!(error.to_string(), "edit failed");
+        // Only the edit is left, and it now refers to the recreated workout
+        assert_eq!(remaining.len(), 1);
+        assert!(matches!(
+            &remaining[0],
+            Change::Workout { before: Some(b), after: Some(a) } if b.id == 10 && a.id == 10
+        ));
+
+        let mut ids = IdChanges::default();
+        assert!(revert_changes(&changes, &mut ids, |_, _| Ok(())).is_ok());
+    }
+}
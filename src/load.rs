@@ -0,0 +1,216 @@
+//! Training load and the acute:chronic workload ratio (`ta load`).
+//!
+//! A day's load is the tonnage of its resistance and bodyweight workouts plus, for
+//! cardio, minutes × intensity (session RPE on a 1-10 scale). The acute load is the
+//! daily average over the last 7 days and the chronic load over the last 28, both
+//! including the day itself. Monotony is the 7-day mean divided by its standard
+//! deviation; strain is the 7-day total times monotony.
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub const ACUTE_DAYS: i64 = 7;
+pub const CHRONIC_DAYS: i64 = 28;
+/// Session RPE assumed for cardio workouts unless `--intensity` says otherwise.
+pub const DEFAULT_CARDIO_INTENSITY: f64 = 5.0;
+/// Ratio band commonly considered safe.
+pub const DEFAULT_BAND: LoadBand = LoadBand {
+    low: 0.8,
+    high: 1.3,
+};
+
+/// Acute:chronic ratios inside this band are considered safe, e.g. `0.8-1.3`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct LoadBand {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Default for LoadBand {
+    fn default() -> Self {
+        DEFAULT_BAND
+    }
+}
+
+impl fmt::Display for LoadBand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.low, self.high)
+    }
+}
+
+// Custom parser for ratio bands: LOW-HIGH
+pub fn parse_load_band(s: &str) -> Result<LoadBand, String> {
+    let invalid = || format!("Invalid band: '{}'. Use LOW-HIGH, e.g. 0.8-1.3.", s);
+    let (low, high) = s.trim().split_once('-').ok_or_else(invalid)?;
+    let low = low.trim().parse::<f64>().map_err(|_| invalid())?;
+    let high = high.trim().parse::<f64>().map_err(|_| invalid())?;
+    if !low.is_finite() || !high.is_finite() || low < 0.0 || low >= high {
+        return Err(format!(
+            "Invalid band: '{}'. LOW must be at least 0 and below HIGH.",
+            s
+        ));
+    }
+    Ok(LoadBand { low, high })
+}
+
+// Custom parser for session RPE: 1-10
+pub fn parse_intensity(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(rpe) if (1.0..=10.0).contains(&rpe) => Ok(rpe),
+        _ => Err(format!(
+            "Invalid intensity: '{}'. Use an RPE from 1 to 10.",
+            s
+        )),
+    }
+}
+
+/// Where a day's ratio falls relative to the safe band.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    Low,
+    Safe,
+    High,
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Zone::Low => "Low",
+            Zone::Safe => "Safe",
+            Zone::High => "High",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// One row of `ta load`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadDay {
+    pub date: NaiveDate,
+    pub load: f64,
+    pub acute: f64,
+    pub chronic: f64,
+    /// `None` without any chronic load.
+    pub ratio: Option<f64>,
+    pub zone: Option<Zone>,
+    /// `None` when the last 7 days had no load or the same load every day.
+    pub monotony: Option<f64>,
+    pub strain: Option<f64>,
+}
+
+/// Load of a cardio workout.
+pub fn cardio_load(duration_minutes: i64, intensity: f64) -> f64 {
+    duration_minutes as f64 * intensity
+}
+
+/// Rows for the `count` days up to and including `end`, oldest first. `daily` must
+/// hold the loads of those days and of the 27 days before them; missing days count
+/// as rest days.
+pub fn days(
+    daily: &BTreeMap<NaiveDate, f64>,
+    end: NaiveDate,
+    count: u32,
+    band: LoadBand,
+) -> Vec<LoadDay> {
+    let load_on = |date: NaiveDate| daily.get(&date).copied().unwrap_or(0.0);
+    let window = |date: NaiveDate, len: i64| -> Vec<f64> {
+        (0..len)
+            .map(|back| load_on(date - Duration::days(back)))
+            .collect()
+    };
+
+    (0..i64::from(count))
+        .rev()
+        .map(|back| {
+            let date = end - Duration::days(back);
+            let week = window(date, ACUTE_DAYS);
+            let acute = week.iter().sum::<f64>() / ACUTE_DAYS as f64;
+            let chronic = window(date, CHRONIC_DAYS).iter().sum::<f64>() / CHRONIC_DAYS as f64;
+            let ratio = (chronic > 0.0).then(|| acute / chronic);
+            let zone = ratio.map(|r| {
+                if r < band.low {
+                    Zone::Low
+                } else if r > band.high {
+                    Zone::High
+                } else {
+                    Zone::Safe
+                }
+            });
+            let variance =
+                week.iter().map(|l| (l - acute).powi(2)).sum::<f64>() / ACUTE_DAYS as f64;
+            let monotony = (variance > 0.0).then(|| acute / variance.sqrt());
+            LoadDay {
+                date,
+                load: load_on(date),
+                acute,
+                chronic,
+                ratio,
+                zone,
+                monotony,
+                strain: monotony.map(|m| m * acute * ACUTE_DAYS as f64),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(offset: i64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap() + Duration::days(offset)
+    }
+
+    #[test]
+    fn test_ratio_and_zones() {
+        // 1000 every other day for seven weeks, then a heavy final week
+        let mut daily = BTreeMap::new();
+        for offset in (0..49).step_by(2) {
+            daily.insert(date(offset), 1000.0);
+        }
+        for offset in 49..56 {
+            daily.insert(date(offset), 1500.0);
+        }
+        let rows = days(&daily, date(55), 8, DEFAULT_BAND);
+        assert_eq!(rows.len(), 8);
+        assert_eq!(rows[0].date, date(48));
+        assert_eq!(rows[0].zone, Some(Zone::Safe));
+
+        let last = rows.last().unwrap();
+        assert_eq!(last.acute, 1500.0);
+        assert_eq!(last.chronic, (11.0 * 1000.0 + 7.0 * 1500.0) / 28.0);
+        assert_eq!(last.zone, Some(Zone::High));
+        // The same load every day has no variation, so monotony is undefined
+        assert_eq!(last.monotony, None);
+        assert!(rows[3].monotony.is_some_and(|m| m > 0.0));
+        assert_eq!(
+            rows[3].strain,
+            rows[3].monotony.map(|m| m * rows[3].acute * 7.0)
+        );
+    }
+
+    #[test]
+    fn test_no_load() {
+        let rows = days(&BTreeMap::new(), date(10), 3, DEFAULT_BAND);
+        assert!(rows.iter().all(|r| r.ratio.is_none() && r.zone.is_none()));
+        assert_eq!(cardio_load(30, DEFAULT_CARDIO_INTENSITY), 150.0);
+    }
+
+    #[test]
+    fn test_parse_load_band() {
+        assert_eq!(
+            parse_load_band("0.8-1.5").unwrap(),
+            LoadBand {
+                low: 0.8,
+                high: 1.5
+            }
+        );
+        assert_eq!(DEFAULT_BAND.to_string(), "0.8-1.3");
+        assert!(parse_load_band("1.3-0.8").is_err());
+        assert!(parse_load_band("1.3").is_err());
+        assert_eq!(parse_intensity("7.5"), Ok(7.5));
+        assert!(parse_intensity("11").is_err());
+    }
+}
@@ -1,19 +1,63 @@
 //! Main executable for the Task Athlete CLI.
 //! Parses arguments, initializes services, and delegates command handling.
 
+mod aggregate;
+mod backup;
+mod calendar;
+mod chart;
 mod cli;
 mod handlers; // NEW: Include handlers module
+mod import;
+mod journal;
+mod load;
+mod muscles;
 mod output; // NEW: Include output module
+mod pbs;
+mod profile;
+mod report;
+mod serialize;
+mod session;
+mod sets;
+mod shell;
+mod store;
+mod strength;
+mod template;
+mod timer;
+mod trash;
+#[cfg(feature = "tui")]
+mod tui;
 
 use anyhow::{Context, Result};
+use handlers::InputRequired;
+use import::apps::App;
+use import::gps::TrackFormat;
+use journal::Setting;
 use std::io::stdout;
+use std::path::PathBuf;
+use std::process::ExitCode;
 use task_athlete_lib::AppService;
 
-#[tokio::main]
-async fn main() -> Result<()> {
+/// Exit code when a prompt would have been needed but input is disabled.
+const EXIT_INPUT_REQUIRED: u8 = 3;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            if e.chain().any(|cause| cause.is::<InputRequired>()) {
+                ExitCode::from(EXIT_INPUT_REQUIRED)
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
+
+fn run() -> Result<()> {
     // --- Parse Args & Handle Completion ---
     let cli_args = cli::parse_args();
-    let export_csv = cli_args.export_csv; // Extract global flag early
+    let interactive = cli_args.interactive();
 
     // Handle completion generation request *before* initializing service
     if let cli::Commands::GenerateCompletion { shell } = cli_args.command {
@@ -24,10 +68,40 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Profiles are managed without opening any database
+    if let cli::Commands::Profile { action } = cli_args.command {
+        return match action {
+            cli::ProfileCommands::Create { name } => handlers::handle_profile_create(name),
+            cli::ProfileCommands::List => handlers::handle_profile_list(),
+            cli::ProfileCommands::Switch { name } => handlers::handle_profile_switch(name),
+            cli::ProfileCommands::Delete { name, yes } => {
+                handlers::handle_profile_delete(name, yes, interactive)
+            }
+        };
+    }
+
+    // Redirect the library to the profile's directory before the runtime starts threads
+    let data_dir = profile::resolve(cli_args.profile.as_deref())?;
+    if let Some(dir) = &data_dir {
+        profile::redirect(dir);
+    }
+
+    tokio::runtime::Runtime::new()
+        .context("Failed to start the async runtime")?
+        .block_on(execute(cli_args, data_dir))
+}
+
+async fn execute(cli_args: cli::Cli, data_dir: Option<PathBuf>) -> Result<()> {
+    let format = cli_args.output_format(); // Extract global flag early
+    let interactive = cli_args.interactive();
+
     // --- Initialize Service ---
     // Needs to be mutable as many handlers modify state or config
     let mut service =
         AppService::initialize().context("Failed to initialize application service")?;
+    if let Some(dir) = &data_dir {
+        profile::check_redirected(&service, dir)?;
+    }
 
     // --- Delegate Command Handling ---
     match cli_args.command {
@@ -68,11 +142,13 @@ async fn main() -> Result<()> {
             sets,
             reps,
             weight,
+            set_entries,
             duration,
             distance,
             notes,
             implicit_type,
             implicit_muscles,
+            answers,
         } => handlers::handle_add_workout(
             &mut service,
             exercise,
@@ -80,11 +156,14 @@ async fn main() -> Result<()> {
             sets,
             reps,
             weight,
+            set_entries,
             duration,
             distance,
             notes,
             implicit_type,
             implicit_muscles,
+            &answers,
+            interactive,
         )?,
         cli::Commands::EditWorkout {
             id,
@@ -92,6 +171,7 @@ async fn main() -> Result<()> {
             sets,
             reps,
             weight,
+            set_entries,
             duration,
             distance,
             notes,
@@ -104,6 +184,7 @@ async fn main() -> Result<()> {
             sets,
             reps,
             weight,
+            set_entries,
             duration,
             distance,
             notes,
@@ -115,6 +196,7 @@ async fn main() -> Result<()> {
         // --- Listing and Stats Commands ---
         cli::Commands::List {
             limit,
+            window,
             today_flag,
             yesterday_flag,
             date,
@@ -123,10 +205,12 @@ async fn main() -> Result<()> {
             muscle,
             nth_last_day_exercise,
             nth_last_day_n,
+            session,
         } => handlers::handle_list_workouts(
-            &service,   // Immutable borrow is fine here
-            export_csv, // Pass the flag
+            &service, // Immutable borrow is fine here
+            format,   // Pass the flag
             limit,
+            &window,
             today_flag,
             yesterday_flag,
             date,
@@ -135,12 +219,58 @@ async fn main() -> Result<()> {
             muscle,
             nth_last_day_exercise,
             nth_last_day_n,
+            session,
         )?,
-        cli::Commands::Stats { exercise } => {
+        cli::Commands::Chart {
+            subject,
+            exercise,
+            metric,
+            options,
+        } => match subject {
+            Some(cli::ChartSubject::Bodyweight { options }) => {
+                handlers::handle_chart_bodyweight(&service, options)?
+            }
+            None => handlers::handle_chart(&service, exercise, metric, options)?,
+        },
+        cli::Commands::Calendar {
+            year,
+            exercise,
+            muscle,
+            by,
+            ascii,
+        } => handlers::handle_calendar(&service, year, exercise, muscle, by, ascii)?,
+        cli::Commands::Import { source } => match source {
+            cli::ImportCommands::Csv {
+                file,
+                kind,
+                dry_run,
+            } => handlers::handle_import_csv(&mut service, file, kind, dry_run)?,
+            cli::ImportCommands::Strong { args } => {
+                handlers::handle_import_app(&mut service, App::Strong, args, interactive)?
+            }
+            cli::ImportCommands::Hevy { args } => {
+                handlers::handle_import_app(&mut service, App::Hevy, args, interactive)?
+            }
+            cli::ImportCommands::Fitnotes { args } => {
+                handlers::handle_import_app(&mut service, App::Fitnotes, args, interactive)?
+            }
+            cli::ImportCommands::Gpx { args } => {
+                handlers::handle_import_tracks(&mut service, TrackFormat::Gpx, args)?
+            }
+            cli::ImportCommands::Tcx { args } => {
+                handlers::handle_import_tracks(&mut service, TrackFormat::Tcx, args)?
+            }
+        },
+        cli::Commands::Stats {
+            exercise,
+            all,
+            since,
+            rm_table,
+        } => {
             handlers::handle_stats(
-                &service,   // Immutable borrow is fine here
-                export_csv, // Pass the flag
-                exercise,
+                &service, // Immutable borrow is fine here
+                format,   // Pass the flag
+                exercise, all, since, rm_table,
             )?
         }
         cli::Commands::Volume {
@@ -152,14 +282,28 @@ async fn main() -> Result<()> {
             start_date,
             end_date,
         } => handlers::handle_volume(
-            &service,   // Immutable borrow is fine here
-            export_csv, // Pass the flag
+            &service, // Immutable borrow is fine here
+            format,   // Pass the flag
             exercise, date, type_, muscle, limit_days, start_date, end_date,
         )?,
+        cli::Commands::Compare {
+            from,
+            to,
+            exercise,
+            type_,
+            muscle,
+        } => handlers::handle_compare(&service, format, from, to, exercise, type_, muscle)?,
+        cli::Commands::Report {
+            period,
+            periods,
+            exercise,
+            type_,
+            muscle,
+        } => handlers::handle_report(&service, format, period, periods, exercise, type_, muscle)?,
         cli::Commands::ListExercises { type_, muscle } => {
             handlers::handle_list_exercises(
-                &service,   // Immutable borrow is fine here
-                export_csv, // Pass the flag
+                &service, // Immutable borrow is fine here
+                format,   // Pass the flag
                 type_, muscle,
             )?
         }
@@ -174,8 +318,8 @@ async fn main() -> Result<()> {
         }
         cli::Commands::ListAliases => {
             handlers::handle_list_aliases(
-                &service,   // Immutable borrow is fine here
-                export_csv, // Pass the flag
+                &service, // Immutable borrow is fine here
+                format,   // Pass the flag
             )?
         }
 
@@ -186,6 +330,10 @@ async fn main() -> Result<()> {
         cli::Commands::ConfigPath => {
             println!("Config file is located at: {:?}", service.get_config_path());
         }
+        cli::Commands::Backup { to } => handlers::handle_backup(&service, to)?,
+        cli::Commands::Restore { archive, yes } => {
+            handlers::handle_restore(&service, archive, yes, interactive)?
+        }
         cli::Commands::SetUnits { units } => handlers::handle_set_units(&mut service, units)?,
 
         // --- Bodyweight Commands ---
@@ -194,8 +342,8 @@ async fn main() -> Result<()> {
         }
         cli::Commands::ListBodyweights { limit } => {
             handlers::handle_list_bodyweights(
-                &service,   // Immutable borrow is fine here
-                export_csv, // Pass the flag
+                &service, // Immutable borrow is fine here
+                format,   // Pass the flag
                 limit,
             )?
         }
@@ -214,30 +362,129 @@ async fn main() -> Result<()> {
         cli::Commands::SetPbNotifyWeight { enabled } => handlers::handle_set_pb_notify_metric(
             &mut service,
             "Weight",
+            Setting::PbNotifyWeight,
             enabled,
             AppService::set_pb_notify_weight,
         )?,
         cli::Commands::SetPbNotifyReps { enabled } => handlers::handle_set_pb_notify_metric(
             &mut service,
             "Reps",
+            Setting::PbNotifyReps,
             enabled,
             AppService::set_pb_notify_reps,
         )?,
         cli::Commands::SetPbNotifyDuration { enabled } => handlers::handle_set_pb_notify_metric(
             &mut service,
             "Duration",
+            Setting::PbNotifyDuration,
             enabled,
             AppService::set_pb_notify_duration,
         )?,
         cli::Commands::SetPbNotifyDistance { enabled } => handlers::handle_set_pb_notify_metric(
             &mut service,
             "Distance",
+            Setting::PbNotifyDistance,
             enabled,
             AppService::set_pb_notify_distance,
         )?,
         cli::Commands::SetStreakInterval { days } => {
             handlers::handle_set_streak_interval(&mut service, days)?
         }
+        cli::Commands::SetE1rmFormula { formula } => {
+            handlers::handle_set_e1rm_formula(&service, formula)?
+        }
+        cli::Commands::SetMuscleTarget {
+            muscle,
+            range,
+            clear: _,
+        } => handlers::handle_set_muscle_target(&service, muscle, range)?,
+        cli::Commands::Muscles { weeks } => handlers::handle_muscles(&service, format, weeks)?,
+        cli::Commands::SetLoadBand { band } => handlers::handle_set_load_band(&service, band)?,
+        cli::Commands::Load {
+            exercise,
+            type_,
+            muscle,
+            days,
+            intensity,
+        } => handlers::handle_load(&service, format, exercise, type_, muscle, days, intensity)?,
+
+        // --- Session Commands ---
+        cli::Commands::Session { action } => match action {
+            cli::SessionCommands::Start { name, notes } => {
+                handlers::handle_session_start(&service, name, notes)?
+            }
+            cli::SessionCommands::End { notes } => handlers::handle_session_end(&service, notes)?,
+            cli::SessionCommands::Show { id } => handlers::handle_session_show(&service, id)?,
+            cli::SessionCommands::List { limit } => {
+                handlers::handle_session_list(&service, format, limit)?
+            }
+        },
+
+        // --- Template Commands ---
+        cli::Commands::Template { action } => match action {
+            cli::TemplateCommands::Create { name, items } => {
+                handlers::handle_template_create(&service, name, items)?
+            }
+            cli::TemplateCommands::Edit {
+                name,
+                rename,
+                add,
+                remove,
+                items,
+            } => handlers::handle_template_edit(&service, name, rename, add, remove, items)?,
+            cli::TemplateCommands::List => handlers::handle_template_list(&service, format)?,
+            cli::TemplateCommands::Delete { name } => {
+                handlers::handle_template_delete(&service, name)?
+            }
+            cli::TemplateCommands::Run { name, date } => {
+                handlers::handle_template_run(&mut service, name, date, interactive)?
+            }
+        },
+
+        cli::Commands::Trash { action } => match action {
+            cli::TrashCommands::List => handlers::handle_trash_list(&service)?,
+            cli::TrashCommands::Restore { id } => handlers::handle_trash_restore(&mut service, id)?,
+            cli::TrashCommands::Empty { older_than, yes } => {
+                handlers::handle_trash_empty(&service, older_than, yes, interactive)?
+            }
+        },
+        cli::Commands::Rest {
+            duration,
+            workout,
+            no_record,
+        } => handlers::handle_rest(&mut service, duration, workout, !no_record).await?,
+        cli::Commands::Timer {
+            kind,
+            rounds,
+            work,
+            rest,
+            log,
+            notes,
+            answers,
+        } => {
+            let phases = timer::plan(kind, rounds, work, rest)?;
+            handlers::handle_timer(
+                &mut service,
+                kind,
+                &phases,
+                log,
+                notes,
+                &answers,
+                interactive,
+            )
+            .await?
+        }
+        cli::Commands::Shell => handlers::handle_shell(&mut service, interactive)?,
+        cli::Commands::Tui => handlers::handle_tui(&mut service, interactive)?,
+        cli::Commands::Undo { count, yes } => {
+            handlers::handle_undo(&mut service, count, yes, interactive)?
+        }
+        cli::Commands::History { action, limit } => match action {
+            None => handlers::handle_history(&service, limit)?,
+            Some(cli::HistoryCommands::Prune { keep, older_than }) => {
+                handlers::handle_history_prune(&service, keep, older_than)?
+            }
+        },
 
         cli::Commands::Sync { server_url } => {
             handlers::handle_sync(&mut service, server_url).await? // Added .await
@@ -247,6 +494,9 @@ async fn main() -> Result<()> {
         cli::Commands::GenerateCompletion { .. } => {
             unreachable!("Completion generation should have exited earlier");
         }
+        cli::Commands::Profile { .. } => {
+            unreachable!("Profile commands should have exited earlier");
+        }
     }
 
     Ok(())
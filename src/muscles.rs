@@ -0,0 +1,279 @@
+//! Weekly hard sets per muscle group and training balance (`ta muscles`).
+//!
+//! Every muscle listed on an exercise is credited with the workout's full sets and
+//! volume. Sets logged with an RPE below [`HARD_SET_MIN_RPE`] are not hard sets; sets
+//! without an RPE are.
+
+use crate::sets::SetEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+pub const HARD_SET_MIN_RPE: f64 = 7.0;
+/// Weekly hard-set range used for muscles without their own target.
+pub const DEFAULT_TARGET: SetRange = SetRange { min: 10, max: 20 };
+/// Key of the default range in `ta set-muscle-target`.
+pub const DEFAULT_TARGET_KEY: &str = "default";
+/// Ratio of hard sets between opposing groups above which they are flagged.
+const IMBALANCE_RATIO: f64 = 1.5;
+
+/// Opposing muscle groups compared for balance, with the muscle names counted in each.
+const OPPOSING_GROUPS: [(&str, &[&str], &str, &[&str]); 2] = [
+    (
+        "push",
+        &[
+            "chest",
+            "pecs",
+            "triceps",
+            "shoulders",
+            "delts",
+            "front delts",
+        ],
+        "pull",
+        &[
+            "back",
+            "lats",
+            "upper back",
+            "traps",
+            "rhomboids",
+            "biceps",
+            "rear delts",
+        ],
+    ),
+    (
+        "quad",
+        &["quads", "quadriceps"],
+        "hamstring",
+        &["hamstrings", "hams"],
+    ),
+];
+
+/// Weekly hard-set target range, e.g. `10-20`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SetRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl fmt::Display for SetRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.min, self.max)
+    }
+}
+
+// Custom parser for set ranges: MIN-MAX
+pub fn parse_set_range(s: &str) -> Result<SetRange, String> {
+    let invalid = || format!("Invalid range: '{}'. Use MIN-MAX, e.g. 10-20.", s);
+    let (min, max) = s.trim().split_once('-').ok_or_else(invalid)?;
+    let min = min.trim().parse::<u32>().map_err(|_| invalid())?;
+    let max = max.trim().parse::<u32>().map_err(|_| invalid())?;
+    if min > max {
+        return Err(format!("Invalid range: '{}'. MIN must not exceed MAX.", s));
+    }
+    Ok(SetRange { min, max })
+}
+
+/// Splits an exercise's comma-separated muscles into lowercase names.
+pub fn parse_muscles(field: Option<&str>) -> Vec<String> {
+    field
+        .unwrap_or("")
+        .split(',')
+        .map(|m| m.trim().to_lowercase())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+/// Number of hard sets among `sets`.
+pub fn hard_sets(sets: &[SetEntry]) -> usize {
+    sets.iter()
+        .filter(|s| s.rpe.is_none_or(|rpe| rpe >= HARD_SET_MIN_RPE))
+        .count()
+}
+
+/// A workout reduced to what the muscle report needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attributed {
+    pub muscles: Vec<String>,
+    pub hard_sets: usize,
+    pub volume: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    NotTrained,
+    Neglected,
+    OnTarget,
+    OverTrained,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Status::NotTrained => "Not trained",
+            Status::Neglected => "Neglected",
+            Status::OnTarget => "On target",
+            Status::OverTrained => "Over-trained",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// One row of `ta muscles`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MuscleRow {
+    pub muscle: String,
+    pub hard_sets: usize,
+    pub weekly_sets: f64,
+    pub volume: f64,
+    pub target: SetRange,
+    pub status: Status,
+}
+
+/// Per-muscle rows over `weeks` weeks, most weekly sets first. Muscles in `known` that
+/// were not trained are listed too.
+pub fn rows(
+    entries: &[Attributed],
+    known: &BTreeSet<String>,
+    weeks: u32,
+    targets: &BTreeMap<String, SetRange>,
+    default_target: SetRange,
+) -> Vec<MuscleRow> {
+    let mut totals: BTreeMap<&str, (usize, f64)> =
+        known.iter().map(|m| (m.as_str(), (0, 0.0))).collect();
+    for entry in entries {
+        for muscle in &entry.muscles {
+            let total = totals.entry(muscle.as_str()).or_default();
+            total.0 += entry.hard_sets;
+            total.1 += entry.volume;
+        }
+    }
+    let mut rows: Vec<MuscleRow> = totals
+        .into_iter()
+        .map(|(muscle, (hard_sets, volume))| {
+            let weekly_sets = hard_sets as f64 / f64::from(weeks.max(1));
+            let target = targets.get(muscle).copied().unwrap_or(default_target);
+            let status = if hard_sets == 0 {
+                Status::NotTrained
+            } else if weekly_sets < f64::from(target.min) {
+                Status::Neglected
+            } else if weekly_sets > f64::from(target.max) {
+                Status::OverTrained
+            } else {
+                Status::OnTarget
+            };
+            MuscleRow {
+                muscle: muscle.to_string(),
+                hard_sets,
+                weekly_sets,
+                volume,
+                target,
+                status,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| b.weekly_sets.total_cmp(&a.weekly_sets));
+    rows
+}
+
+/// Hard sets of two opposing muscle groups. A workout counts once per group, however
+/// many of the group's muscles it lists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Balance {
+    pub first: &'static str,
+    pub first_sets: usize,
+    pub second: &'static str,
+    pub second_sets: usize,
+}
+
+impl Balance {
+    pub fn is_imbalanced(&self) -> bool {
+        let (low, high) = if self.first_sets < self.second_sets {
+            (self.first_sets, self.second_sets)
+        } else {
+            (self.second_sets, self.first_sets)
+        };
+        high > 0 && (high as f64) > low as f64 * IMBALANCE_RATIO
+    }
+}
+
+/// Push/pull and quad/hamstring balance, for the pairs with any hard sets.
+pub fn balances(entries: &[Attributed]) -> Vec<Balance> {
+    let group_sets = |names: &[&str]| -> usize {
+        entries
+            .iter()
+            .filter(|e| e.muscles.iter().any(|m| names.contains(&m.as_str())))
+            .map(|e| e.hard_sets)
+            .sum()
+    };
+    OPPOSING_GROUPS
+        .iter()
+        .map(|(first, first_names, second, second_names)| Balance {
+            first,
+            first_sets: group_sets(first_names),
+            second,
+            second_sets: group_sets(second_names),
+        })
+        .filter(|b| b.first_sets + b.second_sets > 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(muscles: &str, hard_sets: usize) -> Attributed {
+        Attributed {
+            muscles: parse_muscles(Some(muscles)),
+            hard_sets,
+            volume: hard_sets as f64 * 100.0,
+        }
+    }
+
+    #[test]
+    fn test_hard_sets() {
+        let set = |rpe| SetEntry {
+            weight: Some(100.0),
+            reps: 5,
+            rpe,
+        };
+        assert_eq!(hard_sets(&[set(None), set(Some(6.0)), set(Some(8.5))]), 2);
+    }
+
+    #[test]
+    fn test_rows_and_status() {
+        let entries = vec![
+            entry("Chest, Triceps", 24),
+            entry("back,biceps", 12),
+            entry("chest", 6),
+        ];
+        let known: BTreeSet<String> = ["calves".to_string()].into();
+        let targets = BTreeMap::from([("biceps".to_string(), SetRange { min: 4, max: 8 })]);
+        let rows = rows(&entries, &known, 2, &targets, DEFAULT_TARGET);
+
+        let find = |name: &str| rows.iter().find(|r| r.muscle == name).unwrap();
+        assert_eq!(rows[0].muscle, "chest");
+        assert_eq!(find("chest").weekly_sets, 15.0);
+        assert_eq!(find("chest").status, Status::OnTarget);
+        assert_eq!(find("back").status, Status::Neglected);
+        assert_eq!(find("biceps").status, Status::OnTarget);
+        assert_eq!(find("calves").status, Status::NotTrained);
+        assert_eq!(find("triceps").volume, 2400.0);
+    }
+
+    #[test]
+    fn test_balances() {
+        let entries = vec![
+            entry("chest,triceps", 20),
+            entry("lats", 10),
+            entry("quads", 9),
+            entry("hamstrings", 8),
+        ];
+        let balances = balances(&entries);
+        assert_eq!(balances.len(), 2);
+        assert_eq!((balances[0].first_sets, balances[0].second_sets), (20, 10));
+        assert!(balances[0].is_imbalanced());
+        assert!(!balances[1].is_imbalanced());
+        assert_eq!(parse_set_range("12-18").unwrap().to_string(), "12-18");
+        assert!(parse_set_range("18-12").is_err());
+    }
+}
@@ -1,7 +1,20 @@
-use anyhow::Result;
-use chrono::{DateTime, Local, NaiveDate, Utc};
+use crate::aggregate::{CompareMetric, CompareRow};
+use crate::calendar::{self, CalendarMetric, Streak};
+use crate::chart::{self, ChartMetric, ChartOptions};
+use crate::import::ImportReport;
+use crate::journal::JournalEntry;
+use crate::load::{LoadDay, Zone};
+use crate::muscles::MuscleRow;
+use crate::report::PeriodReport;
+use crate::session::{format_duration, Session};
+use crate::sets::{format_sets, SetEntry};
+use crate::strength::{E1rmFormula, E1rmPb};
+use crate::template::Template;
+use crate::timer;
+use crate::trash::TrashItem;
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
 use comfy_table::{presets::UTF8_FULL, Attribute, Cell, Color, ContentArrangement, Table};
-use std::{collections::HashMap, io}; // Added HashMap import
+use std::collections::{BTreeMap, BTreeSet, HashMap}; // Added HashMap import
 use task_athlete_lib::{
     ExerciseDefinition, ExerciseStats, PbMetricInfo, Units, Workout, KM_TO_MILE,
 }; // Import KM_TO_MILE from lib
@@ -53,7 +66,13 @@ pub fn print_bodyweight_table(
 }
 
 /// Prints workout entries in a formatted table, hiding empty columns.
-pub fn print_workout_table(workouts: Vec<Workout>, header_color: Color, units: Units) {
+/// Individually logged sets (keyed by workout ID) are shown in a compact "Set Details" column.
+pub fn print_workout_table(
+    workouts: Vec<Workout>,
+    header_color: Color,
+    units: Units,
+    set_details: &BTreeMap<i64, Vec<SetEntry>>,
+) {
     if workouts.is_empty() {
         println!("No workouts found matching the criteria.");
         return;
@@ -70,6 +89,7 @@ pub fn print_workout_table(workouts: Vec<Workout>, header_color: Color, units: U
         "Sets".to_string(),
         "Reps".to_string(),
         format!("Weight ({})", weight_unit_str),
+        "Set Details".to_string(),
         "Duration (min)".to_string(),
         format!("Distance ({})", distance_unit_str),
         "Notes".to_string(),
@@ -83,6 +103,9 @@ pub fn print_workout_table(workouts: Vec<Workout>, header_color: Color, units: U
                 Units::Imperial => km * KM_TO_MILE,
             });
             let weight = workout.calculate_effective_weight();
+            let logged_sets = set_details
+                .get(&workout.id)
+                .map_or(EMPTY_PLACEHOLDER.to_string(), |sets| format_sets(sets));
 
             vec![
                 workout.id.to_string(),
@@ -102,6 +125,7 @@ pub fn print_workout_table(workouts: Vec<Workout>, header_color: Color, units: U
                     .reps
                     .map_or(EMPTY_PLACEHOLDER.to_string(), |v| v.to_string()),
                 weight.map_or(EMPTY_PLACEHOLDER.to_string(), |v| format!("{v:.2}")),
+                logged_sets,
                 workout
                     .duration_minutes
                     .map_or(EMPTY_PLACEHOLDER.to_string(), |v| v.to_string()),
@@ -203,6 +227,425 @@ pub fn print_volume_table(
     render_dynamic_table(headers_str, data_rows_str, header_color);
 }
 
+/// Formats a change in percent, e.g. `+12.5%`; `-` when there is nothing to compare.
+fn format_change(change_pct: Option<f64>) -> String {
+    change_pct.map_or("-".to_string(), |pct| format!("{pct:+.1}%"))
+}
+
+/// Prints two date ranges side by side with the change from the first to the second.
+pub fn print_compare_table(
+    rows: &[CompareRow],
+    first_label: &str,
+    second_label: &str,
+    units: Units,
+    header_color: Color,
+) {
+    let headers_str = vec![
+        "Metric".to_string(),
+        first_label.to_string(),
+        second_label.to_string(),
+        "Change".to_string(),
+        "Change %".to_string(),
+    ];
+    let data_rows_str: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let format_value = |value: Option<f64>, signed: bool| {
+                let Some(value) = value else {
+                    return EMPTY_PLACEHOLDER.to_string();
+                };
+                let sign = if signed && value >= 0.0 { "+" } else { "" };
+                match row.metric {
+                    CompareMetric::TrainingDays
+                    | CompareMetric::Workouts
+                    | CompareMetric::BestReps => format!("{sign}{value:.0}"),
+                    CompareMetric::Pace if value < 0.0 => {
+                        format!("-{}", timer::format_clock(-value.round() as i64))
+                    }
+                    CompareMetric::Pace => {
+                        format!("{sign}{}", timer::format_clock(value.round() as i64))
+                    }
+                    _ => format!("{sign}{value:.2}"),
+                }
+            };
+            vec![
+                row.metric.label(units),
+                format_value(row.first, false),
+                format_value(row.second, false),
+                format_value(row.change(), true),
+                format_change(row.change_pct()),
+            ]
+        })
+        .collect();
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+}
+
+/// Prints period reports in a table, newest first.
+pub fn print_report_table(reports: &[PeriodReport], units: Units, header_color: Color) {
+    let headers_str = vec![
+        "Period".to_string(),
+        "Sessions".to_string(),
+        "Sets".to_string(),
+        format!("Tonnage ({})", units.weight_abbr()),
+        "vs Previous".to_string(),
+        format!("Distance ({})", units.distance_abbr()),
+        "Duration".to_string(),
+        "Exercises".to_string(),
+        "PBs".to_string(),
+    ];
+    let data_rows_str: Vec<Vec<String>> = reports
+        .iter()
+        .map(|report| {
+            let totals = &report.totals;
+            let distance = match units {
+                Units::Metric => totals.distance_km,
+                Units::Imperial => totals.distance_km * KM_TO_MILE,
+            };
+            vec![
+                report.label.clone(),
+                totals.sessions.to_string(),
+                totals.sets.to_string(),
+                format!("{:.2}", totals.tonnage),
+                format_change(report.tonnage_change_pct),
+                format!("{distance:.2}"),
+                format_duration(Duration::minutes(totals.duration_minutes)),
+                totals.exercises.to_string(),
+                totals.pbs.to_string(),
+            ]
+        })
+        .collect();
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+}
+
+/// Prints weekly hard sets per muscle against the target ranges.
+pub fn print_muscle_table(rows: &[MuscleRow], weeks: u32, units: Units, header_color: Color) {
+    println!("Hard sets per week over the last {} week(s):", weeks);
+    let headers_str = vec![
+        "Muscle".to_string(),
+        "Sets/Week".to_string(),
+        "Target".to_string(),
+        "Status".to_string(),
+        "Hard Sets".to_string(),
+        format!("Volume ({})", units.weight_abbr()),
+    ];
+    let data_rows_str: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            vec![
+                row.muscle.clone(),
+                format!("{:.1}", row.weekly_sets),
+                row.target.to_string(),
+                row.status.to_string(),
+                row.hard_sets.to_string(),
+                format!("{:.2}", row.volume),
+            ]
+        })
+        .collect();
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+}
+
+/// Prints daily training load, oldest first. Ratios outside the safe band are
+/// highlighted, so the table is built here rather than by `render_dynamic_table`.
+pub fn print_load_table(days: &[LoadDay], header_color: Color) {
+    let optional = |value: Option<f64>, decimals: usize| {
+        value.map_or(EMPTY_PLACEHOLDER.to_string(), |v| format!("{v:.decimals$}"))
+    };
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(
+            [
+                "Date",
+                "Load",
+                "Acute (7d)",
+                "Chronic (28d)",
+                "Ratio",
+                "Zone",
+                "Monotony",
+                "Strain",
+            ]
+            .into_iter()
+            .map(|h| Cell::new(h).fg(header_color)),
+        );
+    for day in days {
+        let highlight = |cell: Cell| match day.zone {
+            Some(Zone::High) => cell.fg(Color::Red).add_attribute(Attribute::Bold),
+            Some(Zone::Low) => cell.fg(Color::Yellow),
+            _ => cell,
+        };
+        table.add_row(vec![
+            Cell::new(day.date.format("%Y-%m-%d (%a)")),
+            Cell::new(format!("{:.0}", day.load)),
+            Cell::new(format!("{:.0}", day.acute)),
+            Cell::new(format!("{:.0}", day.chronic)),
+            highlight(Cell::new(optional(day.ratio, 2))),
+            highlight(Cell::new(
+                day.zone
+                    .map_or(EMPTY_PLACEHOLDER.to_string(), |z| z.to_string()),
+            )),
+            Cell::new(optional(day.monotony, 2)),
+            Cell::new(optional(day.strain, 0)),
+        ]);
+    }
+    println!("{table}");
+}
+
+/// Prints training sessions in a table, newest first.
+pub fn print_session_table(sessions: &[Session], header_color: Color) {
+    let headers_str = vec![
+        "ID".to_string(),
+        "Name".to_string(),
+        "Started (Local)".to_string(),
+        "Ended (Local)".to_string(),
+        "Duration".to_string(),
+        "Workouts".to_string(),
+        "PBs".to_string(),
+    ];
+
+    let data_rows_str: Vec<Vec<String>> = sessions
+        .iter()
+        .map(|session| {
+            vec![
+                session.id.to_string(),
+                session
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| EMPTY_PLACEHOLDER.to_string()),
+                session
+                    .started_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                session.ended_at.map_or("open".to_string(), |t| {
+                    t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+                }),
+                format_duration(session.duration()),
+                session.workout_ids.len().to_string(),
+                session.pb_workout_ids.len().to_string(),
+            ]
+        })
+        .collect();
+
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+}
+
+/// Prints a session header followed by a per-exercise summary table.
+/// `exercise_rows` holds (exercise, entries, sets, volume) in the order performed.
+pub fn print_session_summary(
+    session: &Session,
+    exercise_rows: &[(String, usize, i64, f64)],
+    pb_exercises: &[String],
+    rests: &[i64],
+    units: Units,
+    header_color: Color,
+) {
+    println!("\n--- Session {} ---", session.label());
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+    table.add_row(vec![
+        Cell::new("Started").add_attribute(Attribute::Bold),
+        Cell::new(
+            session
+                .started_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+        ),
+    ]);
+    table.add_row(vec![
+        Cell::new("Ended").add_attribute(Attribute::Bold),
+        Cell::new(session.ended_at.map_or("Still open".to_string(), |t| {
+            t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+        })),
+    ]);
+    table.add_row(vec![
+        Cell::new("Duration").add_attribute(Attribute::Bold),
+        Cell::new(format_duration(session.duration())),
+    ]);
+    if !rests.is_empty() {
+        let average = rests.iter().sum::<i64>() / rests.len() as i64;
+        table.add_row(vec![
+            Cell::new("Rests").add_attribute(Attribute::Bold),
+            Cell::new(format!(
+                "{} recorded, average {}",
+                rests.len(),
+                timer::format_clock(average)
+            )),
+        ]);
+    }
+    if let Some(notes) = &session.notes {
+        table.add_row(vec![
+            Cell::new("Notes").add_attribute(Attribute::Bold),
+            Cell::new(notes),
+        ]);
+    }
+    println!("{table}");
+
+    if exercise_rows.is_empty() {
+        println!("No workouts logged in this session.");
+        println!();
+        return;
+    }
+
+    let weight_unit_str = units.weight_abbr();
+    let headers_str = vec![
+        "Exercise".to_string(),
+        "Entries".to_string(),
+        "Sets".to_string(),
+        format!("Volume ({})", weight_unit_str),
+    ];
+    let mut data_rows_str: Vec<Vec<String>> = exercise_rows
+        .iter()
+        .map(|(name, entries, sets, volume)| {
+            vec![
+                name.clone(),
+                entries.to_string(),
+                sets.to_string(),
+                format!("{volume:.2}"),
+            ]
+        })
+        .collect();
+    data_rows_str.push(vec![
+        "Total".to_string(),
+        exercise_rows.iter().map(|r| r.1).sum::<usize>().to_string(),
+        exercise_rows.iter().map(|r| r.2).sum::<i64>().to_string(),
+        format!("{:.2}", exercise_rows.iter().map(|r| r.3).sum::<f64>()),
+    ]);
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+
+    if pb_exercises.is_empty() {
+        println!("No personal bests in this session.");
+    } else {
+        println!("Personal bests hit: {}", pb_exercises.join(", "));
+    }
+    println!();
+}
+
+/// Prints workout templates with their exercises and targets.
+pub fn print_template_table(templates: &[Template], header_color: Color) {
+    let headers_str = vec![
+        "Name".to_string(),
+        "Exercises".to_string(),
+        "Plan".to_string(),
+    ];
+
+    let data_rows_str: Vec<Vec<String>> = templates
+        .iter()
+        .map(|template| {
+            let plan = template
+                .items
+                .iter()
+                .map(|item| {
+                    let target = item.prescription();
+                    if target.is_empty() {
+                        item.exercise.clone()
+                    } else {
+                        format!("{} {}", item.exercise, target)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            vec![
+                template.name.clone(),
+                template.items.len().to_string(),
+                plan,
+            ]
+        })
+        .collect();
+
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+}
+
+/// Prints the items in the trash, oldest first.
+pub fn print_trash_table(items: &[TrashItem], header_color: Color) {
+    let headers_str = vec![
+        "ID".to_string(),
+        "Deleted".to_string(),
+        "Type".to_string(),
+        "Item".to_string(),
+    ];
+
+    let data_rows_str: Vec<Vec<String>> = items
+        .iter()
+        .map(|item| {
+            vec![
+                item.id.to_string(),
+                item.deleted_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                item.record.kind().to_string(),
+                item.record.describe(),
+            ]
+        })
+        .collect();
+
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+}
+
+/// Prints row counts per table of the current database next to those in a backup.
+pub fn print_restore_preview(
+    current: &BTreeMap<String, i64>,
+    archived: &BTreeMap<String, i64>,
+    header_color: Color,
+) {
+    let headers_str = vec![
+        "Table".to_string(),
+        "Current".to_string(),
+        "Backup".to_string(),
+    ];
+
+    let tables: BTreeSet<&String> = current.keys().chain(archived.keys()).collect();
+    let count = |counts: &BTreeMap<String, i64>, table: &String| {
+        counts
+            .get(table)
+            .map_or(EMPTY_PLACEHOLDER.to_string(), |n| n.to_string())
+    };
+    let data_rows_str: Vec<Vec<String>> = tables
+        .into_iter()
+        .map(|table| vec![table.clone(), count(current, table), count(archived, table)])
+        .collect();
+
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+}
+
+/// Prints journal entries, one row per operation with its changes on separate lines.
+pub fn print_history(entries: &[JournalEntry], header_color: Color) {
+    let headers_str = vec![
+        "ID".to_string(),
+        "When".to_string(),
+        "Command".to_string(),
+        "Changes".to_string(),
+    ];
+
+    let data_rows_str: Vec<Vec<String>> = entries
+        .iter()
+        .map(|entry| {
+            vec![
+                entry.id.to_string(),
+                entry
+                    .timestamp
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                entry.command.clone(),
+                entry
+                    .changes
+                    .iter()
+                    .map(|change| change.describe())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ]
+        })
+        .collect();
+
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+}
+
 /// Generic function to render a table with dynamic column hiding.
 fn render_dynamic_table(
     headers_str: Vec<String>,
@@ -308,15 +751,76 @@ fn render_dynamic_table(
     println!("{table}");
 }
 
-// --- Unchanged Functions (print_exercise_stats, print_pb_message_details, CSV functions) ---
+/// Prints the stats of several exercises side by side, one row each, with the best
+/// estimated 1RM next to them.
+pub fn print_stats_table(rows: &[(ExerciseStats, Option<f64>)], units: Units, header_color: Color) {
+    let weight_abbr = units.weight_abbr();
+    let headers_str = vec![
+        "Exercise".to_string(),
+        "Workouts".to_string(),
+        "First".to_string(),
+        "Last".to_string(),
+        "Per Week".to_string(),
+        "Longest Gap (days)".to_string(),
+        "Streak".to_string(),
+        "Longest Streak".to_string(),
+        format!("Max Weight ({weight_abbr})"),
+        format!("Est. 1RM ({weight_abbr})"),
+        "Max Reps".to_string(),
+        "Max Duration (min)".to_string(),
+        format!("Max Distance ({})", units.distance_abbr()),
+    ];
+    let optional = |value: Option<String>| value.unwrap_or(EMPTY_PLACEHOLDER.to_string());
+    let data_rows_str: Vec<Vec<String>> = rows
+        .iter()
+        .map(|(stats, best_e1rm)| {
+            let pbs = &stats.personal_bests;
+            let distance = pbs.max_distance_km.map(|km| match units {
+                Units::Metric => km,
+                Units::Imperial => km * KM_TO_MILE,
+            });
+            vec![
+                stats.canonical_name.clone(),
+                stats.total_workouts.to_string(),
+                optional(stats.first_workout_date.map(|d| d.to_string())),
+                optional(stats.last_workout_date.map(|d| d.to_string())),
+                optional(stats.avg_workouts_per_week.map(|avg| format!("{avg:.2}"))),
+                optional(stats.longest_gap_days.map(|gap| gap.to_string())),
+                stats.current_streak.to_string(),
+                stats.longest_streak.to_string(),
+                optional(pbs.max_weight.map(|w| format!("{w:.2}"))),
+                optional(best_e1rm.map(|e| format!("{e:.2}"))),
+                optional(pbs.max_reps.map(|r| r.to_string())),
+                optional(pbs.max_duration_minutes.map(|d| d.to_string())),
+                optional(distance.map(|d| format!("{d:.2}"))),
+            ]
+        })
+        .collect();
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+}
+
+// --- Unchanged Functions (print_exercise_stats, print_pb_message_details) ---
 // Note: print_exercise_stats uses a key-value format for the main stats,
 // so column hiding doesn't apply directly there. The PB table part already
 // checks `if has_pbs`, effectively hiding the whole section if no PBs exist.
-// CSV functions should generally output all columns for data integrity.
+// Machine-readable output lives in the `serialize` module.
 
-/// Prints exercise statistics.
-pub fn print_exercise_stats(stats: &ExerciseStats, units: Units) {
-    println!("\n--- Statistics for '{}' ---", stats.canonical_name);
+/// Prints exercise statistics. `best_e1rm` is the best estimated 1RM and the formula used;
+/// `trend` holds recent per-day values of a metric, shown as a sparkline.
+pub fn print_exercise_stats(
+    stats: &ExerciseStats,
+    since: Option<NaiveDate>,
+    units: Units,
+    best_e1rm: Option<(f64, E1rmFormula)>,
+    trend: Option<(ChartMetric, &[f64])>,
+) {
+    match since {
+        Some(since) => println!(
+            "\n--- Statistics for '{}' since {} ---",
+            stats.canonical_name, since
+        ),
+        None => println!("\n--- Statistics for '{}' ---", stats.canonical_name),
+    }
 
     let mut table = Table::new();
     table
@@ -373,6 +877,16 @@ pub fn print_exercise_stats(stats: &ExerciseStats, units: Units) {
         Cell::new(format!("Longest Streak {}", streak_interval_str)).add_attribute(Attribute::Bold),
         Cell::new(stats.longest_streak.to_string()), // Use to_string for consistency
     ]);
+    if let Some((metric, values)) = trend.filter(|(_, values)| values.len() > 1) {
+        table.add_row(vec![
+            Cell::new(format!("Recent Trend ({metric})")).add_attribute(Attribute::Bold),
+            Cell::new(format!(
+                "{} (last {} days)",
+                chart::sparkline(values),
+                values.len()
+            )),
+        ]);
+    }
 
     println!("{}", table);
 
@@ -394,6 +908,13 @@ pub fn print_exercise_stats(stats: &ExerciseStats, units: Units) {
         ]);
         has_pbs = true;
     }
+    if let Some((e1rm, formula)) = best_e1rm {
+        pb_table.add_row(vec![
+            Cell::new(format!("Est. 1RM ({formula})")).add_attribute(Attribute::Bold),
+            Cell::new(format!("{:.2} {}", e1rm, weight_unit_str)),
+        ]);
+        has_pbs = true;
+    }
     if let Some(pb_reps) = stats.personal_bests.max_reps {
         pb_table.add_row(vec![
             Cell::new("Max Reps").add_attribute(Attribute::Bold),
@@ -428,13 +949,211 @@ pub fn print_exercise_stats(stats: &ExerciseStats, units: Units) {
     println!(); // Add a blank line at the end
 }
 
+/// Prints a chart of `points` with a title and a legend below it.
+pub fn print_chart(
+    title: &str,
+    points: &[(NaiveDate, f64)],
+    options: &ChartOptions,
+    series_label: &str,
+    units: Units,
+) {
+    println!("\n--- {} ---", title);
+    for line in chart::render_chart(points, options) {
+        println!("{}", line);
+    }
+
+    let mut legend = vec![format!("● {series_label}")];
+    if let Some(window) = options.moving_average {
+        legend.push(format!("· {window}-point moving average"));
+    }
+    if let Some(target) = options.target {
+        legend.push(format!("─ target {:.1} {}", target, units.weight_abbr()));
+    }
+    println!("{}\n", legend.join("   "));
+}
+
+/// Prints the training calendar of `year` followed by the legend and streak details.
+/// Without `color` the calendar is drawn in plain ASCII.
+pub fn print_calendar(
+    year: i32,
+    values: &BTreeMap<NaiveDate, f64>,
+    metric: CalendarMetric,
+    streaks: (Option<Streak>, Option<Streak>),
+    interval_days: u32,
+    color: Option<Color>,
+) {
+    let (current, longest) = streaks;
+    let title = match metric {
+        CalendarMetric::Workouts => "workouts per day",
+        CalendarMetric::Volume => "volume per day",
+    };
+    println!("\n--- {} training calendar ({}) ---", year, title);
+    for line in calendar::render_calendar(year, values, current, longest, color) {
+        println!("{}", line);
+    }
+    println!("{}", calendar::legend(color.is_some()));
+
+    let interval_str = match interval_days {
+        1 => "daily".to_string(),
+        n => format!("{}-day interval", n),
+    };
+    let describe = |streak: Option<Streak>| match streak {
+        Some(s) => format!(
+            "{} training days ({} to {})",
+            s.days,
+            s.start.format("%Y-%m-%d"),
+            s.end.format("%Y-%m-%d")
+        ),
+        None => "none".to_string(),
+    };
+    println!("Current streak ({}): {}", interval_str, describe(current));
+    println!("Longest streak ({}): {}\n", interval_str, describe(longest));
+}
+
+/// Prints the outcome of an import, including every row that failed.
+pub fn print_import_report(report: &ImportReport) {
+    if report.dry_run {
+        println!("Dry run: no changes were made.");
+        println!("Would import: {}", report.imported);
+    } else {
+        println!("Imported: {}", report.imported);
+    }
+    for item in &report.imported_items {
+        println!("  {}", item);
+    }
+    if !report.created_exercises.is_empty() {
+        let verb = if report.dry_run {
+            "Would create"
+        } else {
+            "Created"
+        };
+        println!(
+            "{} exercises: {}",
+            verb,
+            report.created_exercises.join(", ")
+        );
+    }
+    println!("Skipped duplicates: {}", report.duplicates);
+    if report.skipped_rows > 0 {
+        println!(
+            "Skipped rows (warm-ups, rows without data): {}",
+            report.skipped_rows
+        );
+    }
+    println!("Rows with errors: {}", report.errors.len());
+    for error in &report.errors {
+        if error.line == 0 {
+            println!("  {}", error.message);
+        } else {
+            println!("  Line {}: {}", error.line, error.message);
+        }
+    }
+}
+
+/// Prints estimated loads for a range of rep maxes.
+pub fn print_rep_max_table(
+    rep_maxes: &[(i64, f64)],
+    formula: E1rmFormula,
+    units: Units,
+    header_color: Color,
+) {
+    println!("--- Estimated Rep Maxes ({formula}) ---");
+    let weight_unit_str = units.weight_abbr();
+    let headers_str = vec![
+        "Reps".to_string(),
+        format!("Load ({})", weight_unit_str),
+        "% of 1RM".to_string(),
+    ];
+    let one_rm = rep_maxes.first().map_or(0.0, |(_, load)| *load);
+
+    let data_rows_str: Vec<Vec<String>> = rep_maxes
+        .iter()
+        .map(|(reps, load)| {
+            vec![
+                format!("{reps}RM"),
+                format!("{load:.2}"),
+                if one_rm > 0.0 {
+                    format!("{:.0}%", load / one_rm * 100.0)
+                } else {
+                    EMPTY_PLACEHOLDER.to_string()
+                },
+            ]
+        })
+        .collect();
+
+    render_dynamic_table(headers_str, data_rows_str, header_color);
+    println!();
+}
+
 /// Prints the formatted PB message based on achieved PBs and config settings.
 /// Moved here as it's purely an output concern.
+/// e1RM PBs are reported when weight PB notifications are enabled.
 pub fn print_pb_message_details(
-    pb_info: &task_athlete_lib::PBInfo,
+    pb_info: Option<&task_athlete_lib::PBInfo>,
+    e1rm_pb: Option<&E1rmPb>,
     units: Units,
     config: &task_athlete_lib::Config,
 ) {
+    let messages = pb_messages(pb_info, e1rm_pb, units, config);
+
+    if !messages.is_empty() {
+        // Use dynamic width for the box based on the longest message
+        let max_len = messages.iter().map(String::len).max().unwrap_or(25); // Base width if no messages
+        let box_width = std::cmp::max(25, max_len + 2); // Add padding
+
+        let horizontal_line = "*".repeat(box_width + 2); // +2 for the side borders
+        let title = "🎉 Personal Best! 🎉";
+        let title_padding = (box_width - title.chars().count()) / 2; // Center the title roughly
+        let title_line = format!(
+            "*{}{}{}*",
+            " ".repeat(title_padding),
+            title,
+            " ".repeat(box_width - title.chars().count() - title_padding)
+        );
+
+        println!("{horizontal_line}");
+        println!("{title_line}");
+        // println!("* {:<width$} *", "", width = box_width); // Optional empty line
+
+        for msg in messages {
+            println!("* {:<width$} *", msg, width = box_width);
+        }
+        println!("{}", horizontal_line);
+    }
+}
+
+/// Messages for the achieved PBs that are enabled for notification, e1RM included.
+pub fn pb_messages(
+    pb_info: Option<&task_athlete_lib::PBInfo>,
+    e1rm_pb: Option<&E1rmPb>,
+    units: Units,
+    config: &task_athlete_lib::Config,
+) -> Vec<String> {
+    let mut messages = pb_info.map_or_else(Vec::new, |pb_info| {
+        library_pb_messages(pb_info, units, config)
+    });
+
+    if let Some(e1rm) = e1rm_pb.filter(|_| config.pb_notifications.notify_weight) {
+        let old_str = e1rm
+            .previous_value
+            .map_or("N/A".to_string(), |v| format!("{v:.2}"));
+        messages.push(format!(
+            "New Est. 1RM ({}): {:.2} {} (Previous: {})",
+            e1rm.formula,
+            e1rm.new_value,
+            units.weight_abbr(),
+            old_str
+        ));
+    }
+    messages
+}
+
+/// Messages for the PBs reported by the library that are enabled for notification.
+fn library_pb_messages(
+    pb_info: &task_athlete_lib::PBInfo,
+    units: Units,
+    config: &task_athlete_lib::Config,
+) -> Vec<String> {
     let mut messages = Vec::new();
 
     // Helper to check if a PB was achieved and should be notified
@@ -500,233 +1219,7 @@ pub fn print_pb_message_details(
         ));
     }
 
-    if !messages.is_empty() {
-        // Use dynamic width for the box based on the longest message
-        let max_len = messages.iter().map(String::len).max().unwrap_or(25); // Base width if no messages
-        let box_width = std::cmp::max(25, max_len + 2); // Add padding
-
-        let horizontal_line = "*".repeat(box_width + 2); // +2 for the side borders
-        let title = "🎉 Personal Best! 🎉";
-        let title_padding = (box_width - title.chars().count()) / 2; // Center the title roughly
-        let title_line = format!(
-            "*{}{}{}*",
-            " ".repeat(title_padding),
-            title,
-            " ".repeat(box_width - title.chars().count() - title_padding)
-        );
-
-        println!("{horizontal_line}");
-        println!("{title_line}");
-        // println!("* {:<width$} *", "", width = box_width); // Optional empty line
-
-        for msg in messages {
-            println!("* {:<width$} *", msg, width = box_width);
-        }
-        println!("{}", horizontal_line);
-    }
-}
-
-// --- CSV Printing Functions (Unchanged) ---
-// CSV should generally preserve all columns regardless of content
-
-pub fn print_bodyweight_csv(entries: Vec<(i64, DateTime<Utc>, f64)>, units: Units) -> Result<()> {
-    let mut writer = csv::Writer::from_writer(io::stdout());
-    let weight_unit_str = units.weight_abbr();
-
-    writer.write_record([
-        "Id",
-        "Timestamp_UTC", // Standardize on UTC for CSV
-        &format!("Weight_{}", weight_unit_str),
-    ])?;
-
-    for (id, timestamp, weight) in entries {
-        writer.write_record([
-            id.to_string(),
-            timestamp.to_rfc3339(), // Use ISO 8601/RFC3339
-            format!("{:.2}", weight),
-        ])?;
-    }
-    writer.flush()?;
-    Ok(())
-}
-
-pub fn print_workout_csv(workouts: Vec<Workout>, units: Units) -> Result<()> {
-    let mut writer = csv::Writer::from_writer(io::stdout());
-    let weight_unit_str = units.weight_abbr();
-    let distance_unit_str = units.distance_abbr();
-
-    writer.write_record([
-        "ID",
-        "Timestamp_UTC", // Standardize on UTC for CSV
-        "Exercise",
-        "Type",
-        "Sets",
-        "Reps",
-        &format!("Weight_{}", weight_unit_str),
-        "Duration_min",
-        &format!("Distance_{}", distance_unit_str),
-        "Notes",
-    ])?;
-
-    for workout in workouts {
-        let display_distance = workout.distance.map(|km| match units {
-            Units::Metric => km,
-            Units::Imperial => km * KM_TO_MILE,
-        });
-
-        writer.write_record([
-            workout.id.to_string(),
-            workout.timestamp.to_rfc3339(), // Use ISO 8601/RFC3339
-            workout.exercise_name,
-            workout
-                .exercise_type
-                .map_or(String::new(), |t| t.to_string()), // Use empty string for CSV nulls
-            workout.sets.map_or(String::new(), |v| v.to_string()),
-            workout.reps.map_or(String::new(), |v| v.to_string()),
-            workout.weight.map_or(String::new(), |v| format!("{v:.2}")),
-            workout
-                .duration_minutes
-                .map_or(String::new(), |v| v.to_string()),
-            display_distance.map_or(String::new(), |v| format!("{v:.2}")),
-            workout.notes.as_deref().unwrap_or("").to_string(), // Use empty string
-        ])?;
-    }
-
-    writer.flush()?;
-    Ok(())
-}
-
-pub fn print_alias_csv(aliases: HashMap<String, String>) -> Result<()> {
-    let mut writer = csv::Writer::from_writer(io::stdout());
-
-    writer.write_record(["Alias", "Canonical_Exercise_Name"])?;
-
-    let mut sorted_aliases: Vec<_> = aliases.into_iter().collect();
-    sorted_aliases.sort_by(|a, b| a.0.cmp(&b.0));
-
-    for (alias, canonical_name) in sorted_aliases {
-        writer.write_record([alias, canonical_name])?;
-    }
-    writer.flush()?;
-    Ok(())
-}
-
-pub fn print_volume_csv(volume_data: Vec<(NaiveDate, String, f64)>, units: Units) -> Result<()> {
-    let mut writer = csv::Writer::from_writer(io::stdout());
-    let weight_unit_str = units.weight_abbr();
-
-    writer.write_record([
-        "Date",
-        "Exercise",
-        &format!("Volume_Sets*Reps*Weight_{weight_unit_str}"),
-    ])?;
-
-    for (date, exercise_name, volume) in volume_data {
-        writer.write_record([
-            date.format("%Y-%m-%d").to_string(),
-            exercise_name,
-            format!("{volume:.2}"),
-        ])?;
-    }
-    writer.flush()?;
-    Ok(())
-}
-
-pub fn print_stats_csv(stats: &ExerciseStats, units: Units) -> Result<()> {
-    let mut writer = csv::Writer::from_writer(io::stdout());
-
-    writer.write_record(["Statistic", "Value"])?;
-
-    writer.write_record(["Exercise_Name", &stats.canonical_name])?;
-    writer.write_record(["Total_Workouts", &stats.total_workouts.to_string()])?;
-    writer.write_record([
-        "First_Workout",
-        &stats
-            .first_workout_date
-            .map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
-    ])?;
-    writer.write_record([
-        "Last_Workout",
-        &stats
-            .last_workout_date
-            .map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
-    ])?;
-    writer.write_record([
-        "Avg_Workouts_Per_Week",
-        &stats
-            .avg_workouts_per_week
-            .map_or("N/A".to_string(), |avg| format!("{avg:.2}")),
-    ])?;
-    writer.write_record([
-        "Longest_Gap_Days",
-        &stats
-            .longest_gap_days
-            .map_or("N/A".to_string(), |gap| gap.to_string()),
-    ])?;
-    writer.write_record([
-        "Streak_Interval_Days",
-        &stats.streak_interval_days.to_string(),
-    ])?;
-    writer.write_record(["Current_Streak", &stats.current_streak.to_string()])?;
-    writer.write_record(["Longest_Streak", &stats.longest_streak.to_string()])?;
-
-    let weight_unit_str = units.weight_abbr();
-    let distance_unit_str = units.distance_abbr();
-
-    if let Some(pb_weight) = stats.personal_bests.max_weight {
-        writer.write_record([
-            &format!("PB_Max_Weight_{weight_unit_str}"),
-            &format!("{pb_weight:.2}"),
-        ])?;
-    } else {
-        writer.write_record([&format!("PB_Max_Weight_{weight_unit_str}"), ""])?;
-        // Empty if no PB
-    }
-
-    if let Some(pb_reps) = stats.personal_bests.max_reps {
-        writer.write_record(["PB_Max_Reps", &pb_reps.to_string()])?;
-    } else {
-        writer.write_record(["PB_Max_Reps", ""])?;
-    }
-
-    if let Some(pb_duration) = stats.personal_bests.max_duration_minutes {
-        writer.write_record(["PB_Max_Duration_min", &pb_duration.to_string()])?;
-    } else {
-        writer.write_record(["PB_Max_Duration_min", ""])?;
-    }
-
-    if let Some(pb_distance_km) = stats.personal_bests.max_distance_km {
-        let (dist_val, dist_unit) = match units {
-            Units::Metric => (pb_distance_km, distance_unit_str),
-            Units::Imperial => (pb_distance_km * KM_TO_MILE, distance_unit_str),
-        };
-        writer.write_record([
-            &format!("PB_Max_Distance_{}", dist_unit),
-            &format!("{:.2}", dist_val),
-        ])?;
-    } else {
-        writer.write_record([&format!("PB_Max_Distance_{distance_unit_str}"), ""])?;
-    }
-
-    writer.flush()?;
-    Ok(())
-}
-
-pub fn print_exercise_definition_csv(exercises: Vec<ExerciseDefinition>) -> Result<()> {
-    let mut writer = csv::Writer::from_writer(io::stdout());
-
-    writer.write_record(["ID", "Name", "Type", "Muscles"])?;
-
-    for exercise in exercises {
-        writer.write_record([
-            exercise.id.to_string(),
-            exercise.name,
-            exercise.type_.to_string(),
-            exercise.muscles.as_deref().unwrap_or("").to_string(), // Use empty string
-        ])?;
-    }
-    writer.flush()?;
-    Ok(())
+    messages
 }
 
 fn should_display_pb<T>(info: &PbMetricInfo<T>, notify_enabled: bool) -> Option<(T, T)>
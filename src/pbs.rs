@@ -0,0 +1,120 @@
+//! Personal bests derived from the workout history, independent of the library's
+//! notifications (used by `ta tui` and `ta report`).
+
+use chrono::{Local, NaiveDate};
+use std::collections::HashMap;
+use task_athlete_lib::Workout;
+
+/// A workout that beat every earlier entry of its exercise in one metric.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PbEvent {
+    pub date: NaiveDate,
+    pub exercise: String,
+    /// Index into [`Metrics`].
+    pub metric: usize,
+    pub value: f64,
+}
+
+/// Weight, reps, duration and distance (km) of a workout, in that order.
+pub type Metrics = [Option<f64>; 4];
+
+/// Personal bests set in `history` (oldest first), newest first. An exercise's first
+/// entry sets no PB as there is nothing to beat.
+pub fn pb_events(history: &[(NaiveDate, String, Metrics)]) -> Vec<PbEvent> {
+    let mut best: HashMap<&str, Metrics> = HashMap::new();
+    let mut events = Vec::new();
+    for (date, exercise, metrics) in history {
+        let previous = best.entry(exercise.as_str()).or_insert([None; 4]);
+        for (metric, value) in metrics.iter().enumerate() {
+            let Some(value) = *value else { continue };
+            match previous[metric] {
+                Some(old) if value > old => {
+                    events.push(PbEvent {
+                        date: *date,
+                        exercise: exercise.clone(),
+                        metric,
+                        value,
+                    });
+                    previous[metric] = Some(value);
+                }
+                Some(_) => {}
+                None => previous[metric] = Some(value),
+            }
+        }
+    }
+    events.reverse();
+    events
+}
+
+/// History entries for `workouts` (sorted oldest first), dated in local time.
+pub fn history(workouts: &[Workout]) -> Vec<(NaiveDate, String, Metrics)> {
+    workouts
+        .iter()
+        .map(|w| {
+            let metrics = [
+                w.weight,
+                w.reps.map(|r| r as f64),
+                w.duration_minutes.map(|d| d as f64),
+                w.distance,
+            ];
+            (
+                w.timestamp.with_timezone(&Local).date_naive(),
+                w.exercise_name.clone(),
+                metrics,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_pb_events() {
+        let history = vec![
+            (
+                date(1),
+                "Bench".to_string(),
+                [Some(60.0), Some(10.0), None, None],
+            ),
+            (
+                date(3),
+                "Bench".to_string(),
+                [Some(65.0), Some(8.0), None, None],
+            ),
+            (
+                date(4),
+                "Run".to_string(),
+                [None, None, Some(30.0), Some(5.0)],
+            ),
+            (
+                date(6),
+                "Bench".to_string(),
+                [Some(65.0), Some(12.0), None, None],
+            ),
+        ];
+        let events = pb_events(&history);
+        assert_eq!(
+            events,
+            vec![
+                PbEvent {
+                    date: date(6),
+                    exercise: "Bench".to_string(),
+                    metric: 1,
+                    value: 12.0,
+                },
+                PbEvent {
+                    date: date(3),
+                    exercise: "Bench".to_string(),
+                    metric: 0,
+                    value: 65.0,
+                },
+            ]
+        );
+    }
+}
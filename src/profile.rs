@@ -0,0 +1,194 @@
+//! Profiles: separate databases and configs, e.g. one per coached athlete
+//! (`ta profile`, `--profile NAME`, `TA_DATA_DIR`).
+//!
+//! The library keeps its database and config in the platform's data and config
+//! directories. A profile is a directory both are redirected to (via `XDG_DATA_HOME`
+//! and `XDG_CONFIG_HOME`) before the service is initialized, so it gets its own
+//! database, config (units, PB settings, target bodyweight, sync server) and CLI data.
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use task_athlete_lib::AppService;
+
+/// Environment variable pointing the CLI at a data directory outside the profiles.
+pub const DATA_DIR_ENV: &str = "TA_DATA_DIR";
+/// Name of the profile that uses the platform's default locations.
+pub const DEFAULT_PROFILE: &str = "default";
+
+const ACTIVE_FILE_NAME: &str = "active";
+
+/// Directory holding one subdirectory per profile, plus the name of the active one.
+pub fn profiles_dir() -> Result<PathBuf> {
+    let data_dir = dirs::data_dir().context("Could not determine the data directory")?;
+    Ok(data_dir.join("task-athlete").join("profiles"))
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!(
+            "Invalid profile name '{}'. Use letters, digits, '-' and '_'.",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// The active profile set with `ta profile switch`, if it is not the default one.
+pub fn active(root: &Path) -> Result<Option<String>> {
+    let path = root.join(ACTIVE_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let name = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read active profile from {:?}", path))?;
+    let name = name.trim();
+    Ok((!name.is_empty()).then(|| name.to_string()))
+}
+
+/// Names of the created profiles, sorted.
+pub fn list(root: &Path) -> Result<Vec<String>> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+    let mut names: Vec<String> = fs::read_dir(root)
+        .with_context(|| format!("Failed to read profiles directory {:?}", root))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Directory of an existing profile.
+fn existing(root: &Path, name: &str) -> Result<PathBuf> {
+    validate_name(name)?;
+    let dir = root.join(name);
+    if !dir.is_dir() {
+        bail!(
+            "Profile '{}' does not exist. Create it with 'ta profile create {}'.",
+            name,
+            name
+        );
+    }
+    Ok(dir)
+}
+
+pub fn create(root: &Path, name: &str) -> Result<PathBuf> {
+    validate_name(name)?;
+    if name == DEFAULT_PROFILE {
+        bail!("'{}' is the built-in default profile.", DEFAULT_PROFILE);
+    }
+    let dir = root.join(name);
+    if dir.exists() {
+        bail!("Profile '{}' already exists.", name);
+    }
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    Ok(dir)
+}
+
+/// Makes `name` the profile used when neither `--profile` nor `TA_DATA_DIR` is given.
+pub fn switch(root: &Path, name: &str) -> Result<()> {
+    let path = root.join(ACTIVE_FILE_NAME);
+    if name == DEFAULT_PROFILE {
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("Failed to remove {:?}", path))?;
+        }
+        return Ok(());
+    }
+    existing(root, name)?;
+    fs::write(&path, name).with_context(|| format!("Failed to write {:?}", path))
+}
+
+/// Deletes a profile with all its data. The active profile cannot be deleted.
+pub fn delete(root: &Path, name: &str) -> Result<PathBuf> {
+    if name == DEFAULT_PROFILE {
+        bail!("The default profile cannot be deleted.");
+    }
+    let dir = existing(root, name)?;
+    if active(root)?.as_deref() == Some(name) {
+        bail!(
+            "Profile '{}' is active. Switch to another one first ('ta profile switch {}').",
+            name,
+            DEFAULT_PROFILE
+        );
+    }
+    fs::remove_dir_all(&dir).with_context(|| format!("Failed to delete {:?}", dir))?;
+    Ok(dir)
+}
+
+/// Picks the data directory for this run: `--profile`, then `TA_DATA_DIR`, then the
+/// active profile. `None` means the default locations.
+pub fn resolve(profile: Option<&str>) -> Result<Option<PathBuf>> {
+    let root = profiles_dir()?;
+    if let Some(name) = profile {
+        if name == DEFAULT_PROFILE {
+            return Ok(None);
+        }
+        return existing(&root, name).map(Some);
+    }
+    if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        return Ok(Some(dir));
+    }
+    match active(&root)? {
+        Some(name) => existing(&root, &name).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Points the library's data and config directories at `dir`. Must run before the
+/// service is initialized and before any other threads are started.
+pub fn redirect(dir: &Path) {
+    std::env::set_var("XDG_DATA_HOME", dir);
+    std::env::set_var("XDG_CONFIG_HOME", dir);
+}
+
+/// Fails if the service did not pick up the redirection (platforms whose data
+/// directories ignore the XDG variables), rather than using the wrong database.
+pub fn check_redirected(service: &AppService, dir: &Path) -> Result<()> {
+    if !service.get_db_path().starts_with(dir) || !service.get_config_path().starts_with(dir) {
+        bail!(
+            "Could not point the database and config at {:?}; profiles and {} are not supported on this platform.",
+            dir,
+            DATA_DIR_ENV
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_lifecycle() {
+        let root = std::env::temp_dir().join(format!("ta-profiles-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        create(&root, "bob").unwrap();
+        create(&root, "alice").unwrap();
+        assert!(create(&root, "alice").is_err());
+        assert!(create(&root, "../evil").is_err());
+        assert!(create(&root, DEFAULT_PROFILE).is_err());
+        assert_eq!(list(&root).unwrap(), vec!["alice", "bob"]);
+
+        switch(&root, "alice").unwrap();
+        assert_eq!(active(&root).unwrap().as_deref(), Some("alice"));
+        assert!(switch(&root, "carol").is_err());
+        assert!(delete(&root, "alice").is_err());
+
+        switch(&root, DEFAULT_PROFILE).unwrap();
+        assert_eq!(active(&root).unwrap(), None);
+        delete(&root, "alice").unwrap();
+        assert_eq!(list(&root).unwrap(), vec!["bob"]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
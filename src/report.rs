@@ -0,0 +1,195 @@
+//! Weekly, monthly and yearly training reports (`ta report`).
+//!
+//! Workouts are grouped by the local date they were logged on. A period's sessions are
+//! the training sessions its workouts belong to, with workouts outside any session
+//! counted per training day.
+
+use crate::pbs::PbEvent;
+use crate::sets;
+use crate::store::CliStore;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use clap::ValueEnum;
+use std::collections::{BTreeSet, HashMap};
+use task_athlete_lib::{ExerciseType, Workout};
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportPeriod {
+    /// ISO weeks, Monday to Sunday
+    Weekly,
+    /// Calendar months
+    Monthly,
+    /// Calendar years
+    Yearly,
+}
+
+impl ReportPeriod {
+    /// First day of the period containing `date`.
+    pub fn start_of(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            ReportPeriod::Weekly => {
+                date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+            ReportPeriod::Monthly => date.with_day(1).unwrap_or(date),
+            ReportPeriod::Yearly => date.with_ordinal(1).unwrap_or(date),
+        }
+    }
+
+    /// First day of the period before the one starting on `start`.
+    fn previous(self, start: NaiveDate) -> NaiveDate {
+        self.start_of(start - Duration::days(1))
+    }
+
+    /// Label of the period starting on `start`, e.g. `2025-W09`, `2025-03` or `2025`.
+    pub fn label(self, start: NaiveDate) -> String {
+        match self {
+            ReportPeriod::Weekly => {
+                let week = start.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            ReportPeriod::Monthly => start.format("%Y-%m").to_string(),
+            ReportPeriod::Yearly => start.format("%Y").to_string(),
+        }
+    }
+}
+
+/// Totals of one period.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeriodTotals {
+    pub sessions: usize,
+    pub sets: i64,
+    /// Volume of non-cardio workouts in the configured weight unit.
+    pub tonnage: f64,
+    pub distance_km: f64,
+    pub duration_minutes: i64,
+    pub exercises: usize,
+    pub pbs: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodReport {
+    pub label: String,
+    pub start: NaiveDate,
+    pub totals: PeriodTotals,
+    /// Tonnage change against the previous period in percent; `None` if that had none.
+    pub tonnage_change_pct: Option<f64>,
+}
+
+/// Reports for the `count` periods up to the one containing `today`, newest first.
+/// `workouts` must include every workout of those periods and the one before.
+pub fn build(
+    period: ReportPeriod,
+    count: usize,
+    workouts: &[Workout],
+    store: &CliStore,
+    pbs: &[PbEvent],
+    today: NaiveDate,
+) -> Vec<PeriodReport> {
+    let session_of: HashMap<i64, i64> = store
+        .sessions
+        .iter()
+        .flat_map(|s| s.workout_ids.iter().map(move |id| (*id, s.id)))
+        .collect();
+
+    // Per period start: (session keys, exercises, totals)
+    let mut grouped: HashMap<NaiveDate, (BTreeSet<String>, BTreeSet<String>, PeriodTotals)> =
+        HashMap::new();
+    for workout in workouts {
+        let date = workout.timestamp.with_timezone(&Local).date_naive();
+        let (sessions, exercises, totals) = grouped.entry(period.start_of(date)).or_default();
+        sessions.insert(match session_of.get(&workout.id) {
+            Some(id) => format!("session {id}"),
+            None => date.to_string(),
+        });
+        exercises.insert(workout.exercise_name.to_lowercase());
+        totals.sets += workout.sets.unwrap_or(0);
+        if workout.exercise_type != Some(ExerciseType::Cardio) {
+            totals.tonnage += sets::workout_volume(workout, store.workout_sets.get(&workout.id));
+        }
+        totals.distance_km += workout.distance.unwrap_or(0.0);
+        totals.duration_minutes += workout.duration_minutes.unwrap_or(0);
+    }
+    let mut pb_counts: HashMap<NaiveDate, usize> = HashMap::new();
+    for pb in pbs {
+        *pb_counts.entry(period.start_of(pb.date)).or_default() += 1;
+    }
+    let totals_of = |start: NaiveDate| {
+        let mut totals = grouped
+            .get(&start)
+            .map(|(sessions, exercises, totals)| PeriodTotals {
+                sessions: sessions.len(),
+                exercises: exercises.len(),
+                ..totals.clone()
+            })
+            .unwrap_or_default();
+        totals.pbs = pb_counts.get(&start).copied().unwrap_or(0);
+        totals
+    };
+
+    let mut reports = Vec::new();
+    let mut start = period.start_of(today);
+    for _ in 0..count {
+        let totals = totals_of(start);
+        let previous = totals_of(period.previous(start));
+        let tonnage_change_pct = (previous.tonnage > 0.0)
+            .then(|| (totals.tonnage - previous.tonnage) / previous.tonnage * 100.0);
+        reports.push(PeriodReport {
+            label: period.label(start),
+            start,
+            totals,
+            tonnage_change_pct,
+        });
+        start = period.previous(start);
+    }
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_period_starts_and_labels() {
+        let day = date(2025, 3, 5); // A Wednesday
+        assert_eq!(ReportPeriod::Weekly.start_of(day), date(2025, 3, 3));
+        assert_eq!(ReportPeriod::Monthly.start_of(day), date(2025, 3, 1));
+        assert_eq!(ReportPeriod::Yearly.start_of(day), date(2025, 1, 1));
+        assert_eq!(
+            ReportPeriod::Monthly.previous(date(2025, 1, 1)),
+            date(2024, 12, 1)
+        );
+        assert_eq!(
+            ReportPeriod::Weekly.previous(date(2025, 3, 3)),
+            date(2025, 2, 24)
+        );
+        // ISO weeks can belong to the neighbouring year
+        assert_eq!(ReportPeriod::Weekly.label(date(2024, 12, 30)), "2025-W01");
+        assert_eq!(ReportPeriod::Monthly.label(date(2025, 3, 1)), "2025-03");
+    }
+
+    #[test]
+    fn test_pbs_per_period() {
+        let pb = |day| PbEvent {
+            date: date(2025, 3, day),
+            exercise: "Bench".to_string(),
+            metric: 0,
+            value: 100.0,
+        };
+        let reports = build(
+            ReportPeriod::Weekly,
+            2,
+            &[],
+            &CliStore::default(),
+            &[pb(4), pb(6), pb(11)],
+            date(2025, 3, 12),
+        );
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].label, "2025-W11");
+        assert_eq!(reports[0].totals.pbs, 1);
+        assert_eq!(reports[1].totals.pbs, 2);
+        assert_eq!(reports[0].tonnage_change_pct, None);
+    }
+}
@@ -0,0 +1,804 @@
+//! Machine-readable output (CSV, TSV, JSON, NDJSON) shared by every listing command.
+//!
+//! Each printable type implements [`Record`] once; the writers below take care of the
+//! format. Delimited output keeps the unit-suffixed headers the CSV export has always
+//! used (`Weight_kg`, `Distance_mi`, ...) and converts distances to the configured units.
+//! JSON output carries stable field names and raw values instead: UTC timestamps,
+//! distances in km and weights as stored, plus a `units` block describing them.
+
+use crate::aggregate::CompareRow;
+use crate::cli::OutputFormat;
+use crate::load::LoadDay;
+use crate::muscles::MuscleRow;
+use crate::report::PeriodReport;
+use crate::session::Session;
+use crate::strength::E1rmFormula;
+use crate::template::Template;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::{json, Value};
+use std::io::{self, Write};
+use task_athlete_lib::{ExerciseDefinition, ExerciseStats, Units, Workout, KM_TO_MILE};
+
+/// A value that can be written in any of the machine-readable formats.
+pub trait Record {
+    /// Name of the collection, used as `kind` in JSON documents.
+    const KIND: &'static str;
+
+    /// Column headers for CSV/TSV output.
+    fn headers(units: Units) -> Vec<String>;
+
+    /// Cell values for CSV/TSV output, in display units. Missing values are empty strings.
+    fn cells(&self, units: Units) -> Vec<String>;
+
+    /// Raw values keyed by stable field names.
+    fn to_json(&self) -> Value;
+}
+
+/// Writes a list of records. An empty list still produces headers (CSV/TSV) or an empty
+/// `items` array (JSON) so consumers can rely on the shape.
+pub fn write_records<R: Record>(records: &[R], format: OutputFormat, units: Units) -> Result<()> {
+    let mut out = io::stdout().lock();
+    write_records_to(&mut out, records, format, units)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn write_records_to<R: Record>(
+    out: &mut impl Write,
+    records: &[R],
+    format: OutputFormat,
+    units: Units,
+) -> Result<()> {
+    match format {
+        OutputFormat::Csv => write_delimited(out, records, b',', units),
+        OutputFormat::Tsv => write_delimited(out, records, b'\t', units),
+        OutputFormat::Json => {
+            let items: Vec<Value> = records.iter().map(Record::to_json).collect();
+            write_json(
+                out,
+                &json!({
+                    "kind": R::KIND,
+                    "units": units_json(units),
+                    "items": items,
+                }),
+            )
+        }
+        OutputFormat::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut *out, &record.to_json())?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        OutputFormat::Table => unreachable!("table output is rendered by the output module"),
+    }
+}
+
+/// Writes a single record. Delimited formats use a `Statistic,Value` layout with one row
+/// per field, matching the long-standing `ta stats --export-csv` output.
+pub fn write_record<R: Record>(record: &R, format: OutputFormat, units: Units) -> Result<()> {
+    match format {
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let delimiter = if format == OutputFormat::Tsv {
+                b'\t'
+            } else {
+                b','
+            };
+            let mut writer = csv::WriterBuilder::new()
+                .delimiter(delimiter)
+                .from_writer(io::stdout());
+            writer.write_record(["Statistic", "Value"])?;
+            for (header, cell) in R::headers(units).into_iter().zip(record.cells(units)) {
+                writer.write_record([header, cell])?;
+            }
+            writer.flush()?;
+            Ok(())
+        }
+        OutputFormat::Json => write_json(
+            &mut io::stdout().lock(),
+            &json!({
+                "kind": R::KIND,
+                "units": units_json(units),
+                "item": record.to_json(),
+            }),
+        ),
+        OutputFormat::Ndjson => {
+            println!("{}", serde_json::to_string(&record.to_json())?);
+            Ok(())
+        }
+        OutputFormat::Table => unreachable!("table output is rendered by the output module"),
+    }
+}
+
+fn write_delimited<R: Record>(
+    out: &mut impl Write,
+    records: &[R],
+    delimiter: u8,
+    units: Units,
+) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(out);
+    writer.write_record(R::headers(units))?;
+    for record in records {
+        writer.write_record(record.cells(units))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_json(out: &mut impl Write, document: &Value) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *out, document)?;
+    writeln!(out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Describes the units of the raw values in JSON output.
+fn units_json(units: Units) -> Value {
+    json!({
+        "system": format!("{:?}", units).to_lowercase(),
+        "weight": units.weight_abbr(),
+        "distance": "km",
+        "duration": "min",
+    })
+}
+
+/// Converts a canonical km distance into the configured display unit.
+fn display_distance(km: f64, units: Units) -> f64 {
+    match units {
+        Units::Metric => km,
+        Units::Imperial => km * KM_TO_MILE,
+    }
+}
+
+fn opt_cell<T: ToString>(value: Option<T>) -> String {
+    value.map_or(String::new(), |v| v.to_string())
+}
+
+fn opt_cell_f64(value: Option<f64>) -> String {
+    value.map_or(String::new(), |v| format!("{v:.2}"))
+}
+
+// --- Record implementations ---
+
+impl Record for Workout {
+    const KIND: &'static str = "workouts";
+
+    fn headers(units: Units) -> Vec<String> {
+        vec![
+            "ID".to_string(),
+            "Timestamp_UTC".to_string(),
+            "Exercise".to_string(),
+            "Type".to_string(),
+            "Sets".to_string(),
+            "Reps".to_string(),
+            format!("Weight_{}", units.weight_abbr()),
+            "Duration_min".to_string(),
+            format!("Distance_{}", units.distance_abbr()),
+            "Notes".to_string(),
+        ]
+    }
+
+    fn cells(&self, units: Units) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.timestamp.to_rfc3339(),
+            self.exercise_name.clone(),
+            opt_cell(self.exercise_type.as_ref()),
+            opt_cell(self.sets),
+            opt_cell(self.reps),
+            opt_cell_f64(self.weight),
+            opt_cell(self.duration_minutes),
+            opt_cell_f64(self.distance.map(|km| display_distance(km, units))),
+            self.notes.clone().unwrap_or_default(),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "timestamp": self.timestamp.to_rfc3339(),
+            "exercise": self.exercise_name,
+            "type": self.exercise_type.as_ref().map(|t| t.to_string()),
+            "sets": self.sets,
+            "reps": self.reps,
+            "weight": self.weight,
+            "effective_weight": self.calculate_effective_weight(),
+            "duration_min": self.duration_minutes,
+            "distance_km": self.distance,
+            "notes": self.notes,
+        })
+    }
+}
+
+impl Record for ExerciseDefinition {
+    const KIND: &'static str = "exercises";
+
+    fn headers(_units: Units) -> Vec<String> {
+        vec![
+            "ID".to_string(),
+            "Name".to_string(),
+            "Type".to_string(),
+            "Muscles".to_string(),
+        ]
+    }
+
+    fn cells(&self, _units: Units) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.type_.to_string(),
+            self.muscles.clone().unwrap_or_default(),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        let muscles: Vec<&str> = self
+            .muscles
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .collect();
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "type": self.type_.to_string(),
+            "muscles": muscles,
+        })
+    }
+}
+
+/// Alias name paired with the canonical exercise name.
+impl Record for (String, String) {
+    const KIND: &'static str = "aliases";
+
+    fn headers(_units: Units) -> Vec<String> {
+        vec!["Alias".to_string(), "Canonical_Exercise_Name".to_string()]
+    }
+
+    fn cells(&self, _units: Units) -> Vec<String> {
+        vec![self.0.clone(), self.1.clone()]
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "alias": self.0, "exercise": self.1 })
+    }
+}
+
+/// Bodyweight entry as returned by `list_bodyweights`: (id, timestamp, weight).
+impl Record for (i64, DateTime<Utc>, f64) {
+    const KIND: &'static str = "bodyweights";
+
+    fn headers(units: Units) -> Vec<String> {
+        vec![
+            "Id".to_string(),
+            "Timestamp_UTC".to_string(),
+            format!("Weight_{}", units.weight_abbr()),
+        ]
+    }
+
+    fn cells(&self, _units: Units) -> Vec<String> {
+        vec![
+            self.0.to_string(),
+            self.1.to_rfc3339(),
+            format!("{:.2}", self.2),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.0,
+            "timestamp": self.1.to_rfc3339(),
+            "weight": self.2,
+        })
+    }
+}
+
+/// Daily volume row as returned by `calculate_daily_volume`: (date, exercise, volume).
+impl Record for (NaiveDate, String, f64) {
+    const KIND: &'static str = "volume";
+
+    fn headers(units: Units) -> Vec<String> {
+        vec![
+            "Date".to_string(),
+            "Exercise".to_string(),
+            format!("Volume_Sets*Reps*Weight_{}", units.weight_abbr()),
+        ]
+    }
+
+    fn cells(&self, _units: Units) -> Vec<String> {
+        vec![
+            self.0.format("%Y-%m-%d").to_string(),
+            self.1.clone(),
+            format!("{:.2}", self.2),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "date": self.0.format("%Y-%m-%d").to_string(),
+            "exercise": self.1,
+            "volume": self.2,
+        })
+    }
+}
+
+impl Record for MuscleRow {
+    const KIND: &'static str = "muscles";
+
+    fn headers(units: Units) -> Vec<String> {
+        vec![
+            "Muscle".to_string(),
+            "Sets_Per_Week".to_string(),
+            "Target_Min".to_string(),
+            "Target_Max".to_string(),
+            "Status".to_string(),
+            "Hard_Sets".to_string(),
+            format!("Volume_{}", units.weight_abbr()),
+        ]
+    }
+
+    fn cells(&self, _units: Units) -> Vec<String> {
+        vec![
+            self.muscle.clone(),
+            format!("{:.2}", self.weekly_sets),
+            self.target.min.to_string(),
+            self.target.max.to_string(),
+            self.status.to_string(),
+            self.hard_sets.to_string(),
+            format!("{:.2}", self.volume),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "muscle": self.muscle,
+            "sets_per_week": self.weekly_sets,
+            "target_min": self.target.min,
+            "target_max": self.target.max,
+            "status": self.status.to_string(),
+            "hard_sets": self.hard_sets,
+            "volume": self.volume,
+        })
+    }
+}
+
+impl Record for CompareRow {
+    const KIND: &'static str = "comparison";
+
+    fn headers(_units: Units) -> Vec<String> {
+        vec![
+            "Metric".to_string(),
+            "First".to_string(),
+            "Second".to_string(),
+            "Change".to_string(),
+            "Change_Pct".to_string(),
+        ]
+    }
+
+    fn cells(&self, units: Units) -> Vec<String> {
+        let optional = |value: Option<f64>| value.map_or(String::new(), |v| format!("{v:.2}"));
+        vec![
+            self.metric.label(units),
+            optional(self.first),
+            optional(self.second),
+            optional(self.change()),
+            optional(self.change_pct()),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "metric": self.metric.key(),
+            "first": self.first,
+            "second": self.second,
+            "change": self.change(),
+            "change_pct": self.change_pct(),
+        })
+    }
+}
+
+impl Record for LoadDay {
+    const KIND: &'static str = "load";
+
+    fn headers(_units: Units) -> Vec<String> {
+        vec![
+            "Date".to_string(),
+            "Load".to_string(),
+            "Acute".to_string(),
+            "Chronic".to_string(),
+            "Ratio".to_string(),
+            "Zone".to_string(),
+            "Monotony".to_string(),
+            "Strain".to_string(),
+        ]
+    }
+
+    fn cells(&self, _units: Units) -> Vec<String> {
+        let optional = |value: Option<f64>| value.map_or(String::new(), |v| format!("{v:.2}"));
+        vec![
+            self.date.to_string(),
+            format!("{:.2}", self.load),
+            format!("{:.2}", self.acute),
+            format!("{:.2}", self.chronic),
+            optional(self.ratio),
+            self.zone.map_or(String::new(), |z| z.to_string()),
+            optional(self.monotony),
+            optional(self.strain),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "date": self.date.format("%Y-%m-%d").to_string(),
+            "load": self.load,
+            "acute": self.acute,
+            "chronic": self.chronic,
+            "ratio": self.ratio,
+            "zone": self.zone.map(|z| z.to_string()),
+            "monotony": self.monotony,
+            "strain": self.strain,
+        })
+    }
+}
+
+impl Record for PeriodReport {
+    const KIND: &'static str = "report";
+
+    fn headers(units: Units) -> Vec<String> {
+        vec![
+            "Period".to_string(),
+            "Start_Date".to_string(),
+            "Sessions".to_string(),
+            "Sets".to_string(),
+            format!("Tonnage_{}", units.weight_abbr()),
+            "Tonnage_Change_Pct".to_string(),
+            format!("Distance_{}", units.distance_abbr()),
+            "Duration_min".to_string(),
+            "Exercises".to_string(),
+            "PBs".to_string(),
+        ]
+    }
+
+    fn cells(&self, units: Units) -> Vec<String> {
+        let totals = &self.totals;
+        vec![
+            self.label.clone(),
+            self.start.format("%Y-%m-%d").to_string(),
+            totals.sessions.to_string(),
+            totals.sets.to_string(),
+            format!("{:.2}", totals.tonnage),
+            opt_cell_f64(self.tonnage_change_pct),
+            format!("{:.2}", display_distance(totals.distance_km, units)),
+            totals.duration_minutes.to_string(),
+            totals.exercises.to_string(),
+            totals.pbs.to_string(),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        let totals = &self.totals;
+        json!({
+            "period": self.label,
+            "start_date": self.start.format("%Y-%m-%d").to_string(),
+            "sessions": totals.sessions,
+            "sets": totals.sets,
+            "tonnage": totals.tonnage,
+            "tonnage_change_pct": self.tonnage_change_pct,
+            "distance_km": totals.distance_km,
+            "duration_min": totals.duration_minutes,
+            "exercises": totals.exercises,
+            "pbs": totals.pbs,
+        })
+    }
+}
+
+/// Exercise statistics together with the CLI-computed best estimated 1RM.
+pub struct StatsRecord<'a> {
+    pub stats: &'a ExerciseStats,
+    pub best_e1rm: Option<f64>,
+    pub formula: E1rmFormula,
+}
+
+impl Record for StatsRecord<'_> {
+    const KIND: &'static str = "exercise_stats";
+
+    fn headers(units: Units) -> Vec<String> {
+        vec![
+            "Exercise_Name".to_string(),
+            "Total_Workouts".to_string(),
+            "First_Workout".to_string(),
+            "Last_Workout".to_string(),
+            "Avg_Workouts_Per_Week".to_string(),
+            "Longest_Gap_Days".to_string(),
+            "Streak_Interval_Days".to_string(),
+            "Current_Streak".to_string(),
+            "Longest_Streak".to_string(),
+            format!("PB_Max_Weight_{}", units.weight_abbr()),
+            "PB_Max_Reps".to_string(),
+            "PB_Max_Duration_min".to_string(),
+            format!("PB_Max_Distance_{}", units.distance_abbr()),
+            format!("PB_Est_1RM_{}", units.weight_abbr()),
+        ]
+    }
+
+    fn cells(&self, units: Units) -> Vec<String> {
+        let stats = self.stats;
+        let pbs = &stats.personal_bests;
+        vec![
+            stats.canonical_name.clone(),
+            stats.total_workouts.to_string(),
+            stats
+                .first_workout_date
+                .map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
+            stats
+                .last_workout_date
+                .map_or("N/A".to_string(), |d| d.format("%Y-%m-%d").to_string()),
+            stats
+                .avg_workouts_per_week
+                .map_or("N/A".to_string(), |avg| format!("{avg:.2}")),
+            stats
+                .longest_gap_days
+                .map_or("N/A".to_string(), |gap| gap.to_string()),
+            stats.streak_interval_days.to_string(),
+            stats.current_streak.to_string(),
+            stats.longest_streak.to_string(),
+            opt_cell_f64(pbs.max_weight),
+            opt_cell(pbs.max_reps),
+            opt_cell(pbs.max_duration_minutes),
+            opt_cell_f64(pbs.max_distance_km.map(|km| display_distance(km, units))),
+            opt_cell_f64(self.best_e1rm),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        let stats = self.stats;
+        let pbs = &stats.personal_bests;
+        json!({
+            "exercise": stats.canonical_name,
+            "total_workouts": stats.total_workouts,
+            "first_workout": stats.first_workout_date.map(|d| d.format("%Y-%m-%d").to_string()),
+            "last_workout": stats.last_workout_date.map(|d| d.format("%Y-%m-%d").to_string()),
+            "avg_workouts_per_week": stats.avg_workouts_per_week,
+            "longest_gap_days": stats.longest_gap_days,
+            "streak_interval_days": stats.streak_interval_days,
+            "current_streak": stats.current_streak,
+            "longest_streak": stats.longest_streak,
+            "personal_bests": {
+                "max_weight": pbs.max_weight,
+                "max_reps": pbs.max_reps,
+                "max_duration_min": pbs.max_duration_minutes,
+                "max_distance_km": pbs.max_distance_km,
+                "best_e1rm": self.best_e1rm,
+            },
+            "e1rm_formula": self.formula.to_string().to_lowercase(),
+        })
+    }
+}
+
+impl Record for Session {
+    const KIND: &'static str = "sessions";
+
+    fn headers(_units: Units) -> Vec<String> {
+        vec![
+            "ID".to_string(),
+            "Name".to_string(),
+            "Started_UTC".to_string(),
+            "Ended_UTC".to_string(),
+            "Duration_min".to_string(),
+            "Workouts".to_string(),
+            "PBs".to_string(),
+            "Notes".to_string(),
+        ]
+    }
+
+    fn cells(&self, _units: Units) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone().unwrap_or_default(),
+            self.started_at.to_rfc3339(),
+            opt_cell(self.ended_at.map(|t| t.to_rfc3339())),
+            self.duration().num_minutes().to_string(),
+            self.workout_ids.len().to_string(),
+            self.pb_workout_ids.len().to_string(),
+            self.notes.clone().unwrap_or_default(),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "started_at": self.started_at.to_rfc3339(),
+            "ended_at": self.ended_at.map(|t| t.to_rfc3339()),
+            "duration_min": self.duration().num_minutes(),
+            "workout_ids": self.workout_ids,
+            "pb_workout_ids": self.pb_workout_ids,
+            "notes": self.notes,
+        })
+    }
+}
+
+impl Record for Template {
+    const KIND: &'static str = "templates";
+
+    fn headers(_units: Units) -> Vec<String> {
+        vec!["Name".to_string(), "Exercises".to_string()]
+    }
+
+    fn cells(&self, _units: Units) -> Vec<String> {
+        let plan = self
+            .items
+            .iter()
+            .map(|item| format!("{}:{}", item.exercise, item.prescription()))
+            .collect::<Vec<_>>()
+            .join(";");
+        vec![self.name.clone(), plan]
+    }
+
+    fn to_json(&self) -> Value {
+        let items: Vec<Value> = self
+            .items
+            .iter()
+            .map(|item| {
+                json!({
+                    "exercise": item.exercise,
+                    "sets": item.sets,
+                    "reps": item.reps,
+                    "weight": item.weight,
+                })
+            })
+            .collect();
+        json!({ "name": self.name, "items": items })
+    }
+}
+
+/// One row of the estimated rep-max table.
+pub struct RepMax {
+    pub reps: i64,
+    pub load: f64,
+}
+
+impl Record for RepMax {
+    const KIND: &'static str = "rep_maxes";
+
+    fn headers(units: Units) -> Vec<String> {
+        vec!["Reps".to_string(), format!("Load_{}", units.weight_abbr())]
+    }
+
+    fn cells(&self, _units: Units) -> Vec<String> {
+        vec![self.reps.to_string(), format!("{:.2}", self.load)]
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "reps": self.reps, "load": self.load })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use task_athlete_lib::ExerciseType;
+
+    fn run(id: i64, distance_km: f64) -> Workout {
+        Workout {
+            id,
+            timestamp: Utc.with_ymd_and_hms(2025, 3, 1, 7, 30, 0).unwrap(),
+            exercise_name: "Running".to_string(),
+            sets: None,
+            reps: None,
+            weight: None,
+            duration_minutes: Some(50),
+            distance: Some(distance_km),
+            notes: Some("easy, flat".to_string()),
+            exercise_type: Some(ExerciseType::Cardio),
+            bodyweight: None,
+        }
+    }
+
+    fn render<R: Record>(records: &[R], format: OutputFormat, units: Units) -> String {
+        let mut out = Vec::new();
+        write_records_to(&mut out, records, format, units).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_json_field_names_and_units() {
+        let document: Value =
+            serde_json::from_str(&render(&[run(7, 10.0)], OutputFormat::Json, Units::Metric))
+                .unwrap();
+        assert_eq!(document["kind"], "workouts");
+        assert_eq!(
+            document["units"],
+            json!({ "system": "metric", "weight": "kg", "distance": "km", "duration": "min" })
+        );
+        let item = document["items"][0].as_object().unwrap();
+        let mut fields: Vec<&str> = item.keys().map(String::as_str).collect();
+        fields.sort_unstable();
+        assert_eq!(
+            fields,
+            [
+                "distance_km",
+                "duration_min",
+                "effective_weight",
+                "exercise",
+                "id",
+                "notes",
+                "reps",
+                "sets",
+                "timestamp",
+                "type",
+                "weight"
+            ]
+        );
+        assert_eq!(item["timestamp"], "2025-03-01T07:30:00+00:00");
+
+        // Raw values stay in km whatever the configured units
+        let document: Value = serde_json::from_str(&render(
+            &[run(7, 10.0)],
+            OutputFormat::Json,
+            Units::Imperial,
+        ))
+        .unwrap();
+        assert_eq!(document["units"]["system"], "imperial");
+        assert_eq!(document["units"]["distance"], "km");
+        assert_eq!(document["items"][0]["distance_km"], 10.0);
+    }
+
+    #[test]
+    fn test_csv_converts_distances() {
+        let metric = render(&[run(7, 10.0)], OutputFormat::Csv, Units::Metric);
+        let imperial = render(&[run(7, 10.0)], OutputFormat::Csv, Units::Imperial);
+        let distance_cell = |csv: &str| {
+            csv.lines()
+                .nth(1)
+                .unwrap()
+                .split(',')
+                .nth(8)
+                .map(str::to_string)
+        };
+        assert_eq!(distance_cell(&metric).as_deref(), Some("10.00"));
+        assert_eq!(distance_cell(&imperial).as_deref(), Some("6.21"));
+        // Cells with the delimiter are quoted
+        assert!(metric.ends_with(",\"easy, flat\"\n"));
+    }
+
+    #[test]
+    fn test_empty_lists_keep_their_shape() {
+        let empty: [Workout; 0] = [];
+        let csv = render(&empty, OutputFormat::Csv, Units::Metric);
+        assert_eq!(csv.lines().count(), 1);
+        assert!(csv.starts_with("ID,Timestamp_UTC,Exercise,"));
+        let tsv = render(&empty, OutputFormat::Tsv, Units::Metric);
+        assert_eq!(tsv.lines().count(), 1);
+        assert!(tsv.starts_with("ID\tTimestamp_UTC\tExercise\t"));
+
+        let document: Value =
+            serde_json::from_str(&render(&empty, OutputFormat::Json, Units::Metric)).unwrap();
+        assert_eq!(document["kind"], "workouts");
+        assert_eq!(document["items"], json!([]));
+
+        assert_eq!(render(&empty, OutputFormat::Ndjson, Units::Metric), "");
+    }
+
+    #[test]
+    fn test_ndjson_one_object_per_line() {
+        let ndjson = render(
+            &[run(7, 10.0), run(8, 5.0)],
+            OutputFormat::Ndjson,
+            Units::Metric,
+        );
+        let lines: Vec<&str> = ndjson.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, id) in lines.iter().zip([7, 8]) {
+            let item: Value = serde_json::from_str(line).unwrap();
+            assert_eq!(item["id"], id);
+        }
+        assert!(ndjson.ends_with('\n'));
+    }
+}
@@ -0,0 +1,114 @@
+//! Training sessions: a named block of time that groups the workouts logged during it.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Session {
+    pub id: i64,
+    pub name: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the session is still open.
+    pub ended_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    /// Workouts logged while the session was open, in logging order.
+    #[serde(default)]
+    pub workout_ids: Vec<i64>,
+    /// Workouts that achieved a personal best when they were logged.
+    #[serde(default)]
+    pub pb_workout_ids: Vec<i64>,
+}
+
+impl Session {
+    pub fn is_open(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    /// Elapsed time of the session; open sessions are measured up to now.
+    pub fn duration(&self) -> Duration {
+        self.ended_at.unwrap_or_else(Utc::now) - self.started_at
+    }
+
+    /// Display label, e.g. `#3 "Push A"` or `#3`.
+    pub fn label(&self) -> String {
+        match &self.name {
+            Some(name) => format!("#{} \"{}\"", self.id, name),
+            None => format!("#{}", self.id),
+        }
+    }
+
+    /// Appends a line to the session notes.
+    pub fn add_note(&mut self, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            return;
+        }
+        self.notes = Some(match self.notes.take() {
+            Some(existing) => format!("{existing}\n{note}"),
+            None => note.to_string(),
+        });
+    }
+}
+
+/// Formats a duration as `1h 05m` or `42m`.
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn session(name: Option<&str>, ended_after_minutes: Option<i64>) -> Session {
+        let started_at = Utc.with_ymd_and_hms(2025, 3, 1, 18, 0, 0).unwrap();
+        Session {
+            id: 3,
+            name: name.map(str::to_string),
+            started_at,
+            ended_at: ended_after_minutes.map(|m| started_at + Duration::minutes(m)),
+            notes: None,
+            workout_ids: Vec::new(),
+            pb_workout_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_open_and_ended_sessions() {
+        let open = session(None, None);
+        assert!(open.is_open());
+        assert!(open.duration() > Duration::days(1));
+
+        let ended = session(Some("Push A"), Some(65));
+        assert!(!ended.is_open());
+        assert_eq!(ended.duration(), Duration::minutes(65));
+        assert_eq!(ended.label(), "#3 \"Push A\"");
+        assert_eq!(open.label(), "#3");
+    }
+
+    #[test]
+    fn test_add_note() {
+        let mut session = session(None, None);
+        session.add_note("  ");
+        assert_eq!(session.notes, None);
+        session.add_note(" felt strong ");
+        session.add_note("left shoulder tight");
+        assert_eq!(
+            session.notes.as_deref(),
+            Some("felt strong\nleft shoulder tight")
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::minutes(42)), "42m");
+        assert_eq!(format_duration(Duration::minutes(60)), "1h 00m");
+        assert_eq!(format_duration(Duration::seconds(65 * 60 + 59)), "1h 05m");
+        assert_eq!(format_duration(Duration::minutes(-5)), "0m");
+    }
+}
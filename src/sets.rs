@@ -0,0 +1,228 @@
+//! Individual set logging (`ta add --set 100x8 --set 110x6@rpe9`).
+//!
+//! The library stores one sets/reps/weight triple per workout. When sets are logged
+//! individually the workout row keeps the set count and the top set (see [`summarize`]),
+//! and the actual sets are kept in the CLI store. The library only sees the top set, so
+//! a lighter set with more reps doesn't count for its reps PBs; the CLI's own bests
+//! (`ta stats --since`, summaries, e1RM) come from the logged sets.
+
+use serde::{Deserialize, Serialize};
+use task_athlete_lib::Workout;
+
+/// A single performed set.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SetEntry {
+    /// Load for this set. `None` for unloaded bodyweight sets.
+    pub weight: Option<f64>,
+    pub reps: i64,
+    /// Rate of perceived exertion, if recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpe: Option<f64>,
+}
+
+impl SetEntry {
+    /// Compact notation used in tables, e.g. `100x8@9` or `12`.
+    pub fn compact(&self) -> String {
+        let mut s = match self.weight {
+            Some(w) => format!("{}x{}", format_weight(w), self.reps),
+            None => self.reps.to_string(),
+        };
+        if let Some(rpe) = self.rpe {
+            s.push_str(&format!("@{}", format_weight(rpe)));
+        }
+        s
+    }
+}
+
+/// Formats a number without trailing zeros (`100`, `102.5`).
+fn format_weight(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value}")
+    }
+}
+
+// Custom parser for set specifications: WEIGHTxREPS[@rpeN] or REPS[@rpeN]
+pub fn parse_set_spec(s: &str) -> Result<SetEntry, String> {
+    let invalid = || {
+        format!(
+            "Invalid set: '{}'. Use WEIGHTxREPS (e.g. 100x8), REPS (e.g. 12), optionally followed by @rpeN.",
+            s
+        )
+    };
+
+    let lower = s.trim().to_lowercase();
+    let (load_part, rpe) = match lower.split_once('@') {
+        Some((load, rpe_str)) => {
+            let rpe_str = rpe_str.trim_start_matches("rpe");
+            let rpe = rpe_str.parse::<f64>().map_err(|_| invalid())?;
+            if !(0.0..=10.0).contains(&rpe) {
+                return Err(format!("RPE must be between 0 and 10, got {rpe}."));
+            }
+            (load, Some(rpe))
+        }
+        None => (lower.as_str(), None),
+    };
+
+    let (weight, reps) = match load_part.split_once('x') {
+        Some((w, r)) => {
+            let weight = w.trim().parse::<f64>().map_err(|_| invalid())?;
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(invalid());
+            }
+            (
+                Some(weight),
+                r.trim().parse::<i64>().map_err(|_| invalid())?,
+            )
+        }
+        None => (
+            None,
+            load_part.trim().parse::<i64>().map_err(|_| invalid())?,
+        ),
+    };
+
+    if reps <= 0 {
+        return Err(format!("Reps must be positive in set '{}'.", s));
+    }
+
+    Ok(SetEntry { weight, reps, rpe })
+}
+
+/// Summarises sets into the (sets, reps, weight) triple stored on the workout row:
+/// the set count and the top set, i.e. the heaviest set with its own reps (the one
+/// with most reps among equally heavy sets). Reps and weight always come from a set
+/// that was actually performed.
+pub fn summarize(sets: &[SetEntry]) -> (Option<i64>, Option<i64>, Option<f64>) {
+    let Some(top) = sets.iter().max_by(|a, b| {
+        a.weight
+            .unwrap_or(0.0)
+            .total_cmp(&b.weight.unwrap_or(0.0))
+            .then(a.reps.cmp(&b.reps))
+    }) else {
+        return (None, None, None);
+    };
+    (Some(sets.len() as i64), Some(top.reps), top.weight)
+}
+
+/// Returns the sets of a workout: the logged sets if available, otherwise the row's
+/// sets/reps/weight expanded into N identical sets.
+pub fn sets_for_workout(workout: &Workout, logged: Option<&Vec<SetEntry>>) -> Vec<SetEntry> {
+    if let Some(sets) = logged {
+        return sets.clone();
+    }
+    match workout.reps {
+        Some(reps) => {
+            let count = workout.sets.unwrap_or(1).max(1) as usize;
+            vec![
+                SetEntry {
+                    weight: workout.weight,
+                    reps,
+                    rpe: None,
+                };
+                count
+            ]
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Volume (reps * load) of a list of sets. `base_weight` is added to every set's load,
+/// e.g. the bodyweight for BodyWeight exercises.
+pub fn sets_volume(sets: &[SetEntry], base_weight: f64) -> f64 {
+    sets.iter()
+        .map(|s| s.reps as f64 * (base_weight + s.weight.unwrap_or(0.0)))
+        .sum()
+}
+
+/// Volume of a workout computed from its actual sets. For BodyWeight exercises the
+/// bodyweight part of the effective weight is added to every set's load.
+pub fn workout_volume(workout: &Workout, logged: Option<&Vec<SetEntry>>) -> f64 {
+    let effective_weight = workout.calculate_effective_weight().unwrap_or(0.0);
+    let base_weight = effective_weight - workout.weight.unwrap_or(0.0);
+    sets_volume(&sets_for_workout(workout, logged), base_weight)
+}
+
+/// Compact one-line description of a list of sets, e.g. `100x8, 110x6, 120x4@9`.
+pub fn format_sets(sets: &[SetEntry]) -> String {
+    sets.iter()
+        .map(SetEntry::compact)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_weight_and_reps() {
+        let set = parse_set_spec("100x8").unwrap();
+        assert_eq!(
+            set,
+            SetEntry {
+                weight: Some(100.0),
+                reps: 8,
+                rpe: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_with_rpe() {
+        let set = parse_set_spec("120x4@rpe9").unwrap();
+        assert_eq!(set.weight, Some(120.0));
+        assert_eq!(set.reps, 4);
+        assert_eq!(set.rpe, Some(9.0));
+
+        let set = parse_set_spec("102.5X5@8.5").unwrap();
+        assert_eq!(set.weight, Some(102.5));
+        assert_eq!(set.rpe, Some(8.5));
+    }
+
+    #[test]
+    fn test_parse_reps_only() {
+        let set = parse_set_spec("12").unwrap();
+        assert_eq!(set.weight, None);
+        assert_eq!(set.reps, 12);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_set_spec("").is_err());
+        assert!(parse_set_spec("100x").is_err());
+        assert!(parse_set_spec("x8").is_err());
+        assert!(parse_set_spec("100x0").is_err());
+        assert!(parse_set_spec("100x8@rpe11").is_err());
+        assert!(parse_set_spec("heavy").is_err());
+        assert!(parse_set_spec("nanx8").is_err());
+        assert!(parse_set_spec("infx8").is_err());
+    }
+
+    #[test]
+    fn test_summarize_pyramid() {
+        let sets: Vec<SetEntry> = ["100x8", "110x6", "120x4"]
+            .iter()
+            .map(|s| parse_set_spec(s).unwrap())
+            .collect();
+        assert_eq!(summarize(&sets), (Some(3), Some(4), Some(120.0)));
+        assert_eq!(sets_volume(&sets, 0.0), 800.0 + 660.0 + 480.0);
+        assert_eq!(format_sets(&sets), "100x8, 110x6, 120x4");
+    }
+
+    #[test]
+    fn test_summarize_top_set() {
+        let sets: Vec<SetEntry> = ["100x5", "60x12", "100x6"]
+            .iter()
+            .map(|s| parse_set_spec(s).unwrap())
+            .collect();
+        // Never 12 reps at 100: that set was not performed
+        assert_eq!(summarize(&sets), (Some(3), Some(6), Some(100.0)));
+        let bodyweight: Vec<SetEntry> = ["10", "15", "12"]
+            .iter()
+            .map(|s| parse_set_spec(s).unwrap())
+            .collect();
+        assert_eq!(summarize(&bodyweight), (Some(3), Some(15), None));
+        assert_eq!(summarize(&[]), (None, None, None));
+    }
+}
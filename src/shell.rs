@@ -0,0 +1,359 @@
+//! Interactive shell for logging during a gym session (`ta shell`).
+//!
+//! Keeps one service open and reads short commands such as `bench 3x10@60`, with tab
+//! completion of exercise names and aliases and a history kept next to the config
+//! file. Changes are recorded in the undo journal under the line that made them.
+
+use crate::cli::{parse_date_shorthand, ListWindow, OutputFormat, PromptAnswers};
+use crate::handlers;
+use crate::journal;
+use crate::template::{parse_prescription, Prescription};
+use anyhow::{bail, Result};
+use chrono::Utc;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Editor, Helper};
+use task_athlete_lib::AppService;
+
+pub const SHELL_HISTORY_FILE_NAME: &str = "shell_history.txt";
+
+const KEYWORDS: [&str; 7] = ["last", "pb", "list", "undo", "help", "exit", "quit"];
+
+const HELP: &str = "\
+Commands:
+  EXERCISE SETSxREPS[@WEIGHT]  Log a workout, e.g. 'bench 3x10@60' or 'pull-ups 3x8'
+  EXERCISE                     Log the same sets, reps and weight as last time
+  last EXERCISE                Show the last day EXERCISE was trained
+  pb EXERCISE                  Show statistics and personal bests for EXERCISE
+  list [today|yesterday|DATE|N]  List today's, a day's or the last N workouts
+  undo [N]                     Revert the last N changes (default 1)
+  help                         Show this help
+  exit, quit                   Leave the shell (or press Ctrl-D)
+Tab completes exercise names and aliases.";
+
+/// A parsed shell line.
+#[derive(Debug, PartialEq)]
+enum ShellCommand {
+    Log {
+        exercise: String,
+        prescription: Option<Prescription>,
+    },
+    Last(String),
+    Pb(String),
+    List(String),
+    Undo(u32),
+    Help,
+    Exit,
+}
+
+fn parse_line(line: &str) -> Result<ShellCommand> {
+    let line = line.trim();
+    let (keyword, rest) = match line.split_once(char::is_whitespace) {
+        Some((keyword, rest)) => (keyword, rest.trim()),
+        None => (line, ""),
+    };
+    let require_exercise = |command: &str| -> Result<String> {
+        if rest.is_empty() {
+            bail!("Usage: {} EXERCISE", command);
+        }
+        Ok(rest.to_string())
+    };
+    match keyword.to_lowercase().as_str() {
+        "exit" | "quit" if rest.is_empty() => return Ok(ShellCommand::Exit),
+        "help" | "?" if rest.is_empty() => return Ok(ShellCommand::Help),
+        "last" => return Ok(ShellCommand::Last(require_exercise("last")?)),
+        "pb" => return Ok(ShellCommand::Pb(require_exercise("pb")?)),
+        "list" => return Ok(ShellCommand::List(rest.to_string())),
+        "undo" => {
+            let count = match rest {
+                "" => 1,
+                n => match n.parse::<u32>() {
+                    Ok(n) if n > 0 => n,
+                    _ => bail!("Usage: undo [N]"),
+                },
+            };
+            return Ok(ShellCommand::Undo(count));
+        }
+        _ => {}
+    }
+
+    // Anything else logs a workout; a trailing SETSxREPS@WEIGHT is the prescription
+    if let Some((exercise, last)) = line.rsplit_once(char::is_whitespace) {
+        if let Ok(prescription) = parse_prescription(last) {
+            return Ok(ShellCommand::Log {
+                exercise: exercise.trim().to_string(),
+                prescription: Some(prescription),
+            });
+        }
+    }
+    Ok(ShellCommand::Log {
+        exercise: line.to_string(),
+        prescription: None,
+    })
+}
+
+/// Completes keywords and exercise names and aliases.
+struct ShellHelper {
+    exercises: Vec<String>,
+}
+
+impl ShellHelper {
+    fn refresh(&mut self, service: &AppService) {
+        let mut names: Vec<String> = service
+            .list_exercises(None, None)
+            .map(|exercises| exercises.into_iter().map(|e| e.name).collect())
+            .unwrap_or_default();
+        names.extend(service.list_aliases().unwrap_or_default().into_keys());
+        names.sort_by_key(|name| name.to_lowercase());
+        names.dedup();
+        self.exercises = names;
+    }
+
+    /// Start of the text to complete and the matching candidates.
+    fn candidates(&self, line: &str) -> (usize, Vec<String>) {
+        let exercises = self.exercises.iter().map(String::as_str);
+        let (start, options): (usize, Vec<&str>) = match line.split_once(' ') {
+            Some((keyword @ ("last" | "pb" | "list"), rest)) => {
+                let start = line.len() - rest.trim_start().len();
+                if keyword == "list" {
+                    (start, vec!["today", "yesterday"])
+                } else {
+                    (start, exercises.collect())
+                }
+            }
+            _ => (0, KEYWORDS.into_iter().chain(exercises).collect()),
+        };
+        let prefix = line[start..].to_lowercase();
+        let matches = options
+            .into_iter()
+            .filter(|option| option.to_lowercase().starts_with(&prefix))
+            .map(str::to_string)
+            .collect();
+        (start, matches)
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(&line[..pos]))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+fn execute(service: &mut AppService, command: ShellCommand) -> Result<()> {
+    let today = Utc::now().date_naive();
+    match command {
+        ShellCommand::Log {
+            exercise,
+            prescription,
+        } => {
+            let (sets, reps, weight) = match prescription {
+                Some(prescription) => prescription,
+                None => match handlers::last_logged_values(service, &exercise) {
+                    Some(last) => last,
+                    None => bail!(
+                        "Nothing logged for '{}' yet. Give SETSxREPS[@WEIGHT], e.g. '{} 3x10@60'.",
+                        exercise,
+                        exercise
+                    ),
+                },
+            };
+            handlers::handle_add_workout(
+                service,
+                exercise,
+                today.into(),
+                sets,
+                reps,
+                weight,
+                Vec::new(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                &PromptAnswers::default(),
+                true,
+            )
+        }
+        ShellCommand::Last(exercise) => handlers::handle_list_workouts(
+            service,
+            OutputFormat::Table,
+            Some(1),
+            &ListWindow::default(),
+            false,
+            false,
+            None,
+            None,
+            None,
+            None,
+            Some(exercise),
+            Some(1),
+            None,
+        ),
+        ShellCommand::Pb(exercise) => handlers::handle_stats(
+            service,
+            OutputFormat::Table,
+            vec![exercise],
+            false,
+            None,
+            false,
+        ),
+        ShellCommand::List(when) => {
+            let (limit, date) = if when.is_empty() {
+                (None, Some(today))
+            } else if let Ok(limit) = when.parse::<u32>() {
+                (Some(limit), None)
+            } else {
+                match parse_date_shorthand(&when) {
+                    Ok(date) => (None, Some(date)),
+                    Err(e) => bail!(e),
+                }
+            };
+            handlers::handle_list_workouts(
+                service,
+                OutputFormat::Table,
+                limit,
+                &ListWindow::default(),
+                false,
+                false,
+                date,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        }
+        ShellCommand::Undo(count) => handlers::handle_undo(service, count, true, true),
+        ShellCommand::Help => {
+            println!("{}", HELP);
+            Ok(())
+        }
+        ShellCommand::Exit => Ok(()),
+    }
+}
+
+/// Runs the shell until `exit` or end of input.
+pub fn run(service: &mut AppService) -> Result<()> {
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new()?;
+    let mut helper = ShellHelper {
+        exercises: Vec::new(),
+    };
+    helper.refresh(service);
+    editor.set_helper(Some(helper));
+    let history_path = service
+        .get_config_path()
+        .with_file_name(SHELL_HISTORY_FILE_NAME);
+    // A missing history file just means this is the first session
+    let _ = editor.load_history(&history_path);
+
+    println!("Task Athlete shell. Type 'help' for commands, 'exit' to leave.");
+    loop {
+        let line = match editor.readline("ta> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line.as_str())?;
+
+        let result = parse_line(&line).and_then(|command| {
+            if command == ShellCommand::Exit {
+                return Ok(false);
+            }
+            journal::with_command(format!("shell: {}", line.trim()), || {
+                execute(service, command)
+            })?;
+            Ok(true)
+        });
+        match result {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => eprintln!("Error: {}", e),
+        }
+        if let Some(helper) = editor.helper_mut() {
+            helper.refresh(service);
+        }
+    }
+
+    if let Err(e) = editor.save_history(&history_path) {
+        eprintln!("Could not save shell history to {:?}: {}", history_path, e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("bench press 3x10@60").unwrap(),
+            ShellCommand::Log {
+                exercise: "bench press".to_string(),
+                prescription: Some((Some(3), Some(10), Some(60.0))),
+            }
+        );
+        assert_eq!(
+            parse_line("bench").unwrap(),
+            ShellCommand::Log {
+                exercise: "bench".to_string(),
+                prescription: None,
+            }
+        );
+        assert_eq!(
+            parse_line("last  bench ").unwrap(),
+            ShellCommand::Last("bench".to_string())
+        );
+        assert_eq!(parse_line("undo").unwrap(), ShellCommand::Undo(1));
+        assert_eq!(parse_line("undo 2").unwrap(), ShellCommand::Undo(2));
+        assert!(parse_line("undo many").is_err());
+        assert!(parse_line("pb").is_err());
+        assert_eq!(parse_line("QUIT").unwrap(), ShellCommand::Exit);
+    }
+
+    #[test]
+    fn test_completion_candidates() {
+        let helper = ShellHelper {
+            exercises: vec![
+                "Bench Press".to_string(),
+                "bench".to_string(),
+                "Squat".to_string(),
+            ],
+        };
+        assert_eq!(
+            helper.candidates("be"),
+            (0, vec!["Bench Press".to_string(), "bench".to_string()])
+        );
+        assert_eq!(helper.candidates("last sq"), (5, vec!["Squat".to_string()]));
+        assert_eq!(
+            helper.candidates("list y"),
+            (5, vec!["yesterday".to_string()])
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! CLI-side data that the library database has no columns for.
+//!
+//! Stored as a JSON document next to the config file and keyed by library IDs
+//! (e.g. workout ID). The whole file is read on load and rewritten atomically on save.
+
+use crate::load::LoadBand;
+use crate::muscles::SetRange;
+use crate::session::Session;
+use crate::sets::SetEntry;
+use crate::strength::E1rmFormula;
+use crate::template::Template;
+use crate::trash::{Tombstone, TrashItem, TrashedRecord};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use task_athlete_lib::AppService;
+
+pub const STORE_FILE_NAME: &str = "cli_store.json";
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct CliStore {
+    #[serde(skip)]
+    path: PathBuf,
+
+    #[serde(default)]
+    pub settings: CliSettings,
+
+    /// Individually logged sets, keyed by workout ID.
+    #[serde(default)]
+    pub workout_sets: BTreeMap<i64, Vec<SetEntry>>,
+
+    /// Rests taken after a workout's sets (`ta rest`), in seconds, keyed by workout ID.
+    #[serde(default)]
+    pub workout_rests: BTreeMap<i64, Vec<i64>>,
+
+    /// Training sessions, oldest first. At most one is open at a time.
+    #[serde(default)]
+    pub sessions: Vec<Session>,
+
+    /// Workout templates, in creation order.
+    #[serde(default)]
+    pub templates: Vec<Template>,
+
+    /// Deleted records that can still be restored, oldest first.
+    #[serde(default)]
+    pub trash: Vec<TrashItem>,
+
+    /// Deleted records, so sync doesn't bring them back. Kept after the trash is emptied.
+    #[serde(default)]
+    pub tombstones: Vec<Tombstone>,
+}
+
+/// Preferences for CLI-only features (the library config has no fields for them).
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct CliSettings {
+    /// Formula used for estimated one-rep maxes.
+    #[serde(default)]
+    pub e1rm_formula: E1rmFormula,
+
+    /// Weekly hard-set targets by lowercase muscle name (`ta muscles`); the
+    /// `default` entry replaces the built-in range for all other muscles.
+    #[serde(default)]
+    pub muscle_targets: BTreeMap<String, SetRange>,
+
+    /// Acute:chronic workload ratios outside this band are highlighted (`ta load`).
+    #[serde(default)]
+    pub load_band: LoadBand,
+}
+
+impl CliStore {
+    /// Loads the store that lives next to the service's config file.
+    /// A missing file yields an empty store.
+    pub fn load(service: &AppService) -> Result<Self> {
+        let path = service.get_config_path().with_file_name(STORE_FILE_NAME);
+        let mut store: CliStore = if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read CLI data file {:?}", path))?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse CLI data file {:?}", path))?
+        } else {
+            CliStore::default()
+        };
+        store.path = path;
+        Ok(store)
+    }
+
+    /// Writes the store back to disk via a temporary file and rename.
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(&tmp_path, contents)
+            .with_context(|| format!("Failed to write CLI data file {:?}", tmp_path))?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Failed to replace CLI data file {:?}", self.path))?;
+        Ok(())
+    }
+
+    /// Drops data attached to workouts that no longer exist.
+    pub fn forget_workouts(&mut self, ids: &[i64]) {
+        for id in ids {
+            self.workout_sets.remove(id);
+            self.workout_rests.remove(id);
+        }
+        for session in &mut self.sessions {
+            session.workout_ids.retain(|id| !ids.contains(id));
+            session.pb_workout_ids.retain(|id| !ids.contains(id));
+        }
+    }
+
+    pub fn add_tombstone(&mut self, tombstone: Tombstone) {
+        if !self.tombstones.contains(&tombstone) {
+            self.tombstones.push(tombstone);
+        }
+    }
+
+    /// Returns whether the tombstone was there.
+    pub fn remove_tombstone(&mut self, tombstone: &Tombstone) -> bool {
+        let before = self.tombstones.len();
+        self.tombstones.retain(|t| t != tombstone);
+        self.tombstones.len() < before
+    }
+
+    /// Adds a restored workout back to the session it belonged to, if that still exists.
+    pub fn rejoin_session(&mut self, session_id: i64, workout_id: i64, pb: bool) {
+        if let Some(session) = self.sessions.iter_mut().find(|s| s.id == session_id) {
+            session.workout_ids.push(workout_id);
+            if pb {
+                session.pb_workout_ids.push(workout_id);
+            }
+        }
+    }
+
+    /// The currently open session, if any.
+    pub fn active_session_mut(&mut self) -> Option<&mut Session> {
+        self.sessions.iter_mut().find(|s| s.is_open())
+    }
+
+    pub fn session(&self, id: i64) -> Option<&Session> {
+        self.sessions.iter().find(|s| s.id == id)
+    }
+
+    pub fn next_session_id(&self) -> i64 {
+        self.sessions.iter().map(|s| s.id).max().unwrap_or(0) + 1
+    }
+
+    /// Moves a deleted record to the trash and returns the trash item.
+    pub fn add_to_trash(&mut self, record: TrashedRecord) -> TrashItem {
+        let item = TrashItem {
+            id: self.trash.iter().map(|i| i.id).max().unwrap_or(0) + 1,
+            deleted_at: Utc::now(),
+            record,
+        };
+        self.trash.push(item.clone());
+        item
+    }
+
+    /// Looks up a template by name (case-insensitive).
+    pub fn template_index(&self, name: &str) -> Option<usize> {
+        let name = name.trim();
+        self.templates
+            .iter()
+            .position(|t| t.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn session(id: i64, workout_ids: Vec<i64>, pb_workout_ids: Vec<i64>) -> Session {
+        Session {
+            id,
+            name: None,
+            started_at: Utc.with_ymd_and_hms(2025, 3, 1, 18, 0, 0).unwrap(),
+            ended_at: Some(Utc.with_ymd_and_hms(2025, 3, 1, 19, 0, 0).unwrap()),
+            notes: None,
+            workout_ids,
+            pb_workout_ids,
+        }
+    }
+
+    #[test]
+    fn test_start_and_end_session() {
+        let mut store = CliStore::default();
+        assert!(store.active_session_mut().is_none());
+        assert_eq!(store.next_session_id(), 1);
+
+        store.sessions.push(session(1, vec![4], Vec::new()));
+        let mut open = session(store.next_session_id(), Vec::new(), Vec::new());
+        open.ended_at = None;
+        store.sessions.push(open);
+
+        let active = store.active_session_mut().unwrap();
+        assert_eq!(active.id, 2);
+        active.workout_ids.push(7);
+        active.ended_at = Some(Utc::now());
+        assert!(store.active_session_mut().is_none());
+        assert_eq!(store.session(2).unwrap().workout_ids, vec![7]);
+        assert!(store.session(3).is_none());
+        assert_eq!(store.next_session_id(), 3);
+    }
+
+    #[test]
+    fn test_forget_workouts() {
+        let mut store = CliStore::default();
+        store.sessions.push(session(1, vec![4, 5, 6], vec![4, 6]));
+        store.workout_sets.insert(4, Vec::new());
+        store.workout_rests.insert(4, vec![90]);
+        store.workout_rests.insert(5, vec![120]);
+
+        store.forget_workouts(&[4, 6]);
+        assert!(store.workout_sets.is_empty());
+        assert_eq!(store.workout_rests.keys().collect::<Vec<_>>(), [&5]);
+        assert_eq!(store.sessions[0].workout_ids, vec![5]);
+        assert!(store.sessions[0].pb_workout_ids.is_empty());
+    }
+
+    #[test]
+    fn test_forget_and_rejoin_session() {
+        let mut store = CliStore::default();
+        store.sessions.push(session(1, vec![4, 5], vec![5]));
+        store.forget_workouts(&[5]);
+        assert_eq!(store.sessions[0].workout_ids, vec![4]);
+        assert!(store.sessions[0].pb_workout_ids.is_empty());
+
+        // The workout comes back under a new ID
+        store.rejoin_session(1, 9, true);
+        assert_eq!(store.sessions[0].workout_ids, vec![4, 9]);
+        assert_eq!(store.sessions[0].pb_workout_ids, vec![9]);
+
+        // A session that was deleted meanwhile is left alone
+        store.rejoin_session(2, 10, false);
+        assert_eq!(store.sessions.len(), 1);
+    }
+}
@@ -0,0 +1,194 @@
+//! Estimated one-rep max (e1RM) calculations.
+
+use crate::sets::{self, SetEntry};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use task_athlete_lib::Workout;
+
+/// Highest rep count shown in the rep-max table.
+pub const REP_MAX_TABLE_SIZE: i64 = 12;
+
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum E1rmFormula {
+    /// weight * (1 + reps / 30)
+    #[default]
+    Epley,
+    /// weight * 36 / (37 - reps)
+    Brzycki,
+    /// weight * reps^0.10
+    Lombardi,
+}
+
+impl fmt::Display for E1rmFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            E1rmFormula::Epley => "Epley",
+            E1rmFormula::Brzycki => "Brzycki",
+            E1rmFormula::Lombardi => "Lombardi",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl E1rmFormula {
+    /// Estimated 1RM for a set of `reps` with `weight`. A single rep is its own 1RM.
+    /// Returns `None` for sets the formula can't estimate (no load, no reps, or
+    /// 37+ reps for Brzycki).
+    pub fn estimate(self, weight: f64, reps: i64) -> Option<f64> {
+        if weight <= 0.0 || reps <= 0 {
+            return None;
+        }
+        if reps == 1 {
+            return Some(weight);
+        }
+        let r = reps as f64;
+        match self {
+            E1rmFormula::Epley => Some(weight * (1.0 + r / 30.0)),
+            E1rmFormula::Brzycki if reps < 37 => Some(weight * 36.0 / (37.0 - r)),
+            E1rmFormula::Brzycki => None,
+            E1rmFormula::Lombardi => Some(weight * r.powf(0.10)),
+        }
+    }
+
+    /// Load expected to be liftable for `reps` given a 1RM (the inverse of `estimate`).
+    pub fn load_for_reps(self, one_rm: f64, reps: i64) -> f64 {
+        if reps <= 1 {
+            return one_rm;
+        }
+        let r = reps as f64;
+        match self {
+            E1rmFormula::Epley => one_rm / (1.0 + r / 30.0),
+            E1rmFormula::Brzycki => one_rm * (37.0 - r) / 36.0,
+            E1rmFormula::Lombardi => one_rm / r.powf(0.10),
+        }
+    }
+}
+
+/// A new best e1RM achieved by a freshly logged workout.
+#[derive(Clone, Copy, Debug)]
+pub struct E1rmPb {
+    pub new_value: f64,
+    pub previous_value: Option<f64>,
+    pub formula: E1rmFormula,
+}
+
+/// Best e1RM across a list of sets.
+pub fn best_set_e1rm(sets: &[SetEntry], formula: E1rmFormula) -> Option<f64> {
+    sets.iter()
+        .filter_map(|s| formula.estimate(s.weight?, s.reps))
+        .fold(None, |best: Option<f64>, e| {
+            Some(best.map_or(e, |b| b.max(e)))
+        })
+}
+
+/// Best e1RM of a single workout, using individually logged sets when available.
+pub fn workout_e1rm(
+    workout: &Workout,
+    logged: Option<&Vec<SetEntry>>,
+    formula: E1rmFormula,
+) -> Option<f64> {
+    best_set_e1rm(&sets::sets_for_workout(workout, logged), formula)
+}
+
+/// Best e1RM across workouts.
+pub fn best_e1rm(
+    workouts: &[Workout],
+    logged: &BTreeMap<i64, Vec<SetEntry>>,
+    formula: E1rmFormula,
+) -> Option<f64> {
+    workouts
+        .iter()
+        .filter_map(|w| workout_e1rm(w, logged.get(&w.id), formula))
+        .fold(None, |best: Option<f64>, e| {
+            Some(best.map_or(e, |b| b.max(e)))
+        })
+}
+
+/// Estimated loads for 1..=`REP_MAX_TABLE_SIZE` reps.
+pub fn rep_max_table(one_rm: f64, formula: E1rmFormula) -> Vec<(i64, f64)> {
+    (1..=REP_MAX_TABLE_SIZE)
+        .map(|reps| (reps, formula.load_for_reps(one_rm, reps)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_single_rep_is_own_max() {
+        for formula in [
+            E1rmFormula::Epley,
+            E1rmFormula::Brzycki,
+            E1rmFormula::Lombardi,
+        ] {
+            assert_eq!(formula.estimate(140.0, 1), Some(140.0));
+        }
+    }
+
+    #[test]
+    fn test_formulas() {
+        assert!(approx(
+            E1rmFormula::Epley.estimate(100.0, 10).unwrap(),
+            133.333333333
+        ));
+        assert!(approx(
+            E1rmFormula::Brzycki.estimate(100.0, 10).unwrap(),
+            133.333333333
+        ));
+        assert!(approx(
+            E1rmFormula::Lombardi.estimate(100.0, 10).unwrap(),
+            100.0 * 10f64.powf(0.1)
+        ));
+    }
+
+    #[test]
+    fn test_unestimable_sets() {
+        assert_eq!(E1rmFormula::Epley.estimate(0.0, 5), None);
+        assert_eq!(E1rmFormula::Epley.estimate(100.0, 0), None);
+        assert_eq!(E1rmFormula::Brzycki.estimate(20.0, 40), None);
+    }
+
+    #[test]
+    fn test_load_for_reps_inverts_estimate() {
+        for formula in [
+            E1rmFormula::Epley,
+            E1rmFormula::Brzycki,
+            E1rmFormula::Lombardi,
+        ] {
+            let one_rm = formula.estimate(100.0, 8).unwrap();
+            assert!(approx(formula.load_for_reps(one_rm, 8), 100.0));
+        }
+    }
+
+    #[test]
+    fn test_best_set_e1rm() {
+        let sets = vec![
+            SetEntry {
+                weight: Some(100.0),
+                reps: 8,
+                rpe: None,
+            },
+            SetEntry {
+                weight: Some(120.0),
+                reps: 4,
+                rpe: None,
+            },
+            SetEntry {
+                weight: None,
+                reps: 20,
+                rpe: None,
+            },
+        ];
+        let best = best_set_e1rm(&sets, E1rmFormula::Epley).unwrap();
+        assert!(approx(best, 120.0 * (1.0 + 4.0 / 30.0)));
+        assert_eq!(rep_max_table(best, E1rmFormula::Epley).len(), 12);
+    }
+}
@@ -0,0 +1,152 @@
+//! Workout templates: an ordered list of exercises with target sets/reps/weight that
+//! `ta template run` walks through.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Template {
+    pub name: String,
+    pub items: Vec<TemplateItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TemplateItem {
+    /// Name, ID or alias of the exercise, resolved when the template is run.
+    pub exercise: String,
+    pub sets: Option<i64>,
+    pub reps: Option<i64>,
+    pub weight: Option<f64>,
+}
+
+impl TemplateItem {
+    /// Target in `SETSxREPS@WEIGHT` notation, e.g. `3x10@60`. Empty if no target is set.
+    pub fn prescription(&self) -> String {
+        format_prescription(self.sets, self.reps, self.weight)
+    }
+}
+
+/// Formats sets/reps/weight as `3x10@60`, leaving out missing parts.
+pub fn format_prescription(sets: Option<i64>, reps: Option<i64>, weight: Option<f64>) -> String {
+    let mut s = match (sets, reps) {
+        (Some(sets), Some(reps)) => format!("{sets}x{reps}"),
+        (Some(sets), None) => format!("{sets}x"),
+        (None, Some(reps)) => format!("x{reps}"),
+        (None, None) => String::new(),
+    };
+    if let Some(weight) = weight {
+        s.push_str(&format!("@{weight}"));
+    }
+    s
+}
+
+/// Target sets, reps and weight; any of them may be left open.
+pub type Prescription = (Option<i64>, Option<i64>, Option<f64>);
+
+// Custom parser for prescriptions: SETSxREPS[@WEIGHT], SETSx, xREPS or @WEIGHT
+pub fn parse_prescription(s: &str) -> Result<Prescription, String> {
+    let invalid = || {
+        format!(
+            "Invalid target: '{}'. Use SETSxREPS, optionally followed by @WEIGHT (e.g. 3x10@60).",
+            s
+        )
+    };
+
+    let lower = s.trim().to_lowercase();
+    let (sets_reps, weight) = match lower.split_once('@') {
+        Some((sr, w)) => (sr, Some(w.trim().parse::<f64>().map_err(|_| invalid())?)),
+        None => (lower.as_str(), None),
+    };
+
+    let parse_part = |part: &str| -> Result<Option<i64>, String> {
+        let part = part.trim();
+        if part.is_empty() {
+            Ok(None)
+        } else {
+            match part.parse::<i64>() {
+                Ok(n) if n > 0 => Ok(Some(n)),
+                _ => Err(invalid()),
+            }
+        }
+    };
+
+    let (sets, reps) = match sets_reps.split_once('x') {
+        Some((sets, reps)) => (parse_part(sets)?, parse_part(reps)?),
+        None if sets_reps.trim().is_empty() => (None, None),
+        None => return Err(invalid()),
+    };
+
+    if sets.is_none() && reps.is_none() && weight.is_none() {
+        return Err(invalid());
+    }
+    Ok((sets, reps, weight))
+}
+
+// Custom parser for template items: EXERCISE or EXERCISE:SETSxREPS[@WEIGHT]
+pub fn parse_template_item(s: &str) -> Result<TemplateItem, String> {
+    let (exercise, target) = match s.rsplit_once(':') {
+        Some((exercise, target)) => (exercise, Some(target)),
+        None => (s, None),
+    };
+    let exercise = exercise.trim();
+    if exercise.is_empty() {
+        return Err(format!("Missing exercise in template item '{}'.", s));
+    }
+    let (sets, reps, weight) = match target {
+        Some(target) => parse_prescription(target)?,
+        None => (None, None, None),
+    };
+    Ok(TemplateItem {
+        exercise: exercise.to_string(),
+        sets,
+        reps,
+        weight,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_prescription_full() {
+        assert_eq!(
+            parse_prescription("3x10@60").unwrap(),
+            (Some(3), Some(10), Some(60.0))
+        );
+        assert_eq!(
+            parse_prescription("5X5@102.5").unwrap(),
+            (Some(5), Some(5), Some(102.5))
+        );
+    }
+
+    #[test]
+    fn test_parse_prescription_partial() {
+        assert_eq!(
+            parse_prescription("3x10").unwrap(),
+            (Some(3), Some(10), None)
+        );
+        assert_eq!(parse_prescription("x12").unwrap(), (None, Some(12), None));
+        assert_eq!(parse_prescription("@40").unwrap(), (None, None, Some(40.0)));
+    }
+
+    #[test]
+    fn test_parse_prescription_invalid() {
+        assert!(parse_prescription("").is_err());
+        assert!(parse_prescription("ten").is_err());
+        assert!(parse_prescription("3x0").is_err());
+        assert!(parse_prescription("3x10@heavy").is_err());
+    }
+
+    #[test]
+    fn test_parse_template_item() {
+        let item = parse_template_item("Bench Press:3x10@60").unwrap();
+        assert_eq!(item.exercise, "Bench Press");
+        assert_eq!(item.prescription(), "3x10@60");
+
+        let item = parse_template_item("dips").unwrap();
+        assert_eq!(item.exercise, "dips");
+        assert_eq!(item.prescription(), "");
+
+        assert!(parse_template_item(":3x10").is_err());
+    }
+}
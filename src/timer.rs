@@ -0,0 +1,216 @@
+//! Rest and interval timers (`ta rest`, `ta timer`).
+//!
+//! A timer is a list of phases counted down one second at a time. The terminal bell
+//! rings whenever a phase ends, and Ctrl-C stops the timer early; the time elapsed
+//! up to then still counts.
+
+use anyhow::{bail, Result};
+use chrono::Duration;
+use clap::ValueEnum;
+use std::fmt;
+use std::io::{stdout, IsTerminal, Write};
+use tokio::time::{interval, MissedTickBehavior};
+
+const BELL: char = '\x07';
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimerKind {
+    /// Every minute on the minute: one interval per round, rest is what is left of it
+    Emom,
+    /// As many rounds as possible in one block of time
+    Amrap,
+    /// Alternating work and rest, 8 rounds of 20s/10s by default
+    Tabata,
+}
+
+impl fmt::Display for TimerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TimerKind::Emom => "EMOM",
+            TimerKind::Amrap => "AMRAP",
+            TimerKind::Tabata => "Tabata",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+    pub label: String,
+    pub seconds: i64,
+    /// Whether finishing this phase completes a round.
+    pub work: bool,
+}
+
+impl Phase {
+    pub fn rest(duration: Duration) -> Self {
+        Phase {
+            label: "Rest".to_string(),
+            seconds: duration.num_seconds(),
+            work: false,
+        }
+    }
+}
+
+/// How far a timer got before it finished or was stopped.
+#[derive(Debug, Default, PartialEq)]
+pub struct Outcome {
+    pub elapsed_seconds: i64,
+    pub rounds_completed: u32,
+}
+
+/// Phases of an interval timer. Options that do not apply to `kind` are rejected.
+pub fn plan(
+    kind: TimerKind,
+    rounds: Option<u32>,
+    work: Option<Duration>,
+    rest: Option<Duration>,
+) -> Result<Vec<Phase>> {
+    if rounds == Some(0) {
+        bail!("--rounds must be at least 1.");
+    }
+    if work.is_some_and(|w| w.num_seconds() == 0) {
+        bail!("--work must be longer than 0 seconds.");
+    }
+    let phases = match kind {
+        TimerKind::Emom => {
+            if rest.is_some() {
+                bail!("EMOM intervals have no separate rest; --work sets the length of each interval.");
+            }
+            let rounds = rounds.unwrap_or(10);
+            let seconds = work.map_or(60, |w| w.num_seconds());
+            (1..=rounds)
+                .map(|round| Phase {
+                    label: format!("Interval {}/{}", round, rounds),
+                    seconds,
+                    work: true,
+                })
+                .collect()
+        }
+        TimerKind::Amrap => {
+            if rounds.is_some() || rest.is_some() {
+                bail!("AMRAP is a single block of work; set its length with --work.");
+            }
+            vec![Phase {
+                label: "AMRAP".to_string(),
+                seconds: work.map_or(10 * 60, |w| w.num_seconds()),
+                work: true,
+            }]
+        }
+        TimerKind::Tabata => {
+            let rounds = rounds.unwrap_or(8);
+            let work_seconds = work.map_or(20, |w| w.num_seconds());
+            let rest_seconds = rest.map_or(10, |r| r.num_seconds());
+            let mut phases = Vec::new();
+            for round in 1..=rounds {
+                phases.push(Phase {
+                    label: format!("Round {}/{} work", round, rounds),
+                    seconds: work_seconds,
+                    work: true,
+                });
+                if round < rounds && rest_seconds > 0 {
+                    phases.push(Phase {
+                        label: format!("Round {}/{} rest", round, rounds),
+                        seconds: rest_seconds,
+                        work: false,
+                    });
+                }
+            }
+            phases
+        }
+    };
+    Ok(phases)
+}
+
+/// Formats seconds as `1:30` or `1:02:05`.
+pub fn format_clock(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Counts down `phases` in the terminal until they are done or Ctrl-C is pressed.
+pub async fn run(phases: &[Phase]) -> Outcome {
+    let terminal = stdout().is_terminal();
+    let stop = tokio::signal::ctrl_c();
+    tokio::pin!(stop);
+    let mut ticker = interval(std::time::Duration::from_secs(1));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    ticker.tick().await; // The first tick completes immediately
+
+    let mut outcome = Outcome::default();
+    for phase in phases {
+        if !terminal {
+            println!("{} ({})", phase.label, format_clock(phase.seconds));
+        }
+        for remaining in (1..=phase.seconds).rev() {
+            if terminal {
+                print!("\r{}  {}   ", phase.label, format_clock(remaining));
+                let _ = stdout().flush();
+            }
+            tokio::select! {
+                _ = ticker.tick() => outcome.elapsed_seconds += 1,
+                _ = &mut stop => {
+                    println!("\nStopped after {}.", format_clock(outcome.elapsed_seconds));
+                    return outcome;
+                }
+            }
+        }
+        if phase.work {
+            outcome.rounds_completed += 1;
+        }
+        if terminal {
+            print!("\r{}  {}   {}", phase.label, format_clock(0), BELL);
+            println!();
+        }
+    }
+    if terminal {
+        // A longer signal for the end of the whole timer
+        print!("{BELL}{BELL}");
+    }
+    println!("Done after {}.", format_clock(outcome.elapsed_seconds));
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan() {
+        let tabata = plan(TimerKind::Tabata, None, None, None).unwrap();
+        assert_eq!(tabata.len(), 15); // 8 work phases, no rest after the last one
+        assert_eq!(
+            tabata.iter().map(|p| p.seconds).sum::<i64>(),
+            8 * 20 + 7 * 10
+        );
+        assert_eq!(tabata[1].label, "Round 1/8 rest");
+
+        let emom = plan(TimerKind::Emom, Some(12), Some(Duration::seconds(40)), None).unwrap();
+        assert_eq!(emom.len(), 12);
+        assert!(emom.iter().all(|p| p.work && p.seconds == 40));
+
+        assert_eq!(
+            plan(TimerKind::Amrap, None, None, None).unwrap()[0].seconds,
+            600
+        );
+        assert!(plan(TimerKind::Amrap, Some(3), None, None).is_err());
+        assert!(plan(TimerKind::Emom, None, None, Some(Duration::seconds(20))).is_err());
+        assert!(plan(TimerKind::Tabata, Some(0), None, None).is_err());
+    }
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock(5), "0:05");
+        assert_eq!(format_clock(90), "1:30");
+        assert_eq!(format_clock(3725), "1:02:05");
+    }
+}
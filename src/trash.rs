@@ -0,0 +1,413 @@
+//! Trash bin for deleted workouts, exercises and bodyweight entries (`ta trash`).
+//!
+//! Deleting removes the record from the library database, so listings, stats and
+//! volume never see it, and keeps a snapshot in the CLI store. Restoring adds the
+//! record back through the library; it gets a new ID.
+//!
+//! Deleting also leaves a tombstone, which outlives the trash. The sync protocol has
+//! no deletions, so a pull can return the record; `ta sync` deletes it again
+//! afterwards so it stays deleted on this device. Restoring the record, undoing the
+//! deletion or creating a matching record again drops the tombstone. Deletions are
+//! not sent to the server, which keeps its copy.
+
+use crate::journal::{self, BodyweightSnapshot, Change, ExerciseSnapshot, WorkoutSnapshot};
+use crate::store::CliStore;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use task_athlete_lib::{AppService, Workout, WorkoutFilters};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TrashItem {
+    pub id: i64,
+    pub deleted_at: DateTime<Utc>,
+    pub record: TrashedRecord,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TrashedRecord {
+    Workout(WorkoutSnapshot),
+    Exercise(ExerciseSnapshot),
+    Bodyweight(BodyweightSnapshot),
+}
+
+/// Identifies a deleted record across syncs, where library IDs can change.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Tombstone {
+    Workout {
+        exercise_name: String,
+        timestamp: DateTime<Utc>,
+    },
+    Exercise {
+        name: String,
+    },
+    Bodyweight {
+        timestamp: DateTime<Utc>,
+        weight: f64,
+    },
+}
+
+impl Tombstone {
+    fn workout(workout: &WorkoutSnapshot) -> Self {
+        Tombstone::Workout {
+            exercise_name: workout.exercise_name.clone(),
+            timestamp: workout.timestamp,
+        }
+    }
+
+    fn exercise(exercise: &ExerciseSnapshot) -> Self {
+        Tombstone::Exercise {
+            name: exercise.name.clone(),
+        }
+    }
+
+    fn bodyweight(entry: &BodyweightSnapshot) -> Self {
+        Tombstone::Bodyweight {
+            timestamp: entry.timestamp,
+            weight: entry.weight,
+        }
+    }
+
+    /// Tombstone a change could match by creating a record, or by editing one into it.
+    fn created_by(change: &Change) -> Option<Self> {
+        fn new_identity<T>(
+            before: &Option<T>,
+            after: &Option<T>,
+            tombstone: fn(&T) -> Tombstone,
+        ) -> Option<Tombstone> {
+            let after = tombstone(after.as_ref()?);
+            (before.as_ref().map(tombstone) != Some(after.clone())).then_some(after)
+        }
+        match change {
+            Change::Workout { before, after } => new_identity(before, after, Tombstone::workout),
+            Change::Exercise { before, after } => new_identity(before, after, Tombstone::exercise),
+            Change::Bodyweight { before, after } => {
+                new_identity(before, after, Tombstone::bodyweight)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl TrashedRecord {
+    pub fn tombstone(&self) -> Tombstone {
+        match self {
+            TrashedRecord::Workout(workout) => Tombstone::workout(workout),
+            TrashedRecord::Exercise(exercise) => Tombstone::exercise(exercise),
+            TrashedRecord::Bodyweight(entry) => Tombstone::bodyweight(entry),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TrashedRecord::Workout(_) => "Workout",
+            TrashedRecord::Exercise(_) => "Exercise",
+            TrashedRecord::Bodyweight(_) => "Bodyweight",
+        }
+    }
+
+    /// Short description, e.g. `workout #12 Bench Press on 2025-03-01`.
+    pub fn describe(&self) -> String {
+        match self {
+            TrashedRecord::Workout(workout) => format!("workout {}", workout.label()),
+            TrashedRecord::Exercise(exercise) if exercise.aliases.is_empty() => {
+                format!("exercise '{}'", exercise.name)
+            }
+            TrashedRecord::Exercise(exercise) => format!(
+                "exercise '{}' (aliases: {})",
+                exercise.name,
+                exercise.aliases.join(", ")
+            ),
+            TrashedRecord::Bodyweight(entry) => format!(
+                "bodyweight entry #{} ({} on {})",
+                entry.id,
+                entry.weight,
+                entry.timestamp.format("%Y-%m-%d")
+            ),
+        }
+    }
+}
+
+/// Moves a record that was just deleted to the trash. Returns the changes made, for
+/// the undo journal.
+pub fn discard(store: &mut CliStore, record: TrashedRecord) -> Vec<Change> {
+    let deleted = match &record {
+        TrashedRecord::Workout(workout) => Change::Workout {
+            before: Some(workout.clone()),
+            after: None,
+        },
+        TrashedRecord::Exercise(exercise) => Change::Exercise {
+            before: Some(exercise.clone()),
+            after: None,
+        },
+        TrashedRecord::Bodyweight(entry) => Change::Bodyweight {
+            before: Some(entry.clone()),
+            after: None,
+        },
+    };
+    store.add_tombstone(record.tombstone());
+    let item = store.add_to_trash(record);
+    vec![
+        deleted,
+        Change::Trash {
+            before: None,
+            after: Some(item),
+        },
+    ]
+}
+
+/// Adds a trashed record back and takes it out of the trash. Returns the changes
+/// made, for the undo journal; the last one describes the restored record.
+pub fn restore(service: &mut AppService, store: &mut CliStore, id: i64) -> Result<Vec<Change>> {
+    let index = store
+        .trash
+        .iter()
+        .position(|item| item.id == id)
+        .with_context(|| format!("Trash item {} not found.", id))?;
+    let item = store.trash[index].clone();
+
+    let restored = match &item.record {
+        TrashedRecord::Workout(workout) => {
+            let new_id = journal::restore_workout(service, store, workout)?;
+            Change::workout_added(WorkoutSnapshot {
+                id: new_id,
+                ..workout.clone()
+            })
+        }
+        TrashedRecord::Exercise(exercise) => {
+            journal::restore_exercise(service, exercise)?;
+            Change::Exercise {
+                before: None,
+                after: Some(exercise.clone()),
+            }
+        }
+        TrashedRecord::Bodyweight(entry) => {
+            let new_id = service
+                .add_bodyweight_entry(entry.timestamp, entry.weight)
+                .with_context(|| format!("Could not restore bodyweight entry #{}", entry.id))?;
+            Change::Bodyweight {
+                before: None,
+                after: Some(BodyweightSnapshot {
+                    id: new_id,
+                    ..entry.clone()
+                }),
+            }
+        }
+    };
+    store.trash.remove(index);
+    let changes = vec![
+        Change::Trash {
+            before: Some(item),
+            after: None,
+        },
+        restored,
+    ];
+    revive(store, &changes);
+    Ok(changes)
+}
+
+/// Removes items deleted before `cutoff` (all items without one) and returns them.
+/// Their tombstones stay.
+pub fn empty(store: &mut CliStore, cutoff: Option<DateTime<Utc>>) -> Vec<TrashItem> {
+    let (removed, kept) = store
+        .trash
+        .drain(..)
+        .partition(|item| cutoff.is_none_or(|cutoff| item.deleted_at < cutoff));
+    store.trash = kept;
+    removed
+}
+
+/// Drops the tombstones of records that `changes` create again, so sync keeps the new
+/// records. Returns whether any was dropped.
+pub fn revive(store: &mut CliStore, changes: &[Change]) -> bool {
+    changes
+        .iter()
+        .filter_map(Tombstone::created_by)
+        .map(|tombstone| store.remove_tombstone(&tombstone))
+        .fold(false, |revived, removed| revived | removed)
+}
+
+/// Records `changes` in the undo journal, after dropping the tombstones of the records
+/// they create again.
+pub fn record_and_revive(service: &AppService, changes: Vec<Change>) -> Result<()> {
+    let mut store = CliStore::load(service)?;
+    if revive(&mut store, &changes) {
+        store.save()?;
+    }
+    journal::record(service, changes)
+}
+
+/// IDs of the workouts that match a tombstone.
+fn buried_workouts(tombstones: &[Tombstone], workouts: &[Workout]) -> Vec<i64> {
+    workouts
+        .iter()
+        .filter(|w| {
+            tombstones.contains(&Tombstone::Workout {
+                exercise_name: w.exercise_name.clone(),
+                timestamp: w.timestamp,
+            })
+        })
+        .map(|w| w.id)
+        .collect()
+}
+
+/// Deletes records that match a tombstone, e.g. after a sync pulled them back.
+/// Returns how many were deleted.
+pub fn delete_resurrected(service: &AppService, store: &CliStore) -> Result<usize> {
+    let tombstones = &store.tombstones;
+    if tombstones.is_empty() {
+        return Ok(0);
+    }
+    let mut deleted = 0;
+
+    let workouts = service.list_workouts(&WorkoutFilters {
+        exercise_name: None,
+        date: None,
+        exercise_type: None,
+        muscle: None,
+        limit: None,
+    })?;
+    let workout_ids = buried_workouts(tombstones, &workouts);
+    if !workout_ids.is_empty() {
+        deleted += service.delete_workouts(&workout_ids)?.len();
+    }
+
+    let exercise_names: Vec<String> = service
+        .list_exercises(None, None)?
+        .into_iter()
+        .map(|e| e.name)
+        .filter(|name| tombstones.contains(&Tombstone::Exercise { name: name.clone() }))
+        .collect();
+    if !exercise_names.is_empty() {
+        deleted += service.delete_exercise(&exercise_names)? as usize;
+    }
+
+    for (id, timestamp, weight) in service.list_bodyweights(u32::MAX)? {
+        if tombstones.contains(&Tombstone::Bodyweight { timestamp, weight }) {
+            service.delete_bodyweight(id)?;
+            deleted += 1;
+        }
+    }
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn bodyweight_item(id: i64, deleted_at: DateTime<Utc>) -> TrashItem {
+        TrashItem {
+            id,
+            deleted_at,
+            record: TrashedRecord::Bodyweight(BodyweightSnapshot {
+                id: 40 + id,
+                timestamp: Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap(),
+                weight: 80.5,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_empty_older_than() {
+        let now = Utc::now();
+        let mut store = CliStore::default();
+        store.trash = vec![
+            bodyweight_item(1, now - Duration::days(40)),
+            bodyweight_item(2, now - Duration::days(2)),
+        ];
+
+        let removed = empty(&mut store, Some(now - Duration::days(30)));
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].id, 1);
+        assert_eq!(store.trash.len(), 1);
+
+        assert_eq!(empty(&mut store, None).len(), 1);
+        assert!(store.trash.is_empty());
+    }
+
+    #[test]
+    fn test_tombstones_outlive_the_trash() {
+        let mut store = CliStore::default();
+        let record = bodyweight_item(1, Utc::now()).record;
+        discard(&mut store, record.clone());
+        assert_eq!(store.tombstones, vec![record.tombstone()]);
+
+        empty(&mut store, None);
+        assert!(store.trash.is_empty());
+        assert_eq!(store.tombstones, vec![record.tombstone()]);
+    }
+
+    #[test]
+    fn test_recreated_records_survive_sync() {
+        let snapshot = WorkoutSnapshot {
+            id: 4,
+            timestamp: Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap(),
+            exercise_name: "Bench Press".to_string(),
+            exercise_type: Some("Resistance".to_string()),
+            sets: Some(3),
+            reps: Some(8),
+            weight: Some(60.0),
+            duration_minutes: None,
+            distance_km: None,
+            bodyweight: None,
+            notes: None,
+            logged_sets: Vec::new(),
+            rests: Vec::new(),
+            session_id: None,
+            session_pb: false,
+        };
+        let pulled = |id: i64| Workout {
+            id,
+            timestamp: snapshot.timestamp,
+            exercise_name: snapshot.exercise_name.clone(),
+            sets: Some(3),
+            reps: Some(8),
+            weight: Some(60.0),
+            duration_minutes: None,
+            distance: None,
+            notes: None,
+            exercise_type: None,
+            bodyweight: None,
+        };
+
+        // Deleted, so a sync that returns it deletes it again
+        let mut store = CliStore::default();
+        discard(&mut store, TrashedRecord::Workout(snapshot.clone()));
+        assert_eq!(buried_workouts(&store.tombstones, &[pulled(4)]), vec![4]);
+
+        // Logged again for the same time: the new workout is kept, and the old one
+        // can still be restored from the trash
+        let created = Change::workout_added(WorkoutSnapshot {
+            id: 9,
+            ..snapshot.clone()
+        });
+        assert!(revive(&mut store, &[created]));
+        assert!(buried_workouts(&store.tombstones, &[pulled(9)]).is_empty());
+        assert_eq!(store.trash.len(), 1);
+
+        // An edit that doesn't move a workout onto a tombstone leaves it alone
+        discard(&mut store, TrashedRecord::Workout(snapshot.clone()));
+        let edited = Change::Workout {
+            before: Some(snapshot.clone()),
+            after: Some(WorkoutSnapshot {
+                reps: Some(10),
+                ..snapshot.clone()
+            }),
+        };
+        assert!(!revive(&mut store, &[edited]));
+        assert_eq!(store.tombstones.len(), 1);
+    }
+
+    #[test]
+    fn test_describe() {
+        let item = bodyweight_item(1, Utc::now());
+        assert_eq!(item.record.kind(), "Bodyweight");
+        assert_eq!(
+            item.record.describe(),
+            "bodyweight entry #41 (80.5 on 2025-03-01)"
+        );
+    }
+}
@@ -0,0 +1,630 @@
+//! Full-screen dashboard (`ta tui`, built with the `tui` cargo feature).
+//!
+//! Panes show today's workouts, recent personal bests, the bodyweight trend against
+//! the target and the training streaks; a form at the bottom logs a workout through
+//! the library. Tab moves between panes, `a` opens the form, `r` reloads, `q` quits.
+
+use crate::calendar::{self, Streak};
+use crate::handlers;
+use crate::output;
+use crate::pbs::{self, PbEvent};
+use crate::store::CliStore;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Axis, Block, Chart, Dataset, GraphType, List, ListItem, ListState, Paragraph, Row, Table,
+    TableState, Wrap,
+};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::BTreeSet;
+use task_athlete_lib::{
+    AddWorkoutParams, AppService, ExerciseType, Units, Workout, WorkoutFilters, KM_TO_MILE,
+};
+
+const RECENT_PBS_SHOWN: usize = 20;
+const BODYWEIGHTS_CHARTED: u32 = 60;
+
+const METRIC_NAMES: [&str; 4] = ["Weight", "Reps", "Duration", "Distance"];
+
+/// Everything the panes show, reloaded after each change.
+struct Dashboard {
+    today: Vec<Workout>,
+    pbs: Vec<PbEvent>,
+    /// Oldest first.
+    bodyweights: Vec<(DateTime<Utc>, f64)>,
+    target: Option<f64>,
+    streaks: (Option<Streak>, Option<Streak>),
+    interval_days: u32,
+    units: Units,
+}
+
+impl Dashboard {
+    fn load(service: &AppService) -> Result<Self> {
+        let mut workouts = service.list_workouts(&WorkoutFilters {
+            exercise_name: None,
+            date: None,
+            exercise_type: None,
+            muscle: None,
+            limit: None,
+        })?;
+        workouts.sort_by_key(|w| w.timestamp);
+        let today = Local::now().date_naive();
+        let local_date = |w: &Workout| w.timestamp.with_timezone(&Local).date_naive();
+
+        let mut pbs = pbs::pb_events(&pbs::history(&workouts));
+        pbs.truncate(RECENT_PBS_SHOWN);
+
+        let active_days: BTreeSet<NaiveDate> = workouts.iter().map(local_date).collect();
+        let interval_days = service.config.streak_interval_days;
+        let streaks = calendar::find_streaks(&active_days, interval_days, today);
+
+        let mut bodyweights: Vec<(DateTime<Utc>, f64)> = service
+            .list_bodyweights(BODYWEIGHTS_CHARTED)?
+            .into_iter()
+            .map(|(_, timestamp, weight)| (timestamp, weight))
+            .collect();
+        bodyweights.sort_by_key(|(timestamp, _)| *timestamp);
+
+        Ok(Dashboard {
+            today: workouts
+                .into_iter()
+                .filter(|w| local_date(w) == today)
+                .collect(),
+            pbs,
+            bodyweights,
+            target: service.config.target_bodyweight,
+            streaks,
+            interval_days,
+            units: service.config.units,
+        })
+    }
+
+    fn format_metric(&self, metric: usize, value: f64) -> String {
+        match metric {
+            0 => format!("{:.1} {}", value, self.units.weight_abbr()),
+            1 => format!("{} reps", value),
+            2 => format!("{} min", value),
+            _ => format!(
+                "{:.2} {}",
+                self.display_distance(value),
+                self.units.distance_abbr()
+            ),
+        }
+    }
+
+    fn display_distance(&self, km: f64) -> f64 {
+        match self.units {
+            Units::Metric => km,
+            Units::Imperial => km * KM_TO_MILE,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Today,
+    Pbs,
+    Form,
+}
+
+impl Focus {
+    fn next(self) -> Self {
+        match self {
+            Focus::Today => Focus::Pbs,
+            Focus::Pbs => Focus::Form,
+            Focus::Form => Focus::Today,
+        }
+    }
+}
+
+const FORM_FIELDS: [&str; 7] = [
+    "Exercise", "Sets", "Reps", "Weight", "Duration", "Distance", "Notes",
+];
+
+/// The quick-add form: one text value per entry of [`FORM_FIELDS`].
+#[derive(Default)]
+struct Form {
+    values: [String; 7],
+    selected: usize,
+}
+
+impl Form {
+    fn parse<T: std::str::FromStr>(&self, index: usize) -> Result<Option<T>> {
+        let value = self.values[index].trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        match value.parse() {
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(_) => bail!("{} must be a number, not '{}'.", FORM_FIELDS[index], value),
+        }
+    }
+
+    /// Logs the workout described by the form and returns a status message.
+    fn submit(&self, service: &mut AppService) -> Result<String> {
+        let exercise = self.values[0].trim();
+        if exercise.is_empty() {
+            bail!("Enter an exercise.");
+        }
+        let Some(definition) = service.get_exercise_by_identifier_service(exercise)? else {
+            bail!(
+                "Exercise '{}' not found. Create it with 'ta create-exercise'.",
+                exercise
+            );
+        };
+        let notes = self.values[6].trim();
+        // The form cannot prompt, so bodyweight exercises use the latest logged weight
+        let bodyweight_to_use = match definition.type_ {
+            ExerciseType::BodyWeight => Some(service.get_latest_bodyweight()?.unwrap_or(0.0)),
+            _ => None,
+        };
+
+        let performed = handlers::performed_sets(self.parse(2)?, self.parse(3)?, &[]);
+        let mut store = CliStore::load(service)?;
+        let e1rm_pb = handlers::check_e1rm_pb(
+            service,
+            &store,
+            &definition.name,
+            true,
+            definition.type_ == ExerciseType::Resistance,
+            &performed,
+        )?;
+
+        let (id, pb_info) = service.add_workout(AddWorkoutParams {
+            exercise_identifier: &definition.name,
+            date: Utc::now(),
+            sets: self.parse(1)?,
+            reps: self.parse(2)?,
+            weight: self.parse(3)?,
+            duration: self.parse(4)?,
+            // In the configured unit, like `ta add --distance`
+            distance: self.parse(5)?,
+            notes: (!notes.is_empty()).then(|| notes.to_string()),
+            bodyweight_to_use,
+            implicit_type: None,
+            implicit_muscles: None,
+        })?;
+
+        let pb = pb_info.as_ref().is_some_and(handlers::any_pb_achieved) || e1rm_pb.is_some();
+        let session_label =
+            handlers::record_added_workout(service, &mut store, id, pb, Vec::new(), None)?;
+
+        let mut message = format!("Logged {} (ID {}).", definition.name, id);
+        if let Some(label) = session_label {
+            message = format!("{} Attached to session {}.", message, label);
+        }
+        if !handlers::relevant_pb_achieved(&service.config, pb_info.as_ref(), e1rm_pb.as_ref()) {
+            return Ok(message);
+        }
+        // The form can't ask, so unconfigured notifications show the PB with a hint
+        let enabled = handlers::pb_notifications_enabled(service, None, false)?;
+        if enabled != Some(false) {
+            let pbs = output::pb_messages(
+                pb_info.as_ref(),
+                e1rm_pb.as_ref(),
+                service.config.units,
+                &service.config,
+            );
+            message = format!("{} Personal best! {}", message, pbs.join("; "));
+        }
+        if enabled.is_none() {
+            message = format!(
+                "{} Run 'ta set-pb-notification' to choose whether to see PBs.",
+                message
+            );
+        }
+        Ok(message)
+    }
+
+    /// Clears everything but the exercise, ready for the next set.
+    fn reset(&mut self) {
+        for value in self.values.iter_mut().skip(1) {
+            value.clear();
+        }
+        self.selected = 1;
+    }
+}
+
+struct App {
+    data: Dashboard,
+    focus: Focus,
+    today_state: TableState,
+    pb_state: ListState,
+    form: Form,
+    /// Last message and whether it is an error.
+    status: Option<(String, bool)>,
+    quit: bool,
+}
+
+impl App {
+    fn reload(&mut self, service: &AppService) {
+        match Dashboard::load(service) {
+            Ok(data) => self.data = data,
+            Err(e) => self.status = Some((format!("Could not reload: {}", e), true)),
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, service: &mut AppService) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        if self.focus == Focus::Form {
+            let selected = self.form.selected;
+            match key.code {
+                KeyCode::Esc => self.focus = Focus::Today,
+                KeyCode::Tab | KeyCode::Down => {
+                    self.form.selected = (selected + 1) % FORM_FIELDS.len()
+                }
+                KeyCode::BackTab | KeyCode::Up => {
+                    self.form.selected = (selected + FORM_FIELDS.len() - 1) % FORM_FIELDS.len()
+                }
+                KeyCode::Backspace => {
+                    self.form.values[selected].pop();
+                }
+                KeyCode::Char(c) => self.form.values[selected].push(c),
+                KeyCode::Enter => match self.form.submit(service) {
+                    Ok(message) => {
+                        self.status = Some((message, false));
+                        self.form.reset();
+                        self.reload(service);
+                    }
+                    Err(e) => self.status = Some((e.to_string(), true)),
+                },
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::Char('a') => self.focus = Focus::Form,
+            KeyCode::Char('r') => {
+                self.reload(service);
+                self.status = Some(("Reloaded.".to_string(), false));
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.scroll(true),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll(false),
+            _ => {}
+        }
+    }
+
+    fn scroll(&mut self, down: bool) {
+        match self.focus {
+            Focus::Today if down => self.today_state.select_next(),
+            Focus::Today => self.today_state.select_previous(),
+            Focus::Pbs if down => self.pb_state.select_next(),
+            Focus::Pbs => self.pb_state.select_previous(),
+            Focus::Form => {}
+        }
+    }
+
+    fn block(&self, title: &str, pane: Focus) -> Block<'static> {
+        let style = if self.focus == pane {
+            Style::new().fg(Color::Yellow)
+        } else {
+            Style::new()
+        };
+        Block::bordered()
+            .title(title.to_string())
+            .border_style(style)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, form_area, status_area] = Layout::vertical([
+            Constraint::Min(10),
+            Constraint::Length(4),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [top, bottom] =
+            Layout::vertical([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(main);
+        let [today_area, pb_area] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(top);
+        let [bodyweight_area, streak_area] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(bottom);
+
+        self.draw_today(frame, today_area);
+        self.draw_pbs(frame, pb_area);
+        self.draw_bodyweight(frame, bodyweight_area);
+        self.draw_streaks(frame, streak_area);
+        self.draw_form(frame, form_area);
+
+        let status = match &self.status {
+            Some((message, true)) => Line::styled(message.clone(), Style::new().fg(Color::Red)),
+            Some((message, false)) => Line::styled(message.clone(), Style::new().fg(Color::Green)),
+            None => Line::from("Tab: next pane  a: add workout  r: reload  j/k: scroll  q: quit"),
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn draw_today(&mut self, frame: &mut Frame, area: Rect) {
+        let data = &self.data;
+        let cell = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        let rows: Vec<Row> = data
+            .today
+            .iter()
+            .map(|w| {
+                Row::new(vec![
+                    w.exercise_name.clone(),
+                    cell(w.sets.map(|s| s.to_string())),
+                    cell(w.reps.map(|r| r.to_string())),
+                    cell(w.calculate_effective_weight().map(|v| format!("{v:.1}"))),
+                    cell(w.duration_minutes.map(|d| d.to_string())),
+                    cell(
+                        w.distance
+                            .map(|km| format!("{:.2}", data.display_distance(km))),
+                    ),
+                ])
+            })
+            .collect();
+        let header = Row::new(vec![
+            "Exercise".to_string(),
+            "Sets".to_string(),
+            "Reps".to_string(),
+            format!("Weight ({})", data.units.weight_abbr()),
+            "Min".to_string(),
+            format!("Dist ({})", data.units.distance_abbr()),
+        ])
+        .style(Style::new().add_modifier(Modifier::BOLD));
+        let title = format!("Today ({} workouts)", data.today.len());
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(3),
+                Constraint::Length(4),
+                Constraint::Length(4),
+                Constraint::Length(11),
+                Constraint::Length(4),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header)
+        .block(self.block(&title, Focus::Today))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.today_state);
+    }
+
+    fn draw_pbs(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .data
+            .pbs
+            .iter()
+            .map(|pb| {
+                ListItem::new(format!(
+                    "{} {}: {} {}",
+                    pb.date.format("%m-%d"),
+                    pb.exercise,
+                    METRIC_NAMES[pb.metric],
+                    self.data.format_metric(pb.metric, pb.value)
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.block("Recent PBs", Focus::Pbs))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.pb_state);
+    }
+
+    fn draw_bodyweight(&self, frame: &mut Frame, area: Rect) {
+        let data = &self.data;
+        let unit = data.units.weight_abbr();
+        let (Some(first), Some(last)) = (data.bodyweights.first(), data.bodyweights.last()) else {
+            let empty = Paragraph::new("No bodyweight logged yet. Use 'ta log-bodyweight'.")
+                .block(Block::bordered().title("Bodyweight"));
+            frame.render_widget(empty, area);
+            return;
+        };
+
+        let day = |timestamp: DateTime<Utc>| (timestamp - first.0).num_hours() as f64 / 24.0;
+        let points: Vec<(f64, f64)> = data
+            .bodyweights
+            .iter()
+            .map(|(timestamp, weight)| (day(*timestamp), *weight))
+            .collect();
+        let span = day(last.0).max(1.0);
+        let target_points: Vec<(f64, f64)> = data
+            .target
+            .map(|target| vec![(0.0, target), (span, target)])
+            .unwrap_or_default();
+
+        let weights = points.iter().map(|(_, w)| *w).chain(data.target);
+        let min = weights.clone().fold(f64::INFINITY, f64::min).floor() - 1.0;
+        let max = weights.fold(f64::NEG_INFINITY, f64::max).ceil() + 1.0;
+
+        let mut datasets = vec![Dataset::default()
+            .name("Bodyweight")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().fg(Color::Cyan))
+            .data(&points)];
+        if !target_points.is_empty() {
+            datasets.push(
+                Dataset::default()
+                    .name("Target")
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::new().fg(Color::Green))
+                    .data(&target_points),
+            );
+        }
+
+        let title = match data.target {
+            Some(target) => format!(
+                "Bodyweight: {:.1} {} (target {:.1}, {:+.1} to go)",
+                last.1,
+                unit,
+                target,
+                target - last.1
+            ),
+            None => format!("Bodyweight: {:.1} {} (no target set)", last.1, unit),
+        };
+        let chart = Chart::new(datasets)
+            .block(Block::bordered().title(title))
+            .x_axis(Axis::default().bounds([0.0, span]).labels([
+                first.0.with_timezone(&Local).format("%m-%d").to_string(),
+                last.0.with_timezone(&Local).format("%m-%d").to_string(),
+            ]))
+            .y_axis(
+                Axis::default()
+                    .bounds([min, max])
+                    .labels([format!("{min:.0}"), format!("{max:.0}")]),
+            );
+        frame.render_widget(chart, area);
+    }
+
+    fn draw_streaks(&self, frame: &mut Frame, area: Rect) {
+        let data = &self.data;
+        let describe = |streak: Option<Streak>| match streak {
+            Some(s) => format!(
+                "{} training days ({} to {})",
+                s.days,
+                s.start.format("%Y-%m-%d"),
+                s.end.format("%Y-%m-%d")
+            ),
+            None => "none".to_string(),
+        };
+        let (current, longest) = data.streaks;
+        let lines = vec![
+            Line::from(vec![
+                Span::styled("Current: ", Style::new().add_modifier(Modifier::BOLD)),
+                Span::raw(describe(current)),
+            ]),
+            Line::from(vec![
+                Span::styled("Longest: ", Style::new().add_modifier(Modifier::BOLD)),
+                Span::raw(describe(longest)),
+            ]),
+            Line::from(format!(
+                "A streak allows gaps of up to {} day(s) ('ta set-streak-interval').",
+                data.interval_days
+            )),
+        ];
+        let streaks = Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title("Streaks"));
+        frame.render_widget(streaks, area);
+    }
+
+    fn draw_form(&self, frame: &mut Frame, area: Rect) {
+        let editing = self.focus == Focus::Form;
+        let mut spans = Vec::new();
+        for (index, label) in FORM_FIELDS.iter().enumerate() {
+            let selected = editing && index == self.form.selected;
+            let value = &self.form.values[index];
+            let shown = if selected {
+                format!("[{}_]", value)
+            } else {
+                format!("[{}]", value)
+            };
+            let style = if selected {
+                Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::new()
+            };
+            spans.push(Span::raw(format!("{}: ", label)));
+            spans.push(Span::styled(shown, style));
+            spans.push(Span::raw("  "));
+        }
+        let hint = if editing {
+            "Tab/Shift-Tab: field  Enter: log  Esc: back"
+        } else {
+            "Press 'a' to log a workout"
+        };
+        let form = Paragraph::new(vec![Line::from(spans), Line::from(hint)])
+            .wrap(Wrap { trim: false })
+            .block(self.block("Quick add", Focus::Form));
+        frame.render_widget(form, area);
+    }
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    service: &mut AppService,
+) -> Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            app.handle_key(key, service);
+        }
+    }
+    Ok(())
+}
+
+/// Runs the dashboard until the user quits.
+pub fn run(service: &mut AppService) -> Result<()> {
+    let mut app = App {
+        data: Dashboard::load(service)?,
+        focus: Focus::Today,
+        today_state: TableState::default(),
+        pb_state: ListState::default(),
+        form: Form::default(),
+        status: None,
+        quit: false,
+    };
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, service);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_draw_dashboard() {
+        let mut app = App {
+            data: Dashboard {
+                today: Vec::new(),
+                pbs: vec![PbEvent {
+                    date: date(6),
+                    exercise: "Bench".to_string(),
+                    metric: 0,
+                    value: 65.0,
+                }],
+                bodyweights: vec![
+                    (Utc::now() - chrono::Duration::days(10), 82.0),
+                    (Utc::now(), 80.5),
+                ],
+                target: Some(78.0),
+                streaks: (None, None),
+                interval_days: 1,
+                units: Units::Metric,
+            },
+            focus: Focus::Form,
+            today_state: TableState::default(),
+            pb_state: ListState::default(),
+            form: Form::default(),
+            status: None,
+            quit: false,
+        };
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Today (0 workouts)"));
+        assert!(screen.contains("Bench: Weight 65.0 kg"));
+        assert!(screen.contains("target 78.0"));
+    }
+}